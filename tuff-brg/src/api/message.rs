@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use transformer_neo::models::Entity;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "PascalCase")]
@@ -33,6 +34,16 @@ pub enum Message {
         ts: String,
         payload: ApproveFactPayload,
     },
+    UpsertEntity {
+        id: String,
+        ts: String,
+        payload: Entity,
+    },
+    RemoveEntity {
+        id: String,
+        ts: String,
+        payload: RemoveEntityPayload,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -65,6 +76,7 @@ pub struct JudgeResultPayload {
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum VerificationStatus {
     Smoke,
+    Outdated,
     GrayBlack,
     GrayMid,
     GrayWhite,
@@ -120,3 +132,8 @@ pub struct ProposeFactPayload {
 pub struct ApproveFactPayload {
     pub id: String,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoveEntityPayload {
+    pub id: String,
+}
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use axum::extract::ws::{Message as WsMessage, WebSocket};
 use axum::{
     extract::{State, WebSocketUpgrade},
     http::StatusCode,
@@ -6,12 +7,10 @@ use axum::{
     routing::get,
     Router,
 };
-use axum::extract::ws::{Message as WsMessage, WebSocket};
 use chrono::Utc;
 use dotenv::dotenv;
-use futures_util::StreamExt;
-use tokio::sync::{mpsc, watch};
 use futures_util::SinkExt;
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::env;
@@ -24,22 +23,29 @@ use tokio::net::TcpListener;
 use tokio::signal;
 use tokio::signal::unix::{signal as unix_signal, SignalKind};
 use tokio::sync::RwLock;
+use tokio::sync::{mpsc, watch};
 use tokio::time::{timeout, Duration};
+use transformer_neo::db::audit::checkpoint_every_from_env;
+use transformer_neo::db::signing::{require_signatures_from_env, signer_from_env, KeyRegistry};
 use transformer_neo::db::{OpKind, TuffDb, TuffEngine};
 use transformer_neo::lightweight::{
     LightweightCheckStatus, LightweightVerifier, MeaningDb, MeaningMatchMode,
 };
-use transformer_neo::models::{AgentIdentity, Id, IsoDateTime, ManualOverride, VerificationStatus};
+use transformer_neo::models::{
+    AgentIdentity, Entity, EntityRegistry, Id, IsoDateTime, ManualOverride, SharedEntityRegistry,
+    VerificationStatus,
+};
 use transformer_neo::pipeline::{
-    AbstractGenerator, ClaimVerifier, DummyAbstractGenerator, DummySplitter, DummyVerifier,
-    IngestPipeline, LlmAbstractor, LlmGapResolver, LlmVerifier,
-    WebFetcher,
+    client_from_env, verifier_from_env, AbstractGenerator, AsyncInputSplitter, CompositeFetcher,
+    ConfiguredVerifier, CredibilityRegistry, DummyAbstractGenerator, DummySplitter, GapResolver,
+    IngestContext, IngestPipeline, LlmAbstractor, LlmClaimSplitter, LlmGapResolver, PromptSet,
 };
 
 mod api;
 use api::message::{
     ApproveFactPayload, ControlCommand, ControlCommandPayload, ControlTrigger, JudgeResultPayload,
-    Message, ProposeFactPayload, StreamFragmentPayload, VerificationStatus as ProtoStatus,
+    Message, ProposeFactPayload, RemoveEntityPayload, StreamFragmentPayload,
+    VerificationStatus as ProtoStatus,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,28 +56,24 @@ struct PendingFact {
     created_at: String,
 }
 
-enum Verifier {
-    Dummy(DummyVerifier),
-    Llm(LlmVerifier),
+enum Splitter {
+    Dummy(DummySplitter),
+    Llm(Box<LlmClaimSplitter>),
 }
 
 #[async_trait]
-impl ClaimVerifier for Verifier {
-    async fn verify(
-        &self,
-        fragment: &str,
-        facts: &[transformer_neo::models::RequiredFact],
-    ) -> anyhow::Result<transformer_neo::pipeline::traits::VerificationResult> {
+impl AsyncInputSplitter for Splitter {
+    async fn split(&self, input: &str, ctx: &IngestContext) -> anyhow::Result<Vec<String>> {
         match self {
-            Verifier::Dummy(v) => v.verify(fragment, facts).await,
-            Verifier::Llm(v) => v.verify(fragment, facts).await,
+            Splitter::Dummy(s) => AsyncInputSplitter::split(s, input, ctx).await,
+            Splitter::Llm(s) => s.split(input, ctx).await,
         }
     }
 }
 
 enum Abstractor {
     Dummy(DummyAbstractGenerator),
-    Llm(LlmAbstractor),
+    Llm(Box<LlmAbstractor>),
 }
 
 #[async_trait]
@@ -81,22 +83,19 @@ impl AbstractGenerator for Abstractor {
         fragment: &str,
         facts: &[transformer_neo::models::RequiredFact],
         status: VerificationStatus,
+        ctx: &IngestContext,
     ) -> anyhow::Result<transformer_neo::models::Abstract> {
         match self {
-            Abstractor::Dummy(a) => a.generate(fragment, facts, status).await,
-            Abstractor::Llm(a) => a.generate(fragment, facts, status).await,
+            Abstractor::Dummy(a) => a.generate(fragment, facts, status, ctx).await,
+            Abstractor::Llm(a) => a.generate(fragment, facts, status, ctx).await,
         }
     }
 }
 
-fn valid_api_key(key: &str) -> bool {
-    let trimmed = key.trim();
-    !trimmed.is_empty() && !trimmed.contains("...")
-}
-
 fn to_proto_status(status: VerificationStatus) -> ProtoStatus {
     match status {
         VerificationStatus::Smoke => ProtoStatus::Smoke,
+        VerificationStatus::Outdated => ProtoStatus::Outdated,
         VerificationStatus::GrayBlack => ProtoStatus::GrayBlack,
         VerificationStatus::GrayMid => ProtoStatus::GrayMid,
         VerificationStatus::GrayWhite => ProtoStatus::GrayWhite,
@@ -106,22 +105,19 @@ fn to_proto_status(status: VerificationStatus) -> ProtoStatus {
 
 #[derive(Clone)]
 struct AppState {
-    pipeline: Arc<
-        IngestPipeline<
-            DummySplitter,
-            WebFetcher,
-            Verifier,
-            Abstractor,
-            TuffEngine,
-        >,
-    >,
+    pipeline:
+        Arc<IngestPipeline<Splitter, CompositeFetcher, ConfiguredVerifier, Abstractor, TuffEngine>>,
     lightweight_verifier: Option<Arc<RwLock<LightweightVerifier>>>,
-    gap_resolver: Option<Arc<LlmGapResolver>>,
+    // アンサンブル時は meaning DB もメンバーとして投票するので、先に打ち切らない
+    fast_path_short_circuit: bool,
     stop_threshold: f32,
+    ingest_timeout: Duration,
     history_dir: PathBuf,
     history_html: Arc<String>,
     pending_path: PathBuf,
     meaning_path: PathBuf,
+    entities: SharedEntityRegistry,
+    entity_path: PathBuf,
 }
 
 #[tokio::main]
@@ -134,50 +130,122 @@ async fn main() -> anyhow::Result<()> {
     fs::create_dir_all(&wal_dir)?;
     let wal_path = wal_dir.join("tuff.wal");
 
-    let engine = TuffEngine::new(
+    // TUFF_ENTITY_PATH=<json> adds entities to the built-in alias registry;
+    // edits from the bridge are saved back to it
+    let entity_path = env::var("TUFF_ENTITY_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| wal_dir.join("entities.json"));
+    let entities: SharedEntityRegistry =
+        Arc::new(std::sync::RwLock::new(EntityRegistry::load(&entity_path)?));
+
+    // TUFF_PUBLIC_KEYS=<file> verifies signatures on replay,
+    // TUFF_SIGNING_KEY=<file> signs every new op with this origin's key
+    let keys = KeyRegistry::from_env()?;
+    let signer = signer_from_env(keys.as_ref())?;
+    let mut engine = TuffEngine::new(
         wal_path
             .to_str()
             .ok_or_else(|| anyhow::anyhow!("invalid wal path"))?,
-    ).await?;
-
-    let api_key = env::var("OPENAI_API_KEY").ok();
-    let model = env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-4o".to_string());
+    )
+    .await?
+    .with_entities(entities.clone());
+    if let Some(signer) = signer {
+        engine = engine.with_signer(Arc::new(signer));
+    }
+    if let Some(keys) = keys {
+        engine = engine.with_keys(keys, require_signatures_from_env());
+    }
+    // TUFF_CHECKPOINT_EVERY=<n> checkpoints the WAL hash chain (default 100)
+    if let Some(n) = checkpoint_every_from_env() {
+        engine = engine.with_checkpoint_every(n);
+    }
+    let replay = engine.replay().await?;
+    log_line(&format!(
+        "TUFF-BRG WAL replay: applied={} rejected={} unreadable={}",
+        replay.applied,
+        replay.rejected.len(),
+        replay.unreadable
+    ));
+    for rejected in &replay.rejected {
+        log_line(&format!(
+            "TUFF-BRG WAL replay: rejected line={} op={} check={:?}",
+            rejected.line, rejected.op_id, rejected.check
+        ));
+    }
 
-    let verifier = match api_key.as_deref() {
-        Some(key) if valid_api_key(key) => Verifier::Llm(LlmVerifier::new(key, &model)),
-        _ => Verifier::Dummy(DummyVerifier),
+    // TUFF_LLM_PROVIDER / OPENAI_* / ANTHROPIC_* (see llm_client::client_from_env)
+    let llm = client_from_env();
+    // TUFF_PROMPT_DIR=<dir> switches the prompt set (default: built-in)
+    let prompts = Arc::new(PromptSet::from_env()?);
+
+    // TUFF_CLAIM_SPLITTER=llm enables LLM claim extraction (default: line splitting)
+    let llm_splitter = env::var("TUFF_CLAIM_SPLITTER")
+        .map(|v| v.trim().eq_ignore_ascii_case("llm"))
+        .unwrap_or(false);
+    let splitter = match &llm {
+        Some(client) if llm_splitter => Splitter::Llm(Box::new(
+            LlmClaimSplitter::new(client.clone()).with_prompts(prompts.clone()),
+        )),
+        _ => Splitter::Dummy(DummySplitter),
     };
 
-    let abstractor = match api_key.as_deref() {
-        Some(key) if valid_api_key(key) => Abstractor::Llm(LlmAbstractor::new(key, &model)),
-        _ => Abstractor::Dummy(DummyAbstractGenerator),
+    let lightweight_verifier = init_lightweight_verifier(&wal_dir, &entities);
+
+    // TUFF_ENSEMBLE_POLICY / TUFF_RULE_PRECHECK (see ensemble::verifier_from_env).
+    // In an ensemble the meaning-DB fast path votes instead of answering on its own.
+    let verifier = verifier_from_env(llm.as_ref(), prompts.clone(), lightweight_verifier.clone())?;
+    let fast_path_short_circuit = !verifier.is_ensemble();
+
+    let abstractor = match &llm {
+        Some(client) => Abstractor::Llm(Box::new(
+            LlmAbstractor::new(client.clone())
+                .with_prompts(prompts.clone())
+                .with_output_locale(env::var("TUFF_OUTPUT_LOCALE").ok())
+                .with_entities(entities.clone()),
+        )),
+        None => Abstractor::Dummy(DummyAbstractGenerator),
     };
 
-    let gap_resolver = match api_key.as_deref() {
-        Some(key) if valid_api_key(key) => Some(Arc::new(LlmGapResolver::new(key, &model))),
-        _ => None,
+    // SMOKE / OUTDATED verdicts are explained as transitions when an LLM is configured
+    let gap_resolver = llm.as_ref().map(|client| {
+        Arc::new(
+            LlmGapResolver::new(client.clone())
+                .with_prompts(prompts.clone())
+                .with_entities(entities.clone()),
+        ) as Arc<dyn GapResolver>
+    });
+
+    let meaning_path = env::var("TUFF_LIGHTWEIGHT_MEANING_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| wal_dir.join("lightweight").join("meaning.db"));
+
+    let fetcher = CompositeFetcher::from_env(&meaning_path)?;
+    // LLM を呼ぶ段: 検証・要約・遷移解決（＋LLM 分割）
+    let llm_stages = match &llm {
+        Some(_) => 3 + u32::from(llm_splitter),
+        None => 0,
     };
+    let ingest_timeout = ingest_timeout_from_env(&fetcher, llm_stages);
 
     let pipeline = IngestPipeline {
-        splitter: DummySplitter,
-        fetcher: WebFetcher::new(),
+        splitter,
+        fetcher,
         verifier,
         generator: abstractor,
         db: engine,
+        gap_resolver,
+        credibility: Some(CredibilityRegistry::from_env()?),
+        entities: Some(entities.clone()),
     };
 
-    let lightweight_verifier = init_lightweight_verifier(&wal_dir);
-
     let stop_threshold = env::var("TUFF_STOP_CONFIDENCE")
         .ok()
         .and_then(|v| v.parse::<f32>().ok())
         .unwrap_or(0.35);
 
-    let history_dir = PathBuf::from(env::var("TUFF_HISTORY_OUT").unwrap_or_else(|_| "history_out".to_string()));
+    let history_dir =
+        PathBuf::from(env::var("TUFF_HISTORY_OUT").unwrap_or_else(|_| "history_out".to_string()));
     let history_html = include_str!("../assets/history_viewer.html").to_string();
-    let meaning_path = env::var("TUFF_LIGHTWEIGHT_MEANING_PATH")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| wal_dir.join("lightweight").join("meaning.db"));
     let pending_path = env::var("TUFF_PENDING_FACT_PATH")
         .map(PathBuf::from)
         .unwrap_or_else(|_| wal_dir.join("lightweight").join("meaning.pending"));
@@ -185,12 +253,15 @@ async fn main() -> anyhow::Result<()> {
     let state = AppState {
         pipeline: Arc::new(pipeline),
         lightweight_verifier,
-        gap_resolver,
+        fast_path_short_circuit,
         stop_threshold,
+        ingest_timeout,
         history_dir,
         history_html: Arc::new(history_html),
         pending_path,
         meaning_path,
+        entities,
+        entity_path,
     };
 
     let app = Router::new()
@@ -198,7 +269,9 @@ async fn main() -> anyhow::Result<()> {
         .route("/history", get(history_page))
         .route("/history/api/latest", get(history_latest))
         .route("/history/api/timeline", get(history_timeline))
+        .route("/history/api/chains", get(history_chains))
         .route("/facts/pending", get(facts_pending))
+        .route("/entities", get(entities_list))
         .with_state(state);
 
     let addr: SocketAddr = "127.0.0.1:8787".parse()?;
@@ -225,7 +298,25 @@ fn parse_meaning_env_pairs() -> std::collections::HashMap<String, String> {
     map
 }
 
-fn init_lightweight_verifier(wal_dir: &PathBuf) -> Option<Arc<RwLock<LightweightVerifier>>> {
+/// Budget for one LLM call when `TUFF_INGEST_TIMEOUT_MS` is unset.
+const LLM_STAGE_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Timeout for one `ingest_with` call: `TUFF_INGEST_TIMEOUT_MS`, or 3 s plus
+/// the slowest fetch source plus `LLM_STAGE_TIMEOUT` per LLM stage.
+fn ingest_timeout_from_env(fetcher: &CompositeFetcher, llm_stages: u32) -> Duration {
+    env::var("TUFF_INGEST_TIMEOUT_MS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_millis)
+        .unwrap_or_else(|| {
+            Duration::from_secs(3) + fetcher.max_timeout() + LLM_STAGE_TIMEOUT * llm_stages
+        })
+}
+
+fn init_lightweight_verifier(
+    wal_dir: &Path,
+    entities: &SharedEntityRegistry,
+) -> Option<Arc<RwLock<LightweightVerifier>>> {
     let enabled = env::var("TUFF_FAST_PATH")
         .map(|v| v.trim() != "0")
         .unwrap_or(true);
@@ -240,12 +331,13 @@ fn init_lightweight_verifier(wal_dir: &PathBuf) -> Option<Arc<RwLock<Lightweight
         .unwrap_or(default_path);
 
     let mut merged = if path.exists() {
-        MeaningDb::from_path(&path).unwrap_or_else(|_| MeaningDb::new(std::collections::HashMap::new()))
+        MeaningDb::from_path(&path)
+            .unwrap_or_else(|_| MeaningDb::new(std::collections::HashMap::new()))
     } else {
         MeaningDb::new(std::collections::HashMap::new())
     };
     merged.merge(parse_meaning_env_pairs());
-    let verifier = LightweightVerifier::new(merged);
+    let verifier = LightweightVerifier::new(merged).with_entities(entities.clone());
     Some(Arc::new(RwLock::new(verifier)))
 }
 
@@ -290,7 +382,7 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
     log_line("WS: client connected");
     let (mut ws_tx, mut ws_rx) = socket.split();
     let (tx, mut rx) = mpsc::channel::<WsMessage>(256);
-    let (frag_tx, mut frag_rx) = watch::channel(String::new());
+    let (frag_tx, mut frag_rx) = watch::channel((String::new(), IngestContext::default()));
 
     // outbound pump
     let tx_task = tokio::spawn(async move {
@@ -306,13 +398,17 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
     let tx_for_worker = tx.clone();
     let ingest_task = tokio::spawn(async move {
         while frag_rx.changed().await.is_ok() {
-            let fragment = frag_rx.borrow().clone();
+            let (fragment, ingest_ctx) = frag_rx.borrow().clone();
             if fragment.is_empty() {
                 continue;
             }
             log_line("INGEST: start");
 
-            if let Some(lightweight) = state_for_worker.lightweight_verifier.as_ref() {
+            let fast_path = state_for_worker
+                .lightweight_verifier
+                .as_ref()
+                .filter(|_| state_for_worker.fast_path_short_circuit);
+            if let Some(lightweight) = fast_path {
                 let lw = lightweight.read().await;
                 match lw.check_fragment(&fragment) {
                     LightweightCheckStatus::Hit => {
@@ -334,7 +430,9 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                                 },
                             };
                             let _ = tx_for_worker
-                                .send(WsMessage::Text(serde_json::to_string(&judge).unwrap_or_default()))
+                                .send(WsMessage::Text(
+                                    serde_json::to_string(&judge).unwrap_or_default(),
+                                ))
                                 .await;
                             log_line("INGEST: cache hit");
                             continue;
@@ -354,7 +452,9 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                             },
                         };
                         let _ = tx_for_worker
-                            .send(WsMessage::Text(serde_json::to_string(&judge).unwrap_or_default()))
+                            .send(WsMessage::Text(
+                                serde_json::to_string(&judge).unwrap_or_default(),
+                            ))
                             .await;
                         let stop = Message::ControlCommand {
                             id: "system".to_string(),
@@ -367,7 +467,9 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                             },
                         };
                         let _ = tx_for_worker
-                            .send(WsMessage::Text(serde_json::to_string(&stop).unwrap_or_default()))
+                            .send(WsMessage::Text(
+                                serde_json::to_string(&stop).unwrap_or_default(),
+                            ))
                             .await;
                         log_line("INGEST: cache mismatch -> stop");
                         continue;
@@ -377,8 +479,10 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
             }
 
             let ingest_result = timeout(
-                Duration::from_secs(3),
-                state_for_worker.pipeline.ingest(&fragment),
+                state_for_worker.ingest_timeout,
+                state_for_worker
+                    .pipeline
+                    .ingest_with(&fragment, &ingest_ctx),
             )
             .await;
 
@@ -398,7 +502,7 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
             let mut status = VerificationStatus::GrayMid;
             let mut confidence = 0.4_f32;
             let mut evidence_count = 0usize;
-            let mut reason = "ok".to_string();
+            let reason;
             let mut abstract_id: Option<String> = None;
             if let Some(outcome) = ops.first() {
                 status = outcome.status;
@@ -408,6 +512,9 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                 if let OpKind::InsertAbstract { abstract_ } = &outcome.op.kind {
                     abstract_id = Some(abstract_.id.to_string());
                 }
+                if let Some(op) = &outcome.transition {
+                    log_line(&format!("INGEST: transition recorded op_id={}", op.op_id));
+                }
             } else {
                 reason = "source=LLM ok".to_string();
             }
@@ -425,7 +532,9 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                 },
             };
             let _ = tx_for_worker
-                .send(WsMessage::Text(serde_json::to_string(&judge).unwrap_or_default()))
+                .send(WsMessage::Text(
+                    serde_json::to_string(&judge).unwrap_or_default(),
+                ))
                 .await;
             log_line("WS: JudgeResult sent");
 
@@ -446,7 +555,9 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                     },
                 };
                 let _ = tx_for_worker
-                    .send(WsMessage::Text(serde_json::to_string(&stop).unwrap_or_default()))
+                    .send(WsMessage::Text(
+                        serde_json::to_string(&stop).unwrap_or_default(),
+                    ))
                     .await;
                 log_line("WS: ControlCommand STOP sent");
             }
@@ -476,7 +587,9 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                     },
                 };
                 let _ = tx
-                    .send(WsMessage::Text(serde_json::to_string(&stop).unwrap_or_default()))
+                    .send(WsMessage::Text(
+                        serde_json::to_string(&stop).unwrap_or_default(),
+                    ))
                     .await;
                 continue;
             }
@@ -505,6 +618,7 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                     conversation_id,
                     abstract_id,
                     note: Some(note),
+                    signature: None,
                 };
                 let _ = state.pipeline.db.append_override(override_).await;
             }
@@ -521,10 +635,26 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
             continue;
         }
 
+        if let Message::UpsertEntity { payload, .. } = parsed {
+            if let Err(err) = handle_upsert_entity(&state, payload) {
+                log_line(&format!("WS: UpsertEntity failed: {}", err));
+            }
+            continue;
+        }
+
+        if let Message::RemoveEntity { payload, .. } = parsed {
+            if let Err(err) = handle_remove_entity(&state, payload) {
+                log_line(&format!("WS: RemoveEntity failed: {}", err));
+            }
+            continue;
+        }
+
         if let Message::StreamFragment { payload, .. } = parsed {
             log_line("WS: StreamFragment received");
-            let StreamFragmentPayload { fragment, .. } = payload;
-            let _ = frag_tx.send(fragment);
+            let StreamFragmentPayload {
+                fragment, context, ..
+            } = payload;
+            let _ = frag_tx.send((fragment, IngestContext::with_locale(&context.locale)));
         }
     }
 
@@ -554,6 +684,11 @@ async fn history_timeline(State(state): State<AppState>) -> Response {
     (StatusCode::OK, value.to_string()).into_response()
 }
 
+async fn history_chains(State(state): State<AppState>) -> Response {
+    let value = read_json_or_default(&state.history_dir.join("chains.json"), json!([]));
+    (StatusCode::OK, value.to_string()).into_response()
+}
+
 async fn facts_pending(State(state): State<AppState>) -> Response {
     let items = load_pending_facts(&state.pending_path).await;
     (
@@ -563,6 +698,20 @@ async fn facts_pending(State(state): State<AppState>) -> Response {
         .into_response()
 }
 
+async fn entities_list(State(state): State<AppState>) -> Response {
+    let registry = state.entities.read().expect("entity registry lock");
+    let items: Vec<Value> = registry
+        .entities()
+        .iter()
+        .map(|entity| {
+            let mut value = serde_json::to_value(entity).unwrap_or_default();
+            value["scripts"] = json!(entity.scripts());
+            value
+        })
+        .collect();
+    (StatusCode::OK, Value::Array(items).to_string()).into_response()
+}
+
 fn read_json_or_default(path: &Path, default_value: Value) -> Value {
     match fs::read_to_string(path) {
         Ok(body) => serde_json::from_str(&body).unwrap_or(default_value),
@@ -687,6 +836,23 @@ async fn handle_approve(state: &AppState, payload: ApproveFactPayload) -> anyhow
     Ok(())
 }
 
+fn handle_upsert_entity(state: &AppState, entity: Entity) -> anyhow::Result<()> {
+    if entity.id.trim().is_empty() || entity.name.trim().is_empty() {
+        anyhow::bail!("entity needs an id and a name");
+    }
+    let mut registry = state.entities.write().expect("entity registry lock");
+    registry.upsert(entity);
+    registry.save(&state.entity_path)
+}
+
+fn handle_remove_entity(state: &AppState, payload: RemoveEntityPayload) -> anyhow::Result<()> {
+    let mut registry = state.entities.write().expect("entity registry lock");
+    if registry.remove(&payload.id) {
+        registry.save(&state.entity_path)?;
+    }
+    Ok(())
+}
+
 async fn append_pending_fact(path: &Path, item: &PendingFact) -> anyhow::Result<()> {
     if let Some(parent) = path.parent() {
         tokio::fs::create_dir_all(parent).await?;
@@ -736,7 +902,8 @@ async fn append_meaning_line(path: &Path, tag: &str, value: &str) -> anyhow::Res
         .append(true)
         .open(path)
         .await?;
-    file.write_all(format!("{}={}\n", tag, value).as_bytes()).await?;
+    file.write_all(format!("{}={}\n", tag, value).as_bytes())
+        .await?;
     file.flush().await?;
     Ok(())
 }
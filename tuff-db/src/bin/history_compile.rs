@@ -1,8 +1,31 @@
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use transformer_neo::db::signing::{require_signatures_from_env, KeyRegistry};
+use transformer_neo::history::compiler::{compile, compile_verified};
+use transformer_neo::models::EntityRegistry;
 
 fn main() -> anyhow::Result<()> {
     let wal_path = env::var("TUFF_WAL_PATH").unwrap_or_else(|_| "_tuffdb/tuff.wal".to_string());
     let out_dir = env::var("TUFF_HISTORY_OUT").unwrap_or_else(|_| "history_out".to_string());
-    transformer_neo::history::compiler::compile(PathBuf::from(wal_path), PathBuf::from(out_dir))
+    // TUFF_ENTITY_PATH=<json> is the registry the pipeline used (default: next to the WAL)
+    let entity_path = env::var("TUFF_ENTITY_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            Path::new(&wal_path)
+                .parent()
+                .unwrap_or(Path::new("."))
+                .join("entities.json")
+        });
+    let entities = EntityRegistry::load(&entity_path)?;
+    // TUFF_PUBLIC_KEYS=<file> verifies signatures and drops forged ops
+    match KeyRegistry::from_env()? {
+        Some(keys) => compile_verified(
+            PathBuf::from(wal_path),
+            PathBuf::from(out_dir),
+            &keys,
+            require_signatures_from_env(),
+            &entities,
+        ),
+        None => compile(PathBuf::from(wal_path), PathBuf::from(out_dir), &entities),
+    }
 }
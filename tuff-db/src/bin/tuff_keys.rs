@@ -0,0 +1,44 @@
+use std::env;
+use std::path::PathBuf;
+use transformer_neo::db::signing::{AgentSigner, KeyRegistry};
+use transformer_neo::models::AgentIdentity;
+
+const USAGE: &str = "usage:
+  tuff_keys keygen [key file]            new key for AI_ORIGIN (default: TUFF_SIGNING_KEY)
+  tuff_keys register-key <origin> <hex>  trust the key in TUFF_PUBLIC_KEYS";
+
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("keygen") => {
+            let path = args
+                .get(1)
+                .cloned()
+                .or_else(|| env::var("TUFF_SIGNING_KEY").ok())
+                .map(PathBuf::from)
+                .ok_or_else(|| anyhow::anyhow!("no key file given\n{}", USAGE))?;
+            let origin = AgentIdentity::current().origin;
+            let signer = AgentSigner::create(&origin, &path)?;
+            // 公開鍵の登録は管理者が内容を確認してから別途行う
+            println!("{}={}", signer.origin(), signer.public_key_hex());
+            Ok(())
+        }
+        Some("register-key") => {
+            let (Some(origin), Some(key)) = (args.get(1), args.get(2)) else {
+                anyhow::bail!("origin and key are required\n{}", USAGE);
+            };
+            let mut keys = KeyRegistry::from_env()?
+                .ok_or_else(|| anyhow::anyhow!("TUFF_PUBLIC_KEYS is not set"))?;
+            if keys.has_origin(origin) {
+                eprintln!(
+                    "note: {} already has a registered key; adding another",
+                    origin
+                );
+            }
+            keys.register(origin, key)?;
+            println!("registered {}", origin);
+            Ok(())
+        }
+        _ => anyhow::bail!("{}", USAGE),
+    }
+}
@@ -0,0 +1,29 @@
+use std::env;
+use std::path::PathBuf;
+use transformer_neo::db::signing::{require_signatures_from_env, KeyRegistry};
+use transformer_neo::db::{verify_log, verify_log_signed};
+
+fn main() -> anyhow::Result<()> {
+    // verify_log [wal]; defaults to TUFF_WAL_PATH, then _tuffdb/tuff.wal
+    let wal_path = env::args()
+        .nth(1)
+        .or_else(|| env::var("TUFF_WAL_PATH").ok())
+        .unwrap_or_else(|| "_tuffdb/tuff.wal".to_string());
+    let wal_path = PathBuf::from(wal_path);
+    // TUFF_PUBLIC_KEYS=<file> also checks the signature of every record and checkpoint
+    let report = match KeyRegistry::from_env()? {
+        Some(keys) => verify_log_signed(&wal_path, &keys, require_signatures_from_env())?,
+        None => {
+            eprintln!(
+                "warning: TUFF_PUBLIC_KEYS is not set; without signatures the hash chain \
+                 only detects accidental corruption"
+            );
+            verify_log(&wal_path)?
+        }
+    };
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    if !report.is_intact() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
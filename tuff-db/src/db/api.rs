@@ -1,4 +1,5 @@
-use crate::models::{Abstract, ManualOverride, Transition, VerificationStatus};
+use crate::history::chain::{StateSpan, TransitionChain};
+use crate::models::{Abstract, ManualOverride, Signature, Transition, VerificationStatus};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -7,7 +8,7 @@ use uuid::Uuid;
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum OpKind {
     InsertAbstract { abstract_: Abstract },
-    InsertTransition { transition: Transition },
+    InsertTransition { transition: Box<Transition> },
     AppendOverride { override_: ManualOverride },
 }
 
@@ -16,6 +17,14 @@ pub struct OpLog {
     pub op_id: Uuid,
     pub kind: OpKind,
     pub created_at: DateTime<Utc>,
+    // ハッシュチェーン: 通し番号と直前の記録のハッシュ (旧WALでは None)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_hash: Option<String>,
+    // 書き込んだエージェントによる署名 (署名対象からは除く)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -30,4 +39,16 @@ pub trait TuffDb: Send + Sync {
     async fn append_transition(&self, transition: Transition) -> anyhow::Result<OpLog>;
     async fn append_override(&self, override_: ManualOverride) -> anyhow::Result<OpLog>;
     async fn select(&self, query: SelectQuery) -> anyhow::Result<Vec<Abstract>>;
+
+    /// Transitions of a topic (an abstract tag key) as a validated chain.
+    async fn transition_chain(&self, topic_key: &str) -> anyhow::Result<TransitionChain>;
+
+    async fn current_state(&self, topic_key: &str) -> anyhow::Result<Option<String>> {
+        let chain = self.transition_chain(topic_key).await?;
+        Ok(chain.current_state().map(str::to_string))
+    }
+
+    async fn state_history(&self, topic_key: &str) -> anyhow::Result<Vec<StateSpan>> {
+        Ok(self.transition_chain(topic_key).await?.history())
+    }
 }
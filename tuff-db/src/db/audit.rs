@@ -0,0 +1,279 @@
+use crate::db::api::OpLog;
+use crate::db::signing::{canonical_json, KeyRegistry, SignatureCheck};
+use crate::models::Signature;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// `prev_hash` of the first record.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// SHA-256 of the record as stored (canonical JSON, signature included).
+pub fn record_hash(op: &OpLog) -> anyhow::Result<String> {
+    let value = serde_json::to_value(op)?;
+    Ok(hex::encode(Sha256::digest(
+        canonical_json(&value).as_bytes(),
+    )))
+}
+
+/// Last record of the chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainHead {
+    pub seq: u64,
+    pub hash: String,
+}
+
+impl ChainHead {
+    /// Head after the records in `body`; None for an empty log. Records
+    /// written before chaining count by position.
+    pub fn from_wal(body: &str) -> anyhow::Result<Option<Self>> {
+        let mut head = None;
+        let mut count = 0;
+        for line in body.lines().filter(|l| !l.trim().is_empty()) {
+            let Ok(op) = serde_json::from_str::<OpLog>(line) else {
+                continue;
+            };
+            count += 1;
+            head = Some(Self {
+                seq: op.seq.unwrap_or(count),
+                hash: record_hash(&op)?,
+            });
+        }
+        Ok(head)
+    }
+}
+
+/// Chain head persisted every few records to `<wal>.checkpoints`. Signed
+/// with the engine's key when one is configured; unsigned, anyone who can
+/// rewrite the WAL can rewrite the checkpoints to match.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub seq: u64,
+    pub head_hash: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
+}
+
+pub fn checkpoint_path(wal_path: &Path) -> PathBuf {
+    let mut name = wal_path.as_os_str().to_os_string();
+    name.push(".checkpoints");
+    PathBuf::from(name)
+}
+
+pub fn append_checkpoint(path: &Path, checkpoint: &Checkpoint) -> anyhow::Result<()> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", serde_json::to_string(checkpoint)?)?;
+    file.flush()?;
+    Ok(())
+}
+
+pub fn load_checkpoints(path: &Path) -> anyhow::Result<Vec<Checkpoint>> {
+    let body = match std::fs::read_to_string(path) {
+        Ok(v) => v,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    body.lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| Ok(serde_json::from_str(l)?))
+        .collect()
+}
+
+/// First problem found in the log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LogIssue {
+    /// The line is not a readable record.
+    Unreadable { line: usize },
+    /// The record no longer matches the hash the next record or a
+    /// checkpoint holds for it.
+    Tampered { line: usize, seq: u64 },
+    /// Records are missing before this line (or at the end of the log,
+    /// when `line` is past the last record).
+    Missing { line: usize, expected_seq: u64 },
+    /// An unchained record after chaining started.
+    Unchained { line: usize },
+    /// The record's signature is not accepted by the trusted keys.
+    BadSignature {
+        line: usize,
+        seq: u64,
+        check: SignatureCheck,
+    },
+    /// The checkpoint's signature is not accepted by the trusted keys.
+    BadCheckpoint { seq: u64, check: SignatureCheck },
+}
+
+/// Result of `verify_log`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogVerification {
+    pub records: usize,
+    // チェーン導入前の記録数 (先頭に限る)
+    pub legacy_records: usize,
+    pub checkpoints_checked: usize,
+    // 署名を検証した記録数 (鍵を渡した場合のみ)
+    pub signatures_checked: usize,
+    pub head: Option<ChainHead>,
+    pub first_issue: Option<LogIssue>,
+}
+
+impl LogVerification {
+    pub fn is_intact(&self) -> bool {
+        self.first_issue.is_none()
+    }
+}
+
+struct Record {
+    line: usize,
+    seq: u64,
+    hash: String,
+}
+
+/// Walks the WAL and its checkpoint file and reports the first tampered,
+/// missing or unreadable record. Without signatures this only detects
+/// accidental corruption: the hashes can be recomputed by anyone.
+pub fn verify_log(wal_path: &Path) -> anyhow::Result<LogVerification> {
+    verify_log_inner(wal_path, None)
+}
+
+/// Like `verify_log`, but also checks the signature of every record and
+/// checkpoint against `keys` and reports the first one that fails. With
+/// `require_signatures` unsigned records and checkpoints fail too.
+pub fn verify_log_signed(
+    wal_path: &Path,
+    keys: &KeyRegistry,
+    require_signatures: bool,
+) -> anyhow::Result<LogVerification> {
+    verify_log_inner(wal_path, Some((keys, require_signatures)))
+}
+
+fn verify_log_inner(
+    wal_path: &Path,
+    verify: Option<(&KeyRegistry, bool)>,
+) -> anyhow::Result<LogVerification> {
+    let body = std::fs::read_to_string(wal_path)?;
+    let checkpoints = load_checkpoints(&checkpoint_path(wal_path))?;
+    let mut result = LogVerification {
+        records: 0,
+        legacy_records: 0,
+        checkpoints_checked: 0,
+        signatures_checked: 0,
+        head: None,
+        first_issue: None,
+    };
+
+    let mut records: Vec<Record> = Vec::new();
+    let mut last_line = 0;
+    for (no, line) in body.lines().enumerate() {
+        let line_no = no + 1;
+        last_line = line_no;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(op) = serde_json::from_str::<OpLog>(line) else {
+            result.first_issue = Some(LogIssue::Unreadable { line: line_no });
+            return Ok(result);
+        };
+        let previous = records.last();
+        let expected_seq = previous.map_or(1, |p| p.seq + 1);
+        match (op.seq, op.prev_hash.as_deref()) {
+            (Some(seq), Some(prev_hash)) => {
+                // 番号が飛んでいれば欠落、戻っていれば改ざん
+                if seq > expected_seq {
+                    result.first_issue = Some(LogIssue::Missing {
+                        line: line_no,
+                        expected_seq,
+                    });
+                    return Ok(result);
+                }
+                if seq < expected_seq {
+                    result.first_issue = Some(LogIssue::Tampered { line: line_no, seq });
+                    return Ok(result);
+                }
+                // 直前の記録が書き換えられると、このハッシュと合わなくなる
+                let expected_prev = previous.map_or(GENESIS_HASH, |p| p.hash.as_str());
+                if prev_hash != expected_prev {
+                    let (line, seq) = previous.map_or((line_no, seq), |p| (p.line, p.seq));
+                    result.first_issue = Some(LogIssue::Tampered { line, seq });
+                    return Ok(result);
+                }
+            }
+            // チェーン導入前の記録は先頭にのみ許す
+            _ if result.legacy_records == records.len() => result.legacy_records += 1,
+            _ => {
+                result.first_issue = Some(LogIssue::Unchained { line: line_no });
+                return Ok(result);
+            }
+        }
+        let seq = op.seq.unwrap_or(expected_seq);
+        if let Some((keys, require)) = verify {
+            result.signatures_checked += 1;
+            let check = keys.verify_op(&op);
+            if !check.accepted(require) {
+                result.first_issue = Some(LogIssue::BadSignature {
+                    line: line_no,
+                    seq,
+                    check,
+                });
+                return Ok(result);
+            }
+        }
+        records.push(Record {
+            line: line_no,
+            seq,
+            hash: record_hash(&op)?,
+        });
+    }
+    result.records = records.len();
+    result.head = records.last().map(|p| ChainHead {
+        seq: p.seq,
+        hash: p.hash.clone(),
+    });
+
+    // チェックポイントは末尾の改ざん・切り詰めを検出する
+    for checkpoint in &checkpoints {
+        result.checkpoints_checked += 1;
+        // 署名の無いチェックポイントは WAL と一緒に書き換えられる
+        if let Some((keys, require)) = verify {
+            let check = keys.verify(checkpoint);
+            if !check.accepted(require) {
+                result.first_issue = Some(LogIssue::BadCheckpoint {
+                    seq: checkpoint.seq,
+                    check,
+                });
+                return Ok(result);
+            }
+        }
+        match records.iter().find(|r| r.seq == checkpoint.seq) {
+            Some(record) if record.hash == checkpoint.head_hash => {}
+            Some(record) => {
+                result.first_issue = Some(LogIssue::Tampered {
+                    line: record.line,
+                    seq: record.seq,
+                });
+                return Ok(result);
+            }
+            None => {
+                let expected_seq = result.head.as_ref().map_or(1, |h| h.seq + 1);
+                result.first_issue = Some(LogIssue::Missing {
+                    line: last_line + 1,
+                    expected_seq,
+                });
+                return Ok(result);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// `TUFF_CHECKPOINT_EVERY=<n>`; None when unset or not a number.
+pub fn checkpoint_every_from_env() -> Option<u64> {
+    std::env::var("TUFF_CHECKPOINT_EVERY")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+}
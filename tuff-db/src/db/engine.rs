@@ -1,21 +1,64 @@
 use crate::db::api::{OpKind, OpLog, SelectQuery, TuffDb};
+use crate::db::audit::{
+    append_checkpoint, checkpoint_path, record_hash, ChainHead, Checkpoint, GENESIS_HASH,
+};
 use crate::db::index::InMemoryIndex;
-use crate::models::{Abstract, AgentIdentity, ManualOverride, Transition};
+use crate::db::signing::{AgentSigner, KeyRegistry, SignatureCheck};
+use crate::history::chain::TransitionChain;
+use crate::models::{
+    Abstract, AgentIdentity, EntityRegistry, ManualOverride, SharedEntityRegistry, Transition,
+};
 use async_trait::async_trait;
 use chrono::Utc;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::sync::Mutex as StdMutex;
 use tokio::fs::{File, OpenOptions};
 use tokio::io::{AsyncWriteExt, BufWriter};
 use tokio::sync::Mutex as TokioMutex;
 use uuid::Uuid;
 
+const DEFAULT_CHECKPOINT_EVERY: u64 = 100;
+
 pub struct TuffEngine {
     index: StdMutex<InMemoryIndex>,
     wal: TokioMutex<BufWriter<File>>,
+    wal_path: PathBuf,
+    // 最後に書いた記録 (WAL のロック中にのみ更新する)
+    head: StdMutex<Option<ChainHead>>,
+    checkpoint_every: u64,
+    signer: Option<Arc<AgentSigner>>,
+    keys: Option<KeyRegistry>,
+    require_signatures: bool,
+    entities: Option<SharedEntityRegistry>,
+}
+
+/// WAL record skipped during replay.
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectedOp {
+    // WAL の行番号 (1始まり)
+    pub line: usize,
+    pub op_id: Uuid,
+    pub check: SignatureCheck,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReplayReport {
+    pub applied: usize,
+    pub rejected: Vec<RejectedOp>,
+    // JSON として読めなかった行数
+    pub unreadable: usize,
 }
 
 impl TuffEngine {
     pub async fn new(wal_path: &str) -> anyhow::Result<Self> {
+        // 既存の WAL があればその末尾からチェーンを続ける
+        let head = match tokio::fs::read_to_string(wal_path).await {
+            Ok(body) => ChainHead::from_wal(&body)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
         let file = OpenOptions::new()
             .create(true)
             .append(true)
@@ -24,28 +67,136 @@ impl TuffEngine {
         Ok(Self {
             index: StdMutex::new(InMemoryIndex::default()),
             wal: TokioMutex::new(BufWriter::new(file)),
+            wal_path: PathBuf::from(wal_path),
+            head: StdMutex::new(head),
+            checkpoint_every: DEFAULT_CHECKPOINT_EVERY,
+            signer: None,
+            keys: None,
+            require_signatures: false,
+            entities: None,
         })
     }
 
-    async fn write_wal(&self, op: &OpLog) -> anyhow::Result<()> {
+    /// Sign every op (and the transition or override inside it) with this key.
+    pub fn with_signer(mut self, signer: Arc<AgentSigner>) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Write a checkpoint of the chain head every `n` records (0 disables).
+    pub fn with_checkpoint_every(mut self, n: u64) -> Self {
+        self.checkpoint_every = n;
+        self
+    }
+
+    /// Compare transition states under these aliases (default: built-in).
+    pub fn with_entities(mut self, entities: SharedEntityRegistry) -> Self {
+        self.entities = Some(entities);
+        self
+    }
+
+    /// Verify signatures against `keys` on replay. With `require_signatures`
+    /// unsigned records are rejected too.
+    pub fn with_keys(mut self, keys: KeyRegistry, require_signatures: bool) -> Self {
+        self.keys = Some(keys);
+        self.require_signatures = require_signatures;
+        self
+    }
+
+    /// Rebuilds the index from the WAL. Records failing signature checks
+    /// are left out of the index and listed in the report.
+    pub async fn replay(&self) -> anyhow::Result<ReplayReport> {
+        let body = match tokio::fs::read_to_string(&self.wal_path).await {
+            Ok(v) => v,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.into()),
+        };
+        let mut report = ReplayReport::default();
+        let mut index = self.index.lock().expect("index lock");
+        for (no, line) in body.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Ok(op) = serde_json::from_str::<OpLog>(line) else {
+                report.unreadable += 1;
+                continue;
+            };
+            if let Some(keys) = &self.keys {
+                let check = keys.verify_op(&op);
+                if !check.accepted(self.require_signatures) {
+                    report.rejected.push(RejectedOp {
+                        line: no + 1,
+                        op_id: op.op_id,
+                        check,
+                    });
+                    continue;
+                }
+            }
+            match op.kind {
+                OpKind::InsertAbstract { abstract_ } => index.insert(abstract_),
+                OpKind::InsertTransition { transition } => index.insert_transition(*transition),
+                OpKind::AppendOverride { .. } => {}
+            }
+            report.applied += 1;
+        }
+        Ok(report)
+    }
+
+    async fn write_wal(&self, op: &mut OpLog) -> anyhow::Result<()> {
         let mut guard = self.wal.lock().await;
+        // 通し番号と直前のハッシュは署名の対象に含める
+        let previous = self.head.lock().expect("head lock").clone();
+        op.seq = Some(previous.as_ref().map_or(1, |h| h.seq + 1));
+        op.prev_hash = Some(
+            previous
+                .map(|h| h.hash)
+                .unwrap_or_else(|| GENESIS_HASH.to_string()),
+        );
+        if let Some(signer) = &self.signer {
+            signer.sign_op(op)?;
+        }
+        let head = ChainHead {
+            seq: op.seq.unwrap_or_default(),
+            hash: record_hash(op)?,
+        };
         let line = serde_json::to_string(op)?;
         guard.write_all(line.as_bytes()).await?;
         guard.write_all(b"\n").await?;
         guard.flush().await?;
+        if self.checkpoint_every > 0 && head.seq.is_multiple_of(self.checkpoint_every) {
+            let mut checkpoint = Checkpoint {
+                seq: head.seq,
+                head_hash: head.hash.clone(),
+                created_at: Utc::now(),
+                signature: None,
+            };
+            if let Some(signer) = &self.signer {
+                signer.sign(&mut checkpoint)?;
+            }
+            append_checkpoint(&checkpoint_path(&self.wal_path), &checkpoint)?;
+        }
+        *self.head.lock().expect("head lock") = Some(head);
         Ok(())
     }
+
+    /// Last record written to the WAL.
+    pub fn chain_head(&self) -> Option<ChainHead> {
+        self.head.lock().expect("head lock").clone()
+    }
 }
 
 #[async_trait]
 impl TuffDb for TuffEngine {
     async fn append_abstract(&self, abstract_: Abstract) -> anyhow::Result<OpLog> {
-        let op = OpLog {
+        let mut op = OpLog {
             op_id: Uuid::new_v4(),
             kind: OpKind::InsertAbstract { abstract_ },
             created_at: Utc::now(),
+            seq: None,
+            prev_hash: None,
+            signature: None,
         };
-        self.write_wal(&op).await?;
+        self.write_wal(&mut op).await?;
 
         if let OpKind::InsertAbstract { abstract_ } = op.kind.clone() {
             let mut index = self.index.lock().expect("index lock");
@@ -57,31 +208,52 @@ impl TuffDb for TuffEngine {
 
     async fn append_transition(&self, mut transition: Transition) -> anyhow::Result<OpLog> {
         transition.agent = AgentIdentity::current();
-        let op = OpLog {
+        let mut op = OpLog {
             op_id: Uuid::new_v4(),
-            kind: OpKind::InsertTransition { transition },
+            kind: OpKind::InsertTransition {
+                transition: Box::new(transition),
+            },
             created_at: Utc::now(),
+            seq: None,
+            prev_hash: None,
+            signature: None,
         };
-        self.write_wal(&op).await?;
+        self.write_wal(&mut op).await?;
+
+        if let OpKind::InsertTransition { transition } = op.kind.clone() {
+            let mut index = self.index.lock().expect("index lock");
+            index.insert_transition(*transition);
+        }
+
         Ok(op)
     }
 
     async fn append_override(&self, mut override_: ManualOverride) -> anyhow::Result<OpLog> {
         override_.agent = AgentIdentity::current();
-        let op = OpLog {
+        let mut op = OpLog {
             op_id: Uuid::new_v4(),
             kind: OpKind::AppendOverride { override_ },
             created_at: Utc::now(),
+            seq: None,
+            prev_hash: None,
+            signature: None,
         };
-        self.write_wal(&op).await?;
+        self.write_wal(&mut op).await?;
         Ok(op)
     }
 
     async fn select(&self, query: SelectQuery) -> anyhow::Result<Vec<Abstract>> {
         let index = self.index.lock().expect("index lock");
-        Ok(index.select(
-            query.tag_key.as_deref(),
-            query.min_verification,
-        ))
+        Ok(index.select(query.tag_key.as_deref(), query.min_verification))
+    }
+
+    async fn transition_chain(&self, topic_key: &str) -> anyhow::Result<TransitionChain> {
+        let index = self.index.lock().expect("index lock");
+        let guard = self
+            .entities
+            .as_ref()
+            .map(|entities| entities.read().expect("entity registry lock"));
+        let registry = guard.as_deref().unwrap_or(EntityRegistry::builtin_ref());
+        Ok(index.chain(topic_key, registry))
     }
 }
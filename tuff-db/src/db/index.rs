@@ -1,17 +1,46 @@
-use crate::models::{Abstract, VerificationStatus};
+use crate::history::chain::TransitionChain;
+use crate::models::{Abstract, EntityRegistry, Transition, VerificationStatus};
 use std::collections::HashMap;
+use uuid::Uuid;
 
 #[derive(Default, Debug)]
 pub struct InMemoryIndex {
     by_tag_key: HashMap<String, Vec<Abstract>>,
+    // Abstract ID -> タグキー (遷移のトピック解決用)
+    abstract_topics: HashMap<Uuid, String>,
+    transitions_by_topic: HashMap<String, Vec<Transition>>,
 }
 
 impl InMemoryIndex {
     pub fn insert(&mut self, abstract_: Abstract) {
         let key = abstract_.tags.to_key();
+        self.abstract_topics.insert(abstract_.id.0, key.clone());
         self.by_tag_key.entry(key).or_default().push(abstract_);
     }
 
+    /// Files the transition under the tag key of its abstract; transitions
+    /// without a known abstract share the empty key.
+    pub fn insert_transition(&mut self, transition: Transition) {
+        let key = transition
+            .abstract_id
+            .as_ref()
+            .and_then(|id| self.abstract_topics.get(&id.0).cloned())
+            .unwrap_or_default();
+        self.transitions_by_topic
+            .entry(key)
+            .or_default()
+            .push(transition);
+    }
+
+    pub fn chain(&self, topic_key: &str, registry: &EntityRegistry) -> TransitionChain {
+        let transitions = self
+            .transitions_by_topic
+            .get(topic_key)
+            .cloned()
+            .unwrap_or_default();
+        TransitionChain::build(topic_key, transitions, registry)
+    }
+
     pub fn select(
         &self,
         tag_key: Option<&str>,
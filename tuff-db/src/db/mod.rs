@@ -1,7 +1,11 @@
 pub mod api;
+pub mod audit;
 pub mod engine;
 pub mod index;
+pub mod signing;
 
 pub use api::{OpKind, OpLog, SelectQuery, TuffDb};
-pub use engine::TuffEngine;
+pub use audit::{verify_log, verify_log_signed, ChainHead, Checkpoint, LogIssue, LogVerification};
+pub use engine::{RejectedOp, ReplayReport, TuffEngine};
 pub use index::InMemoryIndex;
+pub use signing::{AgentSigner, KeyRegistry, Signable, SignatureCheck};
@@ -0,0 +1,395 @@
+use crate::db::api::{OpKind, OpLog};
+use crate::db::audit::Checkpoint;
+use crate::models::{ManualOverride, Signature, Transition};
+use anyhow::Context;
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A record carrying an Ed25519 signature over its canonical form.
+pub trait Signable: Serialize + Clone {
+    fn signature(&self) -> Option<&Signature>;
+    fn set_signature(&mut self, signature: Option<Signature>);
+    /// Origin the record claims to come from, when it names one.
+    fn claimed_origin(&self) -> Option<&str>;
+}
+
+impl Signable for Transition {
+    fn signature(&self) -> Option<&Signature> {
+        self.signature.as_ref()
+    }
+    fn set_signature(&mut self, signature: Option<Signature>) {
+        self.signature = signature;
+    }
+    fn claimed_origin(&self) -> Option<&str> {
+        Some(&self.agent.origin)
+    }
+}
+
+impl Signable for ManualOverride {
+    fn signature(&self) -> Option<&Signature> {
+        self.signature.as_ref()
+    }
+    fn set_signature(&mut self, signature: Option<Signature>) {
+        self.signature = signature;
+    }
+    fn claimed_origin(&self) -> Option<&str> {
+        Some(&self.agent.origin)
+    }
+}
+
+impl Signable for OpLog {
+    fn signature(&self) -> Option<&Signature> {
+        self.signature.as_ref()
+    }
+    fn set_signature(&mut self, signature: Option<Signature>) {
+        self.signature = signature;
+    }
+    fn claimed_origin(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl Signable for Checkpoint {
+    fn signature(&self) -> Option<&Signature> {
+        self.signature.as_ref()
+    }
+    fn set_signature(&mut self, signature: Option<Signature>) {
+        self.signature = signature;
+    }
+    fn claimed_origin(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// Bytes that are signed: the record without its signature as JSON with
+/// object keys sorted and no whitespace.
+pub fn canonical_bytes<T: Signable>(record: &T) -> anyhow::Result<Vec<u8>> {
+    let mut unsigned = record.clone();
+    unsigned.set_signature(None);
+    Ok(canonical_json(&serde_json::to_value(&unsigned)?).into_bytes())
+}
+
+/// JSON with object keys sorted and no whitespace.
+pub fn canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+fn key_id(public_key: &[u8]) -> String {
+    hex::encode(Sha256::digest(public_key))[..16].to_string()
+}
+
+/// Ed25519 key of one agent origin.
+pub struct AgentSigner {
+    origin: String,
+    key_pair: Ed25519KeyPair,
+}
+
+impl AgentSigner {
+    /// New random key; returns the signer and its PKCS#8 document to store.
+    pub fn generate(origin: &str) -> anyhow::Result<(Self, Vec<u8>)> {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| anyhow::anyhow!("ed25519 key generation failed"))?;
+        let signer = Self::from_pkcs8(origin, pkcs8.as_ref())?;
+        Ok((signer, pkcs8.as_ref().to_vec()))
+    }
+
+    pub fn from_pkcs8(origin: &str, pkcs8: &[u8]) -> anyhow::Result<Self> {
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8)
+            .map_err(|err| anyhow::anyhow!("invalid ed25519 key: {}", err))?;
+        Ok(Self {
+            origin: origin.to_string(),
+            key_pair,
+        })
+    }
+
+    /// Reads the hex PKCS#8 key at `path`.
+    pub fn load(origin: &str, path: &Path) -> anyhow::Result<Self> {
+        let body = std::fs::read_to_string(path)
+            .with_context(|| format!("signing key {}", path.display()))?;
+        let pkcs8 =
+            hex::decode(body.trim()).with_context(|| format!("signing key {}", path.display()))?;
+        Self::from_pkcs8(origin, &pkcs8)
+    }
+
+    /// Generates a key and writes it to `path` (mode 0600); an existing key
+    /// is never overwritten.
+    pub fn create(origin: &str, path: &Path) -> anyhow::Result<Self> {
+        if path.exists() {
+            anyhow::bail!("signing key {} already exists", path.display());
+        }
+        let (signer, pkcs8) = Self::generate(origin)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, hex::encode(pkcs8) + "\n")?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(signer)
+    }
+
+    pub fn origin(&self) -> &str {
+        &self.origin
+    }
+
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.key_pair.public_key().as_ref())
+    }
+
+    pub fn key_id(&self) -> String {
+        key_id(self.key_pair.public_key().as_ref())
+    }
+
+    pub fn sign<T: Signable>(&self, record: &mut T) -> anyhow::Result<()> {
+        let bytes = canonical_bytes(record)?;
+        let sig = self.key_pair.sign(&bytes);
+        record.set_signature(Some(Signature {
+            origin: self.origin.clone(),
+            key_id: self.key_id(),
+            sig_hex: hex::encode(sig.as_ref()),
+        }));
+        Ok(())
+    }
+
+    /// Signs the record inside the op, then the op itself.
+    pub fn sign_op(&self, op: &mut OpLog) -> anyhow::Result<()> {
+        match &mut op.kind {
+            OpKind::InsertTransition { transition } => self.sign(transition.as_mut())?,
+            OpKind::AppendOverride { override_ } => self.sign(override_)?,
+            OpKind::InsertAbstract { .. } => {}
+        }
+        self.sign(op)
+    }
+}
+
+/// Result of checking one signed record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureCheck {
+    Valid,
+    Unsigned,
+    // 署名者の鍵が登録されていない
+    UnknownKey,
+    // 署名者と記録上の origin が違う
+    OriginMismatch,
+    Invalid,
+}
+
+impl SignatureCheck {
+    /// Invalid, unknown-key and origin-mismatch records are never accepted;
+    /// unsigned ones only when signatures are optional.
+    pub fn accepted(self, require_signatures: bool) -> bool {
+        match self {
+            SignatureCheck::Valid => true,
+            SignatureCheck::Unsigned => !require_signatures,
+            _ => false,
+        }
+    }
+}
+
+/// Public keys trusted per agent origin, read from `origin=<hex key>` lines.
+#[derive(Debug, Clone, Default)]
+pub struct KeyRegistry {
+    keys: HashMap<String, Vec<Vec<u8>>>,
+    path: Option<PathBuf>,
+}
+
+impl KeyRegistry {
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        let mut registry = Self::default();
+        for (no, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (origin, key) = line.split_once('=').ok_or_else(|| {
+                anyhow::anyhow!("public keys line {}: expected origin=<hex>", no + 1)
+            })?;
+            let key =
+                hex::decode(key.trim()).with_context(|| format!("public keys line {}", no + 1))?;
+            registry
+                .keys
+                .entry(origin.trim().to_string())
+                .or_default()
+                .push(key);
+        }
+        Ok(registry)
+    }
+
+    /// Reads `path`; a missing file gives an empty registry bound to it.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut registry = if path.exists() {
+            let body = std::fs::read_to_string(path)
+                .with_context(|| format!("public keys {}", path.display()))?;
+            Self::parse(&body)?
+        } else {
+            Self::default()
+        };
+        registry.path = Some(path.to_path_buf());
+        Ok(registry)
+    }
+
+    /// `TUFF_PUBLIC_KEYS=<file>`; None when unset.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        match std::env::var("TUFF_PUBLIC_KEYS") {
+            Ok(path) if !path.trim().is_empty() => Ok(Some(Self::load(Path::new(path.trim()))?)),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn has_origin(&self, origin: &str) -> bool {
+        self.keys.get(origin).is_some_and(|keys| !keys.is_empty())
+    }
+
+    /// True when `public_key_hex` is registered for `origin`.
+    pub fn trusts(&self, origin: &str, public_key_hex: &str) -> bool {
+        let Ok(key) = hex::decode(public_key_hex.trim()) else {
+            return false;
+        };
+        self.keys
+            .get(origin)
+            .is_some_and(|keys| keys.contains(&key))
+    }
+
+    /// Adds a key and appends it to the registry file, if bound to one.
+    pub fn register(&mut self, origin: &str, public_key_hex: &str) -> anyhow::Result<()> {
+        let key = hex::decode(public_key_hex.trim())?;
+        let keys = self.keys.entry(origin.to_string()).or_default();
+        if keys.contains(&key) {
+            return Ok(());
+        }
+        keys.push(key);
+        if let Some(path) = &self.path {
+            use std::io::Write;
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            writeln!(file, "{}={}", origin, public_key_hex.trim())?;
+        }
+        Ok(())
+    }
+
+    pub fn verify<T: Signable>(&self, record: &T) -> SignatureCheck {
+        let Some(signature) = record.signature() else {
+            return SignatureCheck::Unsigned;
+        };
+        if record
+            .claimed_origin()
+            .is_some_and(|origin| origin != signature.origin)
+        {
+            return SignatureCheck::OriginMismatch;
+        }
+        let Some(key) = self
+            .keys
+            .get(&signature.origin)
+            .and_then(|keys| keys.iter().find(|k| key_id(k) == signature.key_id))
+        else {
+            return SignatureCheck::UnknownKey;
+        };
+        let (Ok(bytes), Ok(sig)) = (canonical_bytes(record), hex::decode(&signature.sig_hex))
+        else {
+            return SignatureCheck::Invalid;
+        };
+        match UnparsedPublicKey::new(&ED25519, key).verify(&bytes, &sig) {
+            Ok(()) => SignatureCheck::Valid,
+            Err(_) => SignatureCheck::Invalid,
+        }
+    }
+
+    /// Checks the op and the record inside it; the first failure wins.
+    pub fn verify_op(&self, op: &OpLog) -> SignatureCheck {
+        let outer = self.verify(op);
+        let inner = match &op.kind {
+            OpKind::InsertTransition { transition } => self.verify(transition.as_ref()),
+            OpKind::AppendOverride { override_ } => self.verify(override_),
+            OpKind::InsertAbstract { .. } => outer,
+        };
+        match (outer, inner) {
+            (SignatureCheck::Valid, SignatureCheck::Valid) => SignatureCheck::Valid,
+            (SignatureCheck::Valid, other) | (other, _) => other,
+        }
+    }
+}
+
+/// Signer for this process: `TUFF_SIGNING_KEY=<file>` (hex PKCS#8, made
+/// with `tuff_keys keygen`) under the current `AI_ORIGIN`. With `keys`, the
+/// key must already be registered for that origin (`tuff_keys register-key`);
+/// keys are never trusted on first use.
+pub fn signer_from_env(keys: Option<&KeyRegistry>) -> anyhow::Result<Option<AgentSigner>> {
+    let Ok(path) = std::env::var("TUFF_SIGNING_KEY") else {
+        return Ok(None);
+    };
+    let path = Path::new(path.trim());
+    let origin = crate::models::AgentIdentity::current().origin;
+    if !path.exists() {
+        anyhow::bail!(
+            "signing key {} not found; create it with `tuff_keys keygen {}`",
+            path.display(),
+            path.display()
+        );
+    }
+    let signer = AgentSigner::load(&origin, path)?;
+    if let Some(keys) = keys {
+        if !keys.trusts(&origin, &signer.public_key_hex()) {
+            anyhow::bail!(
+                "the key in {} is not registered for origin {}; an admin must run \
+                 `tuff_keys register-key {} {}`",
+                path.display(),
+                origin,
+                origin,
+                signer.public_key_hex()
+            );
+        }
+    }
+    Ok(Some(signer))
+}
+
+/// `TUFF_REQUIRE_SIGNATURES=1` rejects unsigned records on replay.
+pub fn require_signatures_from_env() -> bool {
+    std::env::var("TUFF_REQUIRE_SIGNATURES")
+        .map(|v| v.trim() == "1")
+        .unwrap_or(false)
+}
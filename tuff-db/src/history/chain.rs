@@ -0,0 +1,178 @@
+use crate::models::{EntityRegistry, Id, IsoDateTime, StateValue, Transition};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Problem found while chaining the transitions of one topic.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChainIssue {
+    /// `from_state` does not continue the previous `to_state`.
+    Gap {
+        transition_id: Id,
+        expected_from: String,
+        found_from: String,
+    },
+    /// Two reports of the same moment leave the same state for different states.
+    Conflict {
+        transition_id: Id,
+        other_id: Id,
+        from_state: String,
+    },
+    /// No `occurred_at`; ordered by `observed_at` instead.
+    Undated { transition_id: Id },
+}
+
+/// A state and the time it held, as read from the chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct StateSpan {
+    pub state: String,
+    pub value: StateValue,
+    // 開始・終了時刻 (不明なら None)
+    pub since: Option<IsoDateTime>,
+    pub until: Option<IsoDateTime>,
+    // この状態に入った遷移 (最初の状態は None)
+    pub entered_by: Option<Id>,
+}
+
+/// Transitions of one topic in `occurred_at` order, with the issues that keep
+/// them from forming a single consistent chain. Conflicting reports are listed
+/// in `issues` only.
+#[derive(Clone, Debug, Serialize)]
+pub struct TransitionChain {
+    pub topic: String,
+    pub transitions: Vec<Transition>,
+    pub issues: Vec<ChainIssue>,
+}
+
+// 構造化した値で比較する (表記揺れ・日英の別名は同一視)
+fn same_from(a: &Transition, b: &Transition, registry: &EntityRegistry) -> bool {
+    a.from_value().same_as_in(&b.from_value(), registry)
+}
+
+fn same_to(a: &Transition, b: &Transition, registry: &EntityRegistry) -> bool {
+    a.to_value().same_as_in(&b.to_value(), registry)
+}
+
+fn when(transition: &Transition) -> DateTime<Utc> {
+    transition
+        .occurred_at
+        .as_ref()
+        .unwrap_or(&transition.observed_at)
+        .0
+}
+
+impl TransitionChain {
+    /// Orders the transitions, drops repeated reports of the same change and
+    /// records gaps, conflicts and undated links. States are compared under
+    /// `registry`'s aliases.
+    pub fn build(topic: &str, mut transitions: Vec<Transition>, registry: &EntityRegistry) -> Self {
+        transitions.sort_by(|a, b| {
+            when(a)
+                .cmp(&when(b))
+                .then(a.observed_at.0.cmp(&b.observed_at.0))
+        });
+
+        let mut chain: Vec<Transition> = Vec::new();
+        let mut issues = Vec::new();
+        for transition in transitions {
+            let duplicate = chain.iter().any(|t| {
+                same_from(t, &transition, registry)
+                    && same_to(t, &transition, registry)
+                    && t.occurred_at == transition.occurred_at
+            });
+            if duplicate {
+                continue;
+            }
+            if transition.occurred_at.is_none() {
+                issues.push(ChainIssue::Undated {
+                    transition_id: transition.transition_id.clone(),
+                });
+            }
+            let conflict = chain.iter().find(|t| {
+                t.occurred_at.is_some()
+                    && t.occurred_at == transition.occurred_at
+                    && same_from(t, &transition, registry)
+                    && !same_to(t, &transition, registry)
+            });
+            // 先に並んだ側を採用し、食い違う報告はチェーンに入れない
+            if let Some(other) = conflict {
+                issues.push(ChainIssue::Conflict {
+                    transition_id: transition.transition_id.clone(),
+                    other_id: other.transition_id.clone(),
+                    from_state: transition.from_state.clone(),
+                });
+                continue;
+            }
+            if let Some(previous) = chain.last() {
+                if !previous
+                    .to_value()
+                    .same_as_in(&transition.from_value(), registry)
+                {
+                    issues.push(ChainIssue::Gap {
+                        transition_id: transition.transition_id.clone(),
+                        expected_from: previous.to_state.clone(),
+                        found_from: transition.from_state.clone(),
+                    });
+                }
+            }
+            chain.push(transition);
+        }
+
+        Self {
+            topic: topic.to_string(),
+            transitions: chain,
+            issues,
+        }
+    }
+
+    /// True when every link continues the previous one (undated links allowed).
+    pub fn is_consistent(&self) -> bool {
+        self.issues
+            .iter()
+            .all(|issue| matches!(issue, ChainIssue::Undated { .. }))
+    }
+
+    /// State after the last transition.
+    pub fn current_state(&self) -> Option<&str> {
+        self.transitions.last().map(|t| t.to_state.as_str())
+    }
+
+    /// States in order with the time each held. A gap starts a new span for
+    /// the unexpected `from_state`, so nothing reported is lost.
+    pub fn history(&self) -> Vec<StateSpan> {
+        let mut spans: Vec<StateSpan> = Vec::new();
+        for (pos, transition) in self.transitions.iter().enumerate() {
+            let at = transition
+                .occurred_at
+                .clone()
+                .unwrap_or_else(|| transition.observed_at.clone());
+            let from = transition.from_value();
+            // 状態の比較は build 時に済んでいる (食い違いは Gap として記録)
+            let continues = pos > 0
+                && !self.issues.iter().any(|issue| {
+                    matches!(issue, ChainIssue::Gap { transition_id, .. }
+                        if *transition_id == transition.transition_id)
+                });
+            if !continues {
+                spans.push(StateSpan {
+                    state: transition.from_state.clone(),
+                    value: from,
+                    since: None,
+                    until: None,
+                    entered_by: None,
+                });
+            }
+            if let Some(last) = spans.last_mut() {
+                last.until = Some(at.clone());
+            }
+            spans.push(StateSpan {
+                state: transition.to_state.clone(),
+                value: transition.to_value(),
+                since: Some(at),
+                until: None,
+                entered_by: Some(transition.transition_id.clone()),
+            });
+        }
+        spans
+    }
+}
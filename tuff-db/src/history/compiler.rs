@@ -1,5 +1,7 @@
+use crate::db::signing::{KeyRegistry, SignatureCheck};
 use crate::db::{OpKind, OpLog};
-use crate::models::{Abstract, ManualOverride, Transition, VerificationStatus};
+use crate::history::chain::{ChainIssue, StateSpan, TransitionChain};
+use crate::models::{Abstract, EntityRegistry, ManualOverride, Transition, VerificationStatus};
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 use std::collections::HashMap;
@@ -50,6 +52,28 @@ pub struct TimelineEvent {
     pub override_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user_note: Option<String>,
+    // 署名検証の結果 (検証しない場合は None)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<SignatureCheck>,
+}
+
+/// Op left out of the history because its signature did not verify,
+/// written to `rejected_ops.json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectedEvent {
+    pub op_id: String,
+    pub timestamp: String,
+    pub check: SignatureCheck,
+}
+
+/// Per-topic state chain written to `chains.json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicChain {
+    pub topic_id: String,
+    pub current_state: Option<String>,
+    pub consistent: bool,
+    pub history: Vec<StateSpan>,
+    pub issues: Vec<ChainIssue>,
 }
 
 #[derive(Debug, Clone)]
@@ -73,9 +97,40 @@ struct LatestState {
     subject: String,
 }
 
-pub fn compile(wal_path: impl AsRef<Path>, out_dir: impl AsRef<Path>) -> anyhow::Result<()> {
-    let wal_path = wal_path.as_ref();
-    let out_dir = out_dir.as_ref();
+/// Compiles the WAL into timelines, latest facts and transition chains;
+/// chain states are compared under `entities`' aliases.
+pub fn compile(
+    wal_path: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+    entities: &EntityRegistry,
+) -> anyhow::Result<()> {
+    compile_inner(wal_path.as_ref(), out_dir.as_ref(), None, entities)
+}
+
+/// Like `compile`, but checks every op against `keys`. Rejected ops are kept
+/// out of the history and listed in `rejected_ops.json`; each event records
+/// its check result.
+pub fn compile_verified(
+    wal_path: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+    keys: &KeyRegistry,
+    require_signatures: bool,
+    entities: &EntityRegistry,
+) -> anyhow::Result<()> {
+    compile_inner(
+        wal_path.as_ref(),
+        out_dir.as_ref(),
+        Some((keys, require_signatures)),
+        entities,
+    )
+}
+
+fn compile_inner(
+    wal_path: &Path,
+    out_dir: &Path,
+    verify: Option<(&KeyRegistry, bool)>,
+    entities: &EntityRegistry,
+) -> anyhow::Result<()> {
     fs::create_dir_all(out_dir)?;
 
     let file = File::open(wal_path)?;
@@ -83,6 +138,8 @@ pub fn compile(wal_path: impl AsRef<Path>, out_dir: impl AsRef<Path>) -> anyhow:
 
     let mut events_by_topic: HashMap<String, Vec<RawEvent>> = HashMap::new();
     let mut abstract_topic: HashMap<Uuid, String> = HashMap::new();
+    let mut transitions_by_topic: HashMap<String, Vec<Transition>> = HashMap::new();
+    let mut rejected: Vec<RejectedEvent> = Vec::new();
 
     for line in reader.lines() {
         let line = line?;
@@ -93,16 +150,38 @@ pub fn compile(wal_path: impl AsRef<Path>, out_dir: impl AsRef<Path>) -> anyhow:
             Ok(v) => v,
             Err(_) => continue,
         };
+        let check = verify.map(|(keys, _)| keys.verify_op(&op));
+        if let (Some(check), Some((_, require))) = (check, verify) {
+            if !check.accepted(require) {
+                rejected.push(RejectedEvent {
+                    op_id: op_id_fmt(op.op_id),
+                    timestamp: op.created_at.to_rfc3339(),
+                    check,
+                });
+                continue;
+            }
+        }
         match op.kind {
             OpKind::InsertAbstract { abstract_ } => {
                 let topic_id = topic_id_from_abstract(&abstract_);
                 abstract_topic.insert(abstract_.id.0, topic_id.clone());
-                let (_event, raw) = event_from_abstract(op.op_id, op.created_at, abstract_);
+                let (_event, mut raw) = event_from_abstract(op.op_id, op.created_at, abstract_);
+                raw.event.signature = check;
                 events_by_topic.entry(topic_id).or_default().push(raw);
             }
             OpKind::InsertTransition { transition } => {
-                let topic_id = topic_id_from_transition(&transition);
-                let raw = event_from_transition(op.op_id, op.created_at, transition, topic_id.clone());
+                let topic_id = transition
+                    .abstract_id
+                    .as_ref()
+                    .and_then(|id| abstract_topic.get(&id.0).cloned())
+                    .unwrap_or_else(|| topic_id_from_transition(&transition));
+                transitions_by_topic
+                    .entry(topic_id.clone())
+                    .or_default()
+                    .push((*transition).clone());
+                let mut raw =
+                    event_from_transition(op.op_id, op.created_at, *transition, topic_id.clone());
+                raw.event.signature = check;
                 events_by_topic.entry(topic_id).or_default().push(raw);
             }
             OpKind::AppendOverride { override_ } => {
@@ -111,7 +190,9 @@ pub fn compile(wal_path: impl AsRef<Path>, out_dir: impl AsRef<Path>) -> anyhow:
                     .as_ref()
                     .and_then(|id| abstract_topic.get(&id.0).cloned())
                     .unwrap_or_else(|| "override:unmapped".to_string());
-                let raw = event_from_override(op.op_id, op.created_at, override_, topic_id.clone());
+                let mut raw =
+                    event_from_override(op.op_id, op.created_at, override_, topic_id.clone());
+                raw.event.signature = check;
                 events_by_topic.entry(topic_id).or_default().push(raw);
             }
         }
@@ -137,7 +218,10 @@ pub fn compile(wal_path: impl AsRef<Path>, out_dir: impl AsRef<Path>) -> anyhow:
             events.push(event);
         }
 
-        timelines.push(Timeline { topic_id: topic_id.clone(), events });
+        timelines.push(Timeline {
+            topic_id: topic_id.clone(),
+            events,
+        });
         if let Some(state) = latest_state {
             latest.push(LatestFact {
                 topic_id,
@@ -159,8 +243,27 @@ pub fn compile(wal_path: impl AsRef<Path>, out_dir: impl AsRef<Path>) -> anyhow:
         facts: latest,
     };
 
+    let mut chains: Vec<TopicChain> = transitions_by_topic
+        .into_iter()
+        .map(|(topic_id, transitions)| {
+            let chain = TransitionChain::build(&topic_id, transitions, entities);
+            TopicChain {
+                current_state: chain.current_state().map(str::to_string),
+                consistent: chain.is_consistent(),
+                history: chain.history(),
+                issues: chain.issues,
+                topic_id,
+            }
+        })
+        .collect();
+    chains.sort_by(|a, b| a.topic_id.cmp(&b.topic_id));
+
     write_json(out_dir.join("latest_facts.json"), &latest_facts)?;
     write_json(out_dir.join("timeline.json"), &timelines)?;
+    write_json(out_dir.join("chains.json"), &chains)?;
+    if verify.is_some() {
+        write_json(out_dir.join("rejected_ops.json"), &rejected)?;
+    }
     Ok(())
 }
 
@@ -178,7 +281,11 @@ fn topic_id_from_transition(transition: &Transition) -> String {
     format!("transition:{}", short_hash(&base))
 }
 
-fn event_from_abstract(op_id: Uuid, ts: DateTime<Utc>, abstract_: Abstract) -> (TimelineEvent, RawEvent) {
+fn event_from_abstract(
+    op_id: Uuid,
+    ts: DateTime<Utc>,
+    abstract_: Abstract,
+) -> (TimelineEvent, RawEvent) {
     let op_id_fmt = op_id_fmt(op_id);
     let status = status_mapping(abstract_.verification);
     let event = TimelineEvent {
@@ -191,6 +298,7 @@ fn event_from_abstract(op_id: Uuid, ts: DateTime<Utc>, abstract_: Abstract) -> (
         reason: Some(abstract_.summary.clone()),
         override_id: None,
         user_note: None,
+        signature: None,
     };
     let raw = RawEvent {
         timestamp: ts,
@@ -221,6 +329,7 @@ fn event_from_transition(
         reason: Some(transition.event),
         override_id: None,
         user_note: None,
+        signature: None,
     };
     RawEvent {
         timestamp: ts,
@@ -246,6 +355,7 @@ fn event_from_override(
         reason: None,
         override_id: Some(format!("ovr_{}", short_id(override_.override_id.0))),
         user_note: override_.note,
+        signature: None,
     };
     RawEvent {
         timestamp: ts,
@@ -277,9 +387,10 @@ fn status_mapping(status: VerificationStatus) -> String {
     match status {
         VerificationStatus::White => "VERIFIED".to_string(),
         VerificationStatus::Smoke => "SMOKE".to_string(),
-        VerificationStatus::GrayBlack | VerificationStatus::GrayMid | VerificationStatus::GrayWhite => {
-            "GRAY_*".to_string()
-        }
+        VerificationStatus::Outdated => "OUTDATED".to_string(),
+        VerificationStatus::GrayBlack
+        | VerificationStatus::GrayMid
+        | VerificationStatus::GrayWhite => "GRAY_*".to_string(),
     }
 }
 
@@ -6,11 +6,8 @@ use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::TcpListener;
 use tokio::sync::Mutex;
 
-mod storage;
-mod verifier;
-
-use storage::{RecoveryMode, WalStorage};
-use verifier::{normalize_tag_key, MeaningDb, Verifier};
+use transformer_neo::lightweight::storage::{RecoveryMode, WalStorage};
+use transformer_neo::lightweight::{normalize_tag_key, MeaningDb, Verifier};
 
 fn log_line(msg: &str) {
     println!("{}", msg);
@@ -70,14 +67,18 @@ async fn flush_ai_buffer(
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let wal_path = std::env::var("TUFF_WAL_PATH").unwrap_or_else(|_| "tuff-db-lightweight.wal".to_string());
-    let addr = std::env::var("TUFF_LIGHTWEIGHT_ADDR").unwrap_or_else(|_| "127.0.0.1:8788".to_string());
+    let wal_path =
+        std::env::var("TUFF_WAL_PATH").unwrap_or_else(|_| "tuff-db-lightweight.wal".to_string());
+    let addr =
+        std::env::var("TUFF_LIGHTWEIGHT_ADDR").unwrap_or_else(|_| "127.0.0.1:8788".to_string());
     let recovery_mode = match std::env::var("TUFF_WAL_RECOVERY_MODE") {
         Ok(v) if v.eq_ignore_ascii_case("strict") => RecoveryMode::Strict,
         _ => RecoveryMode::TruncateCorruptedTail,
     };
 
-    let storage = Arc::new(Mutex::new(WalStorage::open(&wal_path, recovery_mode).await?));
+    let storage = Arc::new(Mutex::new(
+        WalStorage::open(&wal_path, recovery_mode).await?,
+    ));
 
     // MeaningDB: env var "TUFF_MEANING_DB" -> "tag=meaning;tag2=meaning2"
     let mut meaning_map = HashMap::new();
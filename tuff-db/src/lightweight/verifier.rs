@@ -3,6 +3,8 @@ use std::fs;
 use std::io::Write;
 use std::path::Path;
 
+use crate::models::{EntityRegistry, SharedEntityRegistry};
+
 pub const TAG_KEY_MAX_LEN: usize = 64;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -56,6 +58,22 @@ fn meaning_matches(mode: MeaningMatchMode, required: &str, payload: &str) -> boo
     }
 }
 
+// 登録済みの実体なら別表記でも一致とみなす
+fn entity_matches(
+    registry: &EntityRegistry,
+    mode: MeaningMatchMode,
+    required: &str,
+    payload: &str,
+) -> bool {
+    let Some(entity) = registry.resolve(required) else {
+        return false;
+    };
+    match mode {
+        MeaningMatchMode::Exact => registry.resolve(payload).is_some_and(|e| e.id == entity.id),
+        MeaningMatchMode::Contains => registry.mentions(payload).iter().any(|e| e.id == entity.id),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TagIndex {
     map: HashMap<String, String>,
@@ -96,6 +114,11 @@ impl MeaningDb {
 
     pub fn from_path(path: impl AsRef<Path>) -> std::io::Result<Self> {
         let content = fs::read_to_string(path)?;
+        Ok(Self::parse(&content))
+    }
+
+    /// Parses `tag=meaning` lines; blank lines and `#` comments are skipped.
+    pub fn parse(content: &str) -> Self {
         let mut map = HashMap::new();
         for line in content.lines() {
             let line = line.trim();
@@ -115,7 +138,7 @@ impl MeaningDb {
                 map.insert(tag.to_string(), meaning.to_string());
             }
         }
-        Ok(Self::new(map))
+        Self::new(map)
     }
 
     pub fn merge(&mut self, raw_meanings: HashMap<String, String>) {
@@ -132,6 +155,10 @@ impl MeaningDb {
     pub fn meaning_for(&self, tag: &str) -> Option<&str> {
         self.tag_index.get(tag)
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.tag_index.iter()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -151,11 +178,31 @@ pub enum LightweightCheckStatus {
 #[derive(Debug, Clone)]
 pub struct LightweightVerifier {
     meaning_db: MeaningDb,
+    entities: Option<SharedEntityRegistry>,
 }
 
 impl LightweightVerifier {
     pub fn new(meaning_db: MeaningDb) -> Self {
-        Self { meaning_db }
+        Self {
+            meaning_db,
+            entities: None,
+        }
+    }
+
+    /// Accept aliases of a registered entity ("Sanae Takaichi" for 高市早苗).
+    pub fn with_entities(mut self, entities: SharedEntityRegistry) -> Self {
+        self.entities = Some(entities);
+        self
+    }
+
+    fn matches(&self, mode: MeaningMatchMode, required: &str, payload: &str) -> bool {
+        if meaning_matches(mode, required, payload) {
+            return true;
+        }
+        self.entities.as_ref().is_some_and(|entities| {
+            let registry = entities.read().expect("entity registry lock");
+            entity_matches(&registry, mode, required, payload)
+        })
     }
 
     pub fn from_sources(path: Option<&Path>, env_pairs: HashMap<String, String>) -> Option<Self> {
@@ -188,7 +235,7 @@ impl LightweightVerifier {
             return LightweightCheckStatus::Unknown;
         };
         let mode = match_mode_for_tag(&normalized_tag);
-        if meaning_matches(mode, required, payload) {
+        if self.matches(mode, required, payload) {
             LightweightCheckStatus::Hit
         } else {
             LightweightCheckStatus::Mismatch
@@ -199,7 +246,7 @@ impl LightweightVerifier {
         let normalized_tag = normalize_tag_key(tag)?;
         let required = self.meaning_db.meaning_for(&normalized_tag)?;
         let mode = match_mode_for_tag(&normalized_tag);
-        if meaning_matches(mode, required, payload) {
+        if self.matches(mode, required, payload) {
             Some(LightweightHit {
                 tag: normalized_tag,
                 required: required.to_string(),
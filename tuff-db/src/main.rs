@@ -3,37 +3,37 @@ use dotenv::dotenv;
 use std::env;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::RwLock;
+use transformer_neo::db::audit::checkpoint_every_from_env;
+use transformer_neo::db::signing::{require_signatures_from_env, signer_from_env, KeyRegistry};
 use transformer_neo::db::TuffEngine;
-use transformer_neo::models::{Claim, VerificationStatus};
+use transformer_neo::lightweight::{LightweightVerifier, MeaningDb};
+use transformer_neo::models::{EntityRegistry, VerificationStatus};
 use transformer_neo::pipeline::{
-    AbstractGenerator, ClaimVerifier, DummyAbstractGenerator, DummySplitter, DummyVerifier,
-    FactFetcher, GapResolver, IngestPipeline, LlmAbstractor, LlmGapResolver, LlmVerifier,
-    WebFetcher,
+    client_from_env, verifier_from_env, AbstractGenerator, AsyncInputSplitter, CompositeFetcher,
+    CredibilityRegistry, DummyAbstractGenerator, DummySplitter, GapResolver, IngestContext,
+    IngestPipeline, LlmAbstractor, LlmClaimSplitter, LlmGapResolver, PromptSet,
 };
-use transformer_neo::pipeline::traits::VerificationResult;
 
-enum Verifier {
-    Dummy(DummyVerifier),
-    Llm(LlmVerifier),
+enum Splitter {
+    Dummy(DummySplitter),
+    Llm(Box<LlmClaimSplitter>),
 }
 
 #[async_trait]
-impl ClaimVerifier for Verifier {
-    async fn verify(
-        &self,
-        fragment: &str,
-        facts: &[transformer_neo::models::RequiredFact],
-    ) -> anyhow::Result<VerificationResult> {
+impl AsyncInputSplitter for Splitter {
+    async fn split(&self, input: &str, ctx: &IngestContext) -> anyhow::Result<Vec<String>> {
         match self {
-            Verifier::Dummy(v) => v.verify(fragment, facts).await,
-            Verifier::Llm(v) => v.verify(fragment, facts).await,
+            Splitter::Dummy(s) => AsyncInputSplitter::split(s, input, ctx).await,
+            Splitter::Llm(s) => s.split(input, ctx).await,
         }
     }
 }
 
 enum Abstractor {
     Dummy(DummyAbstractGenerator),
-    Llm(LlmAbstractor),
+    Llm(Box<LlmAbstractor>),
 }
 
 #[async_trait]
@@ -43,25 +43,15 @@ impl AbstractGenerator for Abstractor {
         fragment: &str,
         facts: &[transformer_neo::models::RequiredFact],
         status: VerificationStatus,
+        ctx: &IngestContext,
     ) -> anyhow::Result<transformer_neo::models::Abstract> {
         match self {
-            Abstractor::Dummy(a) => a.generate(fragment, facts, status).await,
-            Abstractor::Llm(a) => a.generate(fragment, facts, status).await,
+            Abstractor::Dummy(a) => a.generate(fragment, facts, status, ctx).await,
+            Abstractor::Llm(a) => a.generate(fragment, facts, status, ctx).await,
         }
     }
 }
 
-fn valid_api_key(key: &str) -> bool {
-    let trimmed = key.trim();
-    if trimmed.is_empty() {
-        return false;
-    }
-    if trimmed.contains("...") {
-        return false;
-    }
-    true
-}
-
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenv().ok();
@@ -70,70 +60,112 @@ async fn main() -> anyhow::Result<()> {
     fs::create_dir_all(&wal_dir)?;
     let wal_path = wal_dir.join("tuff.wal");
 
-    let engine = TuffEngine::new(
+    // TUFF_ENTITY_PATH=<json> adds entities to the built-in alias registry
+    let entity_path = env::var("TUFF_ENTITY_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| wal_dir.join("entities.json"));
+    let entities = Arc::new(RwLock::new(EntityRegistry::load(&entity_path)?));
+
+    // TUFF_PUBLIC_KEYS=<file> verifies signatures on replay,
+    // TUFF_SIGNING_KEY=<file> signs every new op with this origin's key
+    let keys = KeyRegistry::from_env()?;
+    let signer = signer_from_env(keys.as_ref())?;
+    let mut engine = TuffEngine::new(
         wal_path
             .to_str()
             .ok_or_else(|| anyhow::anyhow!("invalid wal path"))?,
-    ).await?;
-
-    let api_key = env::var("OPENAI_API_KEY").ok();
-    let model = env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-4o".to_string());
-
-    let verifier = match api_key.as_deref() {
-        Some(key) if valid_api_key(key) => Verifier::Llm(LlmVerifier::new(key, &model)),
-        _ => Verifier::Dummy(DummyVerifier),
+    )
+    .await?
+    .with_entities(entities.clone());
+    if let Some(signer) = signer {
+        engine = engine.with_signer(Arc::new(signer));
+    }
+    if let Some(keys) = keys {
+        engine = engine.with_keys(keys, require_signatures_from_env());
+    }
+    // TUFF_CHECKPOINT_EVERY=<n> checkpoints the WAL hash chain (default 100)
+    if let Some(n) = checkpoint_every_from_env() {
+        engine = engine.with_checkpoint_every(n);
+    }
+    let replay = engine.replay().await?;
+    println!(
+        "replayed={} rejected={}",
+        replay.applied,
+        replay.rejected.len()
+    );
+
+    // TUFF_LLM_PROVIDER / OPENAI_* / ANTHROPIC_* (see llm_client::client_from_env)
+    let llm = client_from_env();
+    // TUFF_PROMPT_DIR=<dir> switches the prompt set (default: built-in)
+    let prompts = Arc::new(PromptSet::from_env()?);
+
+    // TUFF_CLAIM_SPLITTER=llm enables LLM claim extraction (default: line splitting)
+    let llm_splitter = env::var("TUFF_CLAIM_SPLITTER")
+        .map(|v| v.trim().eq_ignore_ascii_case("llm"))
+        .unwrap_or(false);
+    let splitter = match &llm {
+        Some(client) if llm_splitter => Splitter::Llm(Box::new(
+            LlmClaimSplitter::new(client.clone()).with_prompts(prompts.clone()),
+        )),
+        _ => Splitter::Dummy(DummySplitter),
     };
 
-    let abstractor = match api_key.as_deref() {
-        Some(key) if valid_api_key(key) => Abstractor::Llm(LlmAbstractor::new(key, &model)),
-        _ => Abstractor::Dummy(DummyAbstractGenerator),
+    let meaning_path = env::var("TUFF_LIGHTWEIGHT_MEANING_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| wal_dir.join("lightweight").join("meaning.db"));
+
+    // TUFF_ENSEMBLE_POLICY / TUFF_RULE_PRECHECK (see ensemble::verifier_from_env);
+    // the meaning DB votes in the ensemble when the file exists
+    let lightweight = if meaning_path.exists() {
+        let verifier = LightweightVerifier::new(MeaningDb::from_path(&meaning_path)?)
+            .with_entities(entities.clone());
+        Some(Arc::new(tokio::sync::RwLock::new(verifier)))
+    } else {
+        None
     };
-
-    let gap_resolver = match api_key.as_deref() {
-        Some(key) if valid_api_key(key) => Some(LlmGapResolver::new(key, &model)),
-        _ => None,
+    let verifier = verifier_from_env(llm.as_ref(), prompts.clone(), lightweight)?;
+
+    let abstractor = match &llm {
+        Some(client) => Abstractor::Llm(Box::new(
+            LlmAbstractor::new(client.clone())
+                .with_prompts(prompts.clone())
+                .with_output_locale(env::var("TUFF_OUTPUT_LOCALE").ok())
+                .with_entities(entities.clone()),
+        )),
+        None => Abstractor::Dummy(DummyAbstractGenerator),
     };
 
-    let fetcher = WebFetcher::new();
+    let gap_resolver = llm.as_ref().map(|client| {
+        Arc::new(
+            LlmGapResolver::new(client.clone())
+                .with_prompts(prompts.clone())
+                .with_entities(entities.clone()),
+        ) as Arc<dyn GapResolver>
+    });
 
     // Run pipeline
     let pipeline = IngestPipeline {
-        splitter: DummySplitter,
-        fetcher: WebFetcher::new(),
+        splitter,
+        fetcher: CompositeFetcher::from_env(&meaning_path)?,
         verifier,
         generator: abstractor,
         db: engine,
+        gap_resolver,
+        credibility: Some(CredibilityRegistry::from_env()?),
+        entities: Some(entities.clone()),
     };
 
     let input = "高市早苗は首相である";
     let ops = pipeline.ingest(input).await?;
     if let Some(outcome) = ops.first() {
         println!("op_id={}", outcome.op.op_id);
+        if let Some(op) = &outcome.transition {
+            println!("[TRANSITION RECORDED] op_id={}", op.op_id);
+        }
     }
 
     let all = pipeline.select_all().await?;
     println!("stored={}", all.len());
 
-    // Gap resolver integration (mock internal state)
-    if let Some(resolver) = gap_resolver {
-        let internal_state = "Current Prime Minister is Shigeru Ishiba";
-        let facts = fetcher.fetch(input).await?;
-        let evidences: Vec<transformer_neo::models::Evidence> =
-            facts.iter().flat_map(|f| f.evidence.clone()).collect();
-
-        let claim = Claim {
-            statement: input.to_string(),
-            sources: Vec::new(),
-        };
-
-        if let Some(transition) = resolver
-            .resolve(&claim, internal_state, &evidences)
-            .await?
-        {
-            let json = serde_json::to_string(&transition)?;
-            println!("[TRANSITION RECORD GENERATED] {}", json);
-        }
-    }
-
     Ok(())
 }
@@ -1,7 +1,7 @@
 use crate::models::claim::Claim;
 use crate::models::ids::{AbstractId, TagGroupId, TopicId};
-use crate::models::verify::VerificationStatus;
-use chrono::{DateTime, Utc};
+use crate::models::verify::{EvidenceStance, VerificationStatus, VerifierVote};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -25,8 +25,29 @@ impl TagBits {
     pub fn to_key(&self) -> String {
         self.canonical().tags.join("|")
     }
+
+    /// Tags that do not name a topic: empty, the dummy generator's, or the
+    /// abstractor's parse-failure fallback.
+    pub fn is_placeholder(&self) -> bool {
+        let key = self.to_key();
+        key.is_empty()
+            || [&DUMMY_TAGS[..], &[FALLBACK_TAG]]
+                .iter()
+                .any(|tags| key == TagBits::from_strs(tags).to_key())
+    }
+
+    fn from_strs(tags: &[&str]) -> TagBits {
+        TagBits {
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
 }
 
+/// Tags of `DummyAbstractGenerator`.
+pub const DUMMY_TAGS: [&str; 2] = ["smoke", "sanity"];
+/// Tag of an abstract whose LLM response could not be parsed.
+pub const FALLBACK_TAG: &str = "UNKNOWN";
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Abstract {
     pub id: AbstractId,
@@ -37,6 +58,20 @@ pub struct Abstract {
     pub summary: String,
     pub verification: VerificationStatus,
     pub created_at: DateTime<Utc>,
+    // 生成に使ったプロンプトセットの版 (LLM 以外は None)
+    #[serde(default)]
+    pub prompt_version: Option<String>,
+    // 判定時のエビデンスごとの立場 (verifier が返した場合のみ)
+    #[serde(default)]
+    pub stances: Vec<EvidenceStance>,
+    // アンサンブル検証時の各 verifier の票
+    #[serde(default)]
+    pub votes: Vec<VerifierVote>,
+    // 裏付けとなったエビデンスの有効期間
+    #[serde(default)]
+    pub valid_from: Option<NaiveDate>,
+    #[serde(default)]
+    pub valid_to: Option<NaiveDate>,
 }
 
 impl Abstract {
@@ -50,6 +85,11 @@ impl Abstract {
             summary: String::new(),
             verification: VerificationStatus::GrayMid,
             created_at: Utc::now(),
+            prompt_version: None,
+            stances: Vec::new(),
+            votes: Vec::new(),
+            valid_from: None,
+            valid_to: None,
         }
     }
 }
@@ -29,3 +29,12 @@ impl AgentIdentity {
         }
     }
 }
+
+/// Ed25519 signature of a record by the key registered for `origin`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Signature {
+    pub origin: String,
+    // 公開鍵の指紋 (SHA-256 先頭16桁)
+    pub key_id: String,
+    pub sig_hex: String,
+}
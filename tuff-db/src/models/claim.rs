@@ -1,4 +1,6 @@
 use crate::models::evidence::Evidence;
+use crate::models::temporal::TemporalQualifier;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
@@ -8,15 +10,59 @@ pub struct SourceRef {
     pub retrieved_at_rfc3339: String,
 }
 
+/// Which fetcher produced a fact, and how much it is trusted relative to others.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FactOrigin {
+    pub fetcher: String,
+    pub weight: f32,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RequiredFact {
     pub key: String,
     pub value: String,
     pub evidence: Vec<Evidence>,
+    #[serde(default)]
+    pub origin: Option<FactOrigin>,
+    // 事実が成り立つ期間 (両端含む, 不明なら None)
+    #[serde(default)]
+    pub valid_from: Option<NaiveDate>,
+    #[serde(default)]
+    pub valid_to: Option<NaiveDate>,
+    // 同じ key の事実を区別する位置 (表の行など)
+    #[serde(default)]
+    pub qualifier: Option<String>,
+}
+
+impl RequiredFact {
+    pub fn new(key: impl Into<String>, value: impl Into<String>, evidence: Vec<Evidence>) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+            evidence,
+            origin: None,
+            valid_from: None,
+            valid_to: None,
+            qualifier: None,
+        }
+    }
+
+    pub fn with_validity(mut self, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Self {
+        self.valid_from = from;
+        self.valid_to = to;
+        self
+    }
+
+    pub fn with_qualifier(mut self, qualifier: impl Into<String>) -> Self {
+        self.qualifier = Some(qualifier.into());
+        self
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Claim {
     pub statement: String,
     pub sources: Vec<SourceRef>,
+    #[serde(default)]
+    pub temporal: TemporalQualifier,
 }
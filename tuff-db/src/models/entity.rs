@@ -0,0 +1,430 @@
+use anyhow::Context;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Person,
+    Office,
+    Country,
+    Organization,
+    Place,
+    #[default]
+    Other,
+}
+
+/// Writing system of a name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Script {
+    Han,
+    Kana,
+    Latin,
+    Mixed,
+}
+
+pub fn script_of(text: &str) -> Script {
+    let mut found: Option<Script> = None;
+    for c in text.chars().filter(|c| c.is_alphanumeric()) {
+        let script = match c {
+            '\u{3040}'..='\u{30FF}' => Script::Kana,
+            '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '々' => Script::Han,
+            _ if c.is_ascii() => Script::Latin,
+            '\u{FF10}'..='\u{FF5A}' => Script::Latin,
+            _ => return Script::Mixed,
+        };
+        match found {
+            None => found = Some(script),
+            Some(prev) if prev != script => return Script::Mixed,
+            _ => {}
+        }
+    }
+    found.unwrap_or(Script::Mixed)
+}
+
+/// A named thing (person, office, country, ...) under one canonical id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Entity {
+    // 正規ID (例: "person:takaichi-sanae")
+    pub id: String,
+    #[serde(default)]
+    pub kind: EntityKind,
+    // 正規名 (タグ・状態値はこの表記に揃える)
+    pub name: String,
+    // 別表記 (日英・略称)
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    // 読み (かな・ローマ字)
+    #[serde(default)]
+    pub readings: Vec<String>,
+}
+
+impl Entity {
+    pub fn new(id: &str, kind: EntityKind, name: &str) -> Self {
+        Self {
+            id: id.trim().to_string(),
+            kind,
+            name: name.trim().to_string(),
+            aliases: Vec::new(),
+            readings: Vec::new(),
+        }
+    }
+
+    pub fn with_aliases(mut self, aliases: &[&str]) -> Self {
+        self.aliases
+            .extend(aliases.iter().map(|a| a.trim().to_string()));
+        self
+    }
+
+    pub fn with_readings(mut self, readings: &[&str]) -> Self {
+        self.readings
+            .extend(readings.iter().map(|r| r.trim().to_string()));
+        self
+    }
+
+    /// Name, aliases and readings.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.name.as_str())
+            .chain(self.aliases.iter().map(String::as_str))
+            .chain(self.readings.iter().map(String::as_str))
+    }
+
+    /// Scripts the entity can be written in.
+    pub fn scripts(&self) -> Vec<Script> {
+        let mut scripts: Vec<Script> = Vec::new();
+        for script in self.names().map(script_of) {
+            if !scripts.contains(&script) {
+                scripts.push(script);
+            }
+        }
+        scripts
+    }
+
+    /// First name written in `script`, if any.
+    pub fn name_in(&self, script: Script) -> Option<&str> {
+        self.names().find(|name| script_of(name) == script)
+    }
+}
+
+/// On-disk form of a registry: entries added or changed on top of the
+/// built-in ones and the built-in ids removed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct EntityFile {
+    #[serde(default)]
+    entities: Vec<Entity>,
+    #[serde(default)]
+    removed: Vec<String>,
+}
+
+impl EntityFile {
+    // 旧形式 (実体の配列のみ) も読む
+    fn read(path: &Path) -> anyhow::Result<Self> {
+        let body = std::fs::read_to_string(path)
+            .with_context(|| format!("entity registry {}", path.display()))?;
+        let value: serde_json::Value = serde_json::from_str(&body)
+            .with_context(|| format!("entity registry {}", path.display()))?;
+        let file = if value.is_array() {
+            EntityFile {
+                entities: serde_json::from_value(value)?,
+                removed: Vec::new(),
+            }
+        } else {
+            serde_json::from_value(value)?
+        };
+        Ok(file)
+    }
+}
+
+/// Entities keyed by every normalized spelling of their names.
+#[derive(Debug, Clone, Default)]
+pub struct EntityRegistry {
+    entities: Vec<Entity>,
+    keys: HashMap<String, usize>,
+}
+
+/// Registry shared between the pipeline components and the bridge.
+pub type SharedEntityRegistry = Arc<RwLock<EntityRegistry>>;
+
+impl EntityRegistry {
+    pub fn new(entities: Vec<Entity>) -> Self {
+        let mut registry = Self::default();
+        for entity in entities {
+            registry.upsert(entity);
+        }
+        registry
+    }
+
+    /// The built-in entries (current and recent Japanese prime ministers, the
+    /// office itself and Japan).
+    pub fn builtin() -> Self {
+        Self::new(vec![
+            Entity::new("person:ishiba-shigeru", EntityKind::Person, "石破茂")
+                .with_aliases(&["Shigeru Ishiba", "石破", "Ishiba"])
+                .with_readings(&["いしばしげる"]),
+            Entity::new("person:takaichi-sanae", EntityKind::Person, "高市早苗")
+                .with_aliases(&["Sanae Takaichi", "高市", "Takaichi"])
+                .with_readings(&["たかいちさなえ"]),
+            Entity::new("person:kishida-fumio", EntityKind::Person, "岸田文雄")
+                .with_aliases(&["Fumio Kishida", "岸田", "Kishida"])
+                .with_readings(&["きしだふみお"]),
+            Entity::new("person:suga-yoshihide", EntityKind::Person, "菅義偉")
+                .with_aliases(&["Yoshihide Suga", "Suga"])
+                .with_readings(&["すがよしひで"]),
+            Entity::new(
+                "office:prime-minister-jp",
+                EntityKind::Office,
+                "内閣総理大臣",
+            )
+            .with_aliases(&[
+                "首相",
+                "総理大臣",
+                "総理",
+                "Prime Minister",
+                "Prime Minister of Japan",
+                "PM",
+            ])
+            .with_readings(&["ないかくそうりだいじん"]),
+            Entity::new("country:jp", EntityKind::Country, "日本")
+                .with_aliases(&["Japan", "日本国"])
+                .with_readings(&["にほん", "にっぽん", "Nippon"]),
+        ])
+    }
+
+    /// Shared built-in registry, used where no registry is configured.
+    pub fn builtin_ref() -> &'static EntityRegistry {
+        static BUILTIN: OnceLock<EntityRegistry> = OnceLock::new();
+        BUILTIN.get_or_init(Self::builtin)
+    }
+
+    /// Only the entities listed in a registry file, without the built-in ones.
+    pub fn from_path(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Ok(Self::new(EntityFile::read(path.as_ref())?.entities))
+    }
+
+    /// Built-in entries overlaid with the file at `path`: its `removed` ids
+    /// are dropped and its entries added (the same id replaces the built-in
+    /// one). A missing file is not an error.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut registry = Self::builtin();
+        if path.exists() {
+            let file = EntityFile::read(path)?;
+            for id in &file.removed {
+                registry.remove(id);
+            }
+            for entity in file.entities {
+                registry.upsert(entity);
+            }
+        }
+        Ok(registry)
+    }
+
+    /// Writes the difference from the built-in registry, so later changes to
+    /// the built-in entries still reach a saved registry.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let builtin = Self::builtin_ref();
+        let file = EntityFile {
+            entities: self
+                .entities
+                .iter()
+                .filter(|entity| builtin.get(&entity.id) != Some(*entity))
+                .cloned()
+                .collect(),
+            removed: builtin
+                .entities
+                .iter()
+                .filter(|entity| self.get(&entity.id).is_none())
+                .map(|entity| entity.id.clone())
+                .collect(),
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&file)? + "\n")?;
+        Ok(())
+    }
+
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Entity> {
+        self.entities.iter().find(|e| e.id == id)
+    }
+
+    /// Adds the entity, or replaces the one with the same id.
+    pub fn upsert(&mut self, entity: Entity) {
+        match self.entities.iter().position(|e| e.id == entity.id) {
+            Some(pos) => self.entities[pos] = entity,
+            None => self.entities.push(entity),
+        }
+        self.reindex();
+    }
+
+    pub fn remove(&mut self, id: &str) -> bool {
+        let before = self.entities.len();
+        self.entities.retain(|e| e.id != id);
+        let removed = self.entities.len() != before;
+        if removed {
+            self.reindex();
+        }
+        removed
+    }
+
+    fn reindex(&mut self) {
+        self.keys.clear();
+        for (pos, entity) in self.entities.iter().enumerate() {
+            for name in entity.names() {
+                let key = entity_key(name);
+                if !key.is_empty() {
+                    self.keys.insert(key, pos);
+                }
+                if let Some(reversed) = reversed_key(name) {
+                    self.keys.entry(reversed).or_insert(pos);
+                }
+            }
+        }
+    }
+
+    /// The entity a name refers to ("Sanae Takaichi", "高市 早苗", "高市氏").
+    pub fn resolve(&self, name: &str) -> Option<&Entity> {
+        self.keys
+            .get(&entity_key(name))
+            .map(|&pos| &self.entities[pos])
+    }
+
+    /// Comparison key: the entity id when known, otherwise the normalized name.
+    pub fn canonical_key(&self, name: &str) -> String {
+        match self.resolve(name) {
+            Some(entity) => entity.id.clone(),
+            None => entity_key(name),
+        }
+    }
+
+    /// Canonical name when known, otherwise the trimmed input.
+    pub fn canonical_name(&self, name: &str) -> String {
+        match self.resolve(name) {
+            Some(entity) => entity.name.clone(),
+            None => name.trim().to_string(),
+        }
+    }
+
+    /// Entities mentioned anywhere in `text`. Latin names must stand as
+    /// whole words; other names are matched with spaces ignored and must not
+    /// be part of a longer kanji or katakana word, unless the neighbour is
+    /// another known name or an honorific (`石破茂首相`, but not `日本銀行`
+    /// or `副総理`).
+    pub fn mentions(&self, text: &str) -> Vec<&Entity> {
+        let spaced = fold(text);
+        let compact: Vec<char> = spaced.chars().filter(|c| !c.is_whitespace()).collect();
+        let known: Vec<Vec<char>> = self
+            .entities
+            .iter()
+            .flat_map(|entity| entity.names())
+            .map(fold)
+            .filter(|name| !name.is_ascii())
+            .map(|name| name.chars().filter(|c| !c.is_whitespace()).collect())
+            .chain(HONORIFICS.iter().map(|h| h.chars().collect()))
+            .collect();
+        self.entities
+            .iter()
+            .filter(|entity| {
+                entity.names().any(|name| {
+                    let name = fold(name);
+                    let name = name.trim();
+                    if name.is_empty() {
+                        return false;
+                    }
+                    if name.is_ascii() {
+                        contains_word(&spaced, name)
+                    } else {
+                        let name: Vec<char> = name.chars().filter(|c| !c.is_whitespace()).collect();
+                        contains_cjk_word(&compact, &name, &known)
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+// 人名の後ろに付く敬称
+const HONORIFICS: &[&str] = &["さん", "氏", "様", "君"];
+
+// 全角英数を半角に、全角空白を空白に寄せて小文字化する
+fn fold(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            '\u{3000}' => ' ',
+            _ => c,
+        })
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Normalized spelling of a name: folded, spaces, `・` and `.` removed,
+/// honorifics dropped.
+pub fn entity_key(name: &str) -> String {
+    let folded: String = fold(name)
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '・' && *c != '.')
+        .collect();
+    for suffix in HONORIFICS {
+        if let Some(stripped) = folded.strip_suffix(suffix) {
+            if stripped.chars().count() >= 2 {
+                return stripped.to_string();
+            }
+        }
+    }
+    folded
+}
+
+// "Shigeru Ishiba" -> "ishibashigeru" (姓名の順序違い)
+fn reversed_key(name: &str) -> Option<String> {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    (words.len() == 2 && name.is_ascii()).then(|| entity_key(&format!("{} {}", words[1], words[0])))
+}
+
+// 漢字・カタカナは語の途中で切れない（「日本銀行」の「日本」は日本ではない）
+fn is_cjk_word_char(c: char) -> bool {
+    matches!(c,
+        '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}'
+        | '々' | '\u{30A1}'..='\u{30FA}' | 'ー')
+}
+
+fn contains_cjk_word(text: &[char], name: &[char], known: &[Vec<char>]) -> bool {
+    if name.is_empty() || name.len() > text.len() {
+        return false;
+    }
+    (0..=text.len() - name.len()).any(|start| {
+        let end = start + name.len();
+        if text[start..end] != *name {
+            return false;
+        }
+        // 隣が別の既知の名前や敬称なら語の境界とみなす
+        let left = start == 0
+            || !is_cjk_word_char(text[start - 1])
+            || known
+                .iter()
+                .any(|k| !k.is_empty() && text[..start].ends_with(k));
+        let right = end == text.len()
+            || !is_cjk_word_char(text[end])
+            || known
+                .iter()
+                .any(|k| !k.is_empty() && text[end..].starts_with(k));
+        left && right
+    })
+}
+
+fn contains_word(haystack: &str, word: &str) -> bool {
+    haystack.match_indices(word).any(|(start, _)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + word.len()..].chars().next();
+        !before.is_some_and(|c| c.is_ascii_alphanumeric())
+            && !after.is_some_and(|c| c.is_ascii_alphanumeric())
+    })
+}
@@ -1,5 +1,7 @@
 use crate::models::Id;
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use url::Url;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -7,6 +9,78 @@ pub struct SourceMeta {
     pub url: Url,
     pub retrieved_at_rfc3339: String,
     pub sha256_hex: String,
+    // 前回取得時のハッシュ (キャッシュが無ければ None)
+    #[serde(default)]
+    pub previous_sha256_hex: Option<String>,
+    // ページ由来のメタデータ (<title>, 公開/更新日時, canonical)
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub published_at: Option<String>,
+    #[serde(default)]
+    pub modified_at: Option<String>,
+    #[serde(default)]
+    pub canonical_url: Option<Url>,
+}
+
+impl SourceMeta {
+    /// Source retrieved now, without page metadata.
+    pub fn new(url: Url, sha256_hex: impl Into<String>) -> Self {
+        Self {
+            url,
+            retrieved_at_rfc3339: Utc::now().to_rfc3339(),
+            sha256_hex: sha256_hex.into(),
+            previous_sha256_hex: None,
+            title: None,
+            published_at: None,
+            modified_at: None,
+            canonical_url: None,
+        }
+    }
+
+    pub fn with_retrieved_at(mut self, rfc3339: &str) -> Self {
+        self.retrieved_at_rfc3339 = rfc3339.to_string();
+        self
+    }
+
+    /// True when the source was seen before and its content hash differs.
+    pub fn content_changed(&self) -> bool {
+        self.previous_sha256_hex
+            .as_deref()
+            .is_some_and(|prev| prev != self.sha256_hex)
+    }
+}
+
+/// Character offsets of a snippet within the cleaned source text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextSpan {
+    pub char_start: usize,
+    pub char_end: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceCategory {
+    Government,
+    Academic,
+    News,
+    Reference,
+    Corporate,
+    Blog,
+    Social,
+    Local,
+    Unknown,
+}
+
+/// Trust assigned to the source domain by the credibility registry.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SourceCredibility {
+    pub domain: String,
+    // 0.0 (信用できない) 〜 1.0 (一次情報)
+    pub trust: f32,
+    pub category: SourceCategory,
+    #[serde(default)]
+    pub bias_note: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -14,4 +88,36 @@ pub struct Evidence {
     pub evidence_id: Id,
     pub source: SourceMeta,
     pub snippet: String,
+    #[serde(default)]
+    pub span: Option<TextSpan>,
+    #[serde(default)]
+    pub credibility: Option<SourceCredibility>,
+}
+
+impl Evidence {
+    pub fn new(source: SourceMeta, snippet: impl Into<String>) -> Self {
+        Self {
+            evidence_id: Id::new(),
+            source,
+            snippet: snippet.into(),
+            span: None,
+            credibility: None,
+        }
+    }
+
+    /// Evidence whose source is just the snippet (hash of the snippet, retrieved now).
+    pub fn from_snippet(url: Url, snippet: &str) -> Self {
+        let sha256_hex = hex::encode(Sha256::digest(snippet.as_bytes()));
+        Self::new(SourceMeta::new(url, sha256_hex), snippet)
+    }
+
+    pub fn with_span(mut self, span: TextSpan) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn with_credibility(mut self, credibility: SourceCredibility) -> Self {
+        self.credibility = Some(credibility);
+        self
+    }
 }
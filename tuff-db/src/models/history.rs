@@ -1,4 +1,4 @@
-use crate::models::{AgentIdentity, Id, IsoDateTime};
+use crate::models::{AgentIdentity, Id, IsoDateTime, Signature, StateValue};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -14,6 +14,12 @@ pub struct Transition {
     pub from_state: String,
     pub to_state: String,
 
+    // 構造化したState (旧WALでは None。比較はこちらを優先)
+    #[serde(default)]
+    pub from: Option<StateValue>,
+    #[serde(default)]
+    pub to: Option<StateValue>,
+
     // 遷移を引き起こしたイベント (例: "General Election 2025")
     pub event: String,
 
@@ -22,6 +28,34 @@ pub struct Transition {
 
     // 根拠となるEvidence ID
     pub evidence_ids: Vec<Id>,
+
+    // 遷移を導いたプロンプトセットの版
+    #[serde(default)]
+    pub prompt_version: Option<String>,
+
+    // 遷移を検出した Abstract (IngestPipeline 経由の場合)
+    #[serde(default)]
+    pub abstract_id: Option<Id>,
+
+    // agent.origin の鍵による署名 (署名対象からは除く)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
+}
+
+impl Transition {
+    /// Typed source state; parsed from `from_state` for older records.
+    pub fn from_value(&self) -> StateValue {
+        self.from
+            .clone()
+            .unwrap_or_else(|| StateValue::parse(&self.from_state))
+    }
+
+    /// Typed target state; parsed from `to_state` for older records.
+    pub fn to_value(&self) -> StateValue {
+        self.to
+            .clone()
+            .unwrap_or_else(|| StateValue::parse(&self.to_state))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
@@ -32,4 +66,6 @@ pub struct ManualOverride {
     pub conversation_id: Option<String>,
     pub abstract_id: Option<Id>,
     pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
 }
@@ -2,18 +2,24 @@ pub mod abstract_;
 pub mod agent;
 pub mod claim;
 pub mod common;
+pub mod entity;
 pub mod evidence;
 pub mod history;
 pub mod ids;
 pub mod output;
+pub mod state;
+pub mod temporal;
 pub mod verify;
 
-pub use abstract_::{Abstract, TagBits};
+pub use abstract_::{Abstract, TagBits, DUMMY_TAGS, FALLBACK_TAG};
 pub use agent::*;
-pub use claim::{Claim, RequiredFact, SourceRef};
+pub use claim::{Claim, FactOrigin, RequiredFact, SourceRef};
 pub use common::{Id, IsoDateTime};
-pub use evidence::{Evidence, SourceMeta};
+pub use entity::{Entity, EntityKind, EntityRegistry, Script, SharedEntityRegistry};
+pub use evidence::{Evidence, SourceCategory, SourceCredibility, SourceMeta, TextSpan};
 pub use history::*;
 pub use ids::{AbstractId, TagGroupId, TopicId};
 pub use output::{OutputGate, OutputPacket};
-pub use verify::VerificationStatus;
+pub use state::{canonical_entity, StateValue};
+pub use temporal::{TemporalQualifier, TemporalScope};
+pub use verify::{EvidenceStance, Stance, VerificationStatus, VerifierVote};
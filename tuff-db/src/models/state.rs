@@ -0,0 +1,166 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::models::entity::{EntityKind, EntityRegistry};
+
+/// A state as a fact: `subject` has `object` in role `predicate`
+/// (日本 / 内閣総理大臣 / 石破茂), with optional qualifiers such as `term`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct StateValue {
+    #[serde(default)]
+    pub subject: String,
+    pub predicate: String,
+    pub object: String,
+    #[serde(default)]
+    pub qualifiers: BTreeMap<String, String>,
+}
+
+impl StateValue {
+    pub fn new(subject: &str, predicate: &str, object: &str) -> Self {
+        Self {
+            subject: subject.trim().to_string(),
+            predicate: predicate.trim().to_string(),
+            object: object.trim().to_string(),
+            qualifiers: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_qualifier(mut self, key: &str, value: &str) -> Self {
+        self.qualifiers
+            .insert(key.trim().to_string(), value.trim().to_string());
+        self
+    }
+
+    /// Reads a free-text state with the built-in registry (see `parse_in`).
+    pub fn parse(text: &str) -> Self {
+        Self::parse_in(text, EntityRegistry::builtin_ref())
+    }
+
+    /// Reads a free-text state: `PM: Ishiba`, `石破茂が内閣総理大臣`,
+    /// `日本の首相は石破茂`, `Ishiba is Prime Minister`.
+    ///
+    /// Either side of が / は / is may be the role: the side naming a known
+    /// office is the predicate (`日本の` before it becomes the subject),
+    /// otherwise the side naming another known entity is the object. Every
+    /// occurrence of the separator is tried, so names containing は or が
+    /// still split at the right place. Text where neither side is known
+    /// becomes the object alone.
+    pub fn parse_in(text: &str, registry: &EntityRegistry) -> Self {
+        let text = text.trim();
+        if let Some((predicate, object)) = text.split_once(':').or_else(|| text.split_once('：')) {
+            return Self::new("", predicate, object);
+        }
+        let splits: Vec<(&str, &str)> = ["が", "は", " is the ", " is "]
+            .iter()
+            .flat_map(|sep| {
+                text.match_indices(sep)
+                    .map(move |(at, _)| (&text[..at], &text[at + sep.len()..]))
+            })
+            .map(|(left, right)| (left.trim(), clean_tail(right)))
+            .filter(|(left, right)| !left.is_empty() && !right.is_empty())
+            .collect();
+        // 役職が分かる分け方を優先し、無ければ既知の実体で判断する
+        for (left, right) in &splits {
+            if let Some((subject, role)) = office_of(right, registry) {
+                return Self::new(subject, role, left);
+            }
+            if let Some((subject, role)) = office_of(left, registry) {
+                return Self::new(subject, role, right);
+            }
+        }
+        for (left, right) in &splits {
+            match (registry.resolve(left), registry.resolve(right)) {
+                (Some(_), None) => return Self::new("", right, left),
+                (None, Some(_)) => return Self::new("", left, right),
+                _ => {}
+            }
+        }
+        Self::new("", "", text)
+    }
+
+    /// Display form kept in `Transition.from_state` / `to_state`.
+    pub fn render(&self) -> String {
+        let mut out = match (self.subject.is_empty(), self.predicate.is_empty()) {
+            (_, true) => self.object.clone(),
+            (true, false) => format!("{}: {}", self.predicate, self.object),
+            (false, false) => format!("{} {}: {}", self.subject, self.predicate, self.object),
+        };
+        if !self.qualifiers.is_empty() {
+            let qualifiers: Vec<String> = self
+                .qualifiers
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect();
+            out.push_str(&format!(" ({})", qualifiers.join(", ")));
+        }
+        out
+    }
+
+    /// Equal after entity normalization. An empty subject or predicate on
+    /// either side matches anything, and only qualifiers present on both
+    /// sides are compared.
+    pub fn same_as_in(&self, other: &StateValue, registry: &EntityRegistry) -> bool {
+        let key = |name: &str| registry.canonical_key(name);
+        let slot = |a: &str, b: &str| a.is_empty() || b.is_empty() || key(a) == key(b);
+        slot(&self.subject, &other.subject)
+            && slot(&self.predicate, &other.predicate)
+            && key(&self.object) == key(&other.object)
+            && self
+                .qualifiers
+                .iter()
+                .all(|(k, value)| other.qualifiers.get(k).is_none_or(|v| key(v) == key(value)))
+    }
+
+    /// Same subject and role, whatever the object: `高市早苗は首相` and
+    /// `石破茂は首相` describe one slot. The predicate must be present on
+    /// both sides; the subject is compared only when both have one.
+    pub fn same_slot_in(&self, other: &StateValue, registry: &EntityRegistry) -> bool {
+        let key = |name: &str| registry.canonical_key(name);
+        !self.predicate.is_empty()
+            && !other.predicate.is_empty()
+            && key(&self.predicate) == key(&other.predicate)
+            && (self.subject.is_empty()
+                || other.subject.is_empty()
+                || key(&self.subject) == key(&other.subject))
+    }
+
+    /// Subject, predicate and object rewritten to their canonical entity names.
+    pub fn canonicalized(&self, registry: &EntityRegistry) -> StateValue {
+        StateValue {
+            subject: registry.canonical_name(&self.subject),
+            predicate: registry.canonical_name(&self.predicate),
+            object: registry.canonical_name(&self.object),
+            qualifiers: self.qualifiers.clone(),
+        }
+    }
+}
+
+// 文末の句点と「である」「です」を落とす
+fn clean_tail(text: &str) -> &str {
+    let text = text.trim().trim_end_matches(['。', '.']);
+    text.strip_suffix("である")
+        .or_else(|| text.strip_suffix("です"))
+        .unwrap_or(text)
+        .trim()
+}
+
+// 役職名 (「日本の首相」なら主語「日本」付き) として登録されていれば返す
+fn office_of<'a>(text: &'a str, registry: &EntityRegistry) -> Option<(&'a str, &'a str)> {
+    let is_office = |name: &str| {
+        registry
+            .resolve(name)
+            .is_some_and(|e| e.kind == EntityKind::Office)
+    };
+    if is_office(text) {
+        return Some(("", text));
+    }
+    let (subject, role) = text.rsplit_once('の')?;
+    (!subject.trim().is_empty() && is_office(role)).then(|| (subject.trim(), role.trim()))
+}
+
+/// Comparison key for an entity name under the built-in registry
+/// ("Shigeru Ishiba", "Ishiba Shigeru", "石破 茂" share one key).
+pub fn canonical_entity(name: &str) -> String {
+    EntityRegistry::builtin_ref().canonical_key(name)
+}
@@ -0,0 +1,23 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Whether a claim speaks about the present or about the past.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemporalScope {
+    Current,
+    Past,
+    #[default]
+    Unspecified,
+}
+
+/// Time reference extracted from a claim ("現在", "was", "2024年時点").
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TemporalQualifier {
+    pub scope: TemporalScope,
+    // 主張が対象とする期間 (日付の記載がある場合のみ, 両端含む)
+    #[serde(default)]
+    pub from: Option<NaiveDate>,
+    #[serde(default)]
+    pub to: Option<NaiveDate>,
+}
@@ -1,12 +1,44 @@
+use crate::models::Id;
 use serde::{Deserialize, Serialize};
 
-#[derive(
-    Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize,
-)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum VerificationStatus {
     Smoke = 0,
-    GrayBlack = 1,
-    GrayMid = 2,
-    GrayWhite = 3,
-    White = 4,
+    // かつては正しかったが現在は成り立たない
+    Outdated = 1,
+    GrayBlack = 2,
+    GrayMid = 3,
+    GrayWhite = 4,
+    White = 5,
+}
+
+/// How one piece of evidence bears on the claim.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stance {
+    Supports,
+    Refutes,
+    Irrelevant,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EvidenceStance {
+    pub evidence_id: Id,
+    pub stance: Stance,
+    // 根拠となる抜粋 (スニペット内に実在するもののみ)
+    #[serde(default)]
+    pub quote: Option<String>,
+}
+
+/// One member's verdict inside an ensemble verifier.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VerifierVote {
+    pub verifier: String,
+    pub status: VerificationStatus,
+    pub confidence: f32,
+    pub weight: f32,
+    pub reason: String,
+    // 判断材料が無く棄権した (集計に含めない)
+    #[serde(default)]
+    pub abstained: bool,
 }
@@ -0,0 +1,157 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Maps raw model confidence to a calibrated probability that the verdict is
+/// right, by linear interpolation over `(raw, calibrated)` points.
+///
+/// An empty table is the identity. Tables are either written by hand or
+/// fitted on labelled verifier runs with `fit`, and stored as TSV.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConfidenceCalibration {
+    points: Vec<(f32, f32)>,
+}
+
+impl ConfidenceCalibration {
+    pub fn identity() -> Self {
+        Self::default()
+    }
+
+    /// Points must lie in `[0, 1]` and be non-decreasing once sorted by raw
+    /// value, so a more confident model never maps to a lower confidence.
+    pub fn from_points(mut points: Vec<(f32, f32)>) -> anyhow::Result<Self> {
+        for (raw, calibrated) in &points {
+            if !(0.0..=1.0).contains(raw) || !(0.0..=1.0).contains(calibrated) {
+                anyhow::bail!("calibration point ({}, {}) outside [0, 1]", raw, calibrated);
+            }
+        }
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        points.dedup_by(|b, a| a.0 == b.0);
+        if points.windows(2).any(|w| w[1].1 < w[0].1) {
+            anyhow::bail!("calibration table is not monotonic");
+        }
+        Ok(Self { points })
+    }
+
+    /// Fit a table from `(raw confidence, verdict was correct)` samples:
+    /// equal-width bins, then pool-adjacent-violators so the result is
+    /// monotonic. Empty bins are skipped.
+    pub fn fit(samples: &[(f32, bool)], bins: usize) -> Self {
+        let bins = bins.max(1);
+        // (sum raw, sum correct, count)
+        let mut acc = vec![(0.0f32, 0.0f32, 0usize); bins];
+        for (raw, correct) in samples {
+            if !raw.is_finite() {
+                continue;
+            }
+            let raw = raw.clamp(0.0, 1.0);
+            let idx = ((raw * bins as f32) as usize).min(bins - 1);
+            acc[idx].0 += raw;
+            acc[idx].1 += if *correct { 1.0 } else { 0.0 };
+            acc[idx].2 += 1;
+        }
+
+        // pool adjacent violators over (mean raw, accuracy, weight)
+        let mut blocks: Vec<(f32, f32, usize)> = Vec::new();
+        for (sum_raw, sum_correct, count) in acc.into_iter().filter(|b| b.2 > 0) {
+            blocks.push((sum_raw, sum_correct, count));
+            while blocks.len() > 1 {
+                let n = blocks.len();
+                let (prev, last) = (blocks[n - 2], blocks[n - 1]);
+                if last.1 / last.2 as f32 >= prev.1 / prev.2 as f32 {
+                    break;
+                }
+                blocks.truncate(n - 2);
+                blocks.push((prev.0 + last.0, prev.1 + last.1, prev.2 + last.2));
+            }
+        }
+
+        let mut points: Vec<(f32, f32)> = blocks
+            .into_iter()
+            .map(|(sum_raw, sum_correct, count)| {
+                (sum_raw / count as f32, sum_correct / count as f32)
+            })
+            .collect();
+        points.dedup_by(|b, a| a.0 == b.0);
+        Self { points }
+    }
+
+    /// `TUFF_CONFIDENCE_CALIBRATION=<file>` loads a table; otherwise identity.
+    pub fn from_env() -> anyhow::Result<Self> {
+        match env::var("TUFF_CONFIDENCE_CALIBRATION") {
+            Ok(path) if !path.trim().is_empty() => Self::load_path(path.trim()),
+            _ => Ok(Self::identity()),
+        }
+    }
+
+    /// One point per line: `raw<TAB>calibrated`. Blank lines and `#`
+    /// comments are ignored.
+    pub fn load_path(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|err| anyhow::anyhow!("calibration {}: {}", path.display(), err))?;
+        Self::load_str(&content)
+    }
+
+    pub fn load_str(content: &str) -> anyhow::Result<Self> {
+        let mut points = Vec::new();
+        for (lineno, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let cols: Vec<&str> = line.split('\t').map(str::trim).collect();
+            let parse = |col: Option<&&str>| col.and_then(|v| v.parse::<f32>().ok());
+            match (parse(cols.first()), parse(cols.get(1))) {
+                (Some(raw), Some(calibrated)) => points.push((raw, calibrated)),
+                _ => anyhow::bail!(
+                    "calibration line {}: expected raw<TAB>calibrated",
+                    lineno + 1
+                ),
+            }
+        }
+        Self::from_points(points)
+    }
+
+    pub fn to_tsv(&self) -> String {
+        let mut out = String::from("# raw\tcalibrated\n");
+        for (raw, calibrated) in &self.points {
+            out.push_str(&format!("{:.4}\t{:.4}\n", raw, calibrated));
+        }
+        out
+    }
+
+    pub fn points(&self) -> &[(f32, f32)] {
+        &self.points
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn apply(&self, raw: f32) -> f32 {
+        let raw = raw.clamp(0.0, 1.0);
+        let (Some(first), Some(last)) = (self.points.first(), self.points.last()) else {
+            return raw;
+        };
+        if raw <= first.0 {
+            return first.1;
+        }
+        if raw >= last.0 {
+            return last.1;
+        }
+        for w in self.points.windows(2) {
+            let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+            if raw <= x1 {
+                return y0 + (y1 - y0) * (raw - x0) / (x1 - x0);
+            }
+        }
+        last.1
+    }
+}
+
+/// Read a model-reported confidence; anything non-finite or outside 0..=1 is
+/// rejected (`85` is not guessed to be a percentage).
+pub fn validate_confidence(raw: f32) -> Option<f32> {
+    (raw.is_finite() && (0.0..=1.0).contains(&raw)).then_some(raw)
+}
@@ -0,0 +1,213 @@
+use crate::models::{FactOrigin, RequiredFact};
+use crate::pipeline::fetch::WebFetcher;
+use crate::pipeline::http_cache::sha256_hex;
+use crate::pipeline::local_fetch::LocalCorpusFetcher;
+use crate::pipeline::meaning_fetch::MeaningDbFetcher;
+use crate::pipeline::politeness::FetchPolicy;
+use crate::pipeline::traits::FactFetcher;
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::path::Path;
+use std::time::Duration;
+
+struct Member {
+    name: String,
+    fetcher: Box<dyn FactFetcher>,
+    weight: f32,
+    timeout: Duration,
+}
+
+/// Fans a fragment out to several fetchers and merges their facts.
+///
+/// Members run concurrently, each under its own timeout; a failing or slow
+/// member is logged and skipped. Facts are tagged with a `FactOrigin`, ordered
+/// by member weight, and evidence seen twice (same source hash and snippet)
+/// is kept only on the higher-weighted fact.
+#[derive(Default)]
+pub struct CompositeFetcher {
+    members: Vec<Member>,
+}
+
+impl CompositeFetcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_source(
+        mut self,
+        name: &str,
+        fetcher: Box<dyn FactFetcher>,
+        weight: f32,
+        timeout: Duration,
+    ) -> Self {
+        self.members.push(Member {
+            name: name.to_string(),
+            fetcher,
+            weight,
+            timeout,
+        });
+        self
+    }
+
+    /// Standard source set: meaning DB, `TUFF_LOCAL_CORPUS` (if set) and the web
+    /// (unless `TUFF_WEB_FETCH=0`). Weights come from
+    /// `TUFF_SOURCE_WEIGHTS="meaning_db=1.0;local=0.9;web=0.8"`, timeouts from
+    /// `TUFF_FETCH_SOURCE_TIMEOUTS_MS="meaning_db=500;web=12000"`; a source not
+    /// listed there uses `TUFF_FETCH_SOURCE_TIMEOUT_MS` (default: `source_timeout`
+    /// of the environment's `FetchPolicy`).
+    pub fn from_env(meaning_path: &Path) -> anyhow::Result<Self> {
+        let mut weights: HashMap<String, f32> = HashMap::from([
+            ("meaning_db".to_string(), 1.0),
+            ("local".to_string(), 0.9),
+            ("web".to_string(), 0.8),
+        ]);
+        weights.extend(named_values::<f32>("TUFF_SOURCE_WEIGHTS"));
+        let default_timeout = env::var("TUFF_FETCH_SOURCE_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(Duration::from_millis)
+            .unwrap_or_else(|| source_timeout(&FetchPolicy::from_env()));
+        let timeouts = named_values::<u64>("TUFF_FETCH_SOURCE_TIMEOUTS_MS");
+        let timeout = |name: &str| {
+            timeouts
+                .get(name)
+                .map(|ms| Duration::from_millis(*ms))
+                .unwrap_or(default_timeout)
+        };
+
+        let mut fetcher = Self::new().with_source(
+            "meaning_db",
+            Box::new(MeaningDbFetcher::new(meaning_path)),
+            weights["meaning_db"],
+            timeout("meaning_db"),
+        );
+        if let Ok(dir) = env::var("TUFF_LOCAL_CORPUS") {
+            if !dir.trim().is_empty() {
+                fetcher = fetcher.with_source(
+                    "local",
+                    Box::new(LocalCorpusFetcher::from_dir(dir.trim())?),
+                    weights["local"],
+                    timeout("local"),
+                );
+            }
+        }
+        let web_enabled = env::var("TUFF_WEB_FETCH")
+            .map(|v| v.trim() != "0")
+            .unwrap_or(true);
+        if web_enabled {
+            fetcher = fetcher.with_source(
+                "web",
+                Box::new(WebFetcher::new()),
+                weights["web"],
+                timeout("web"),
+            );
+        }
+        Ok(fetcher)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub fn timeout_of(&self, name: &str) -> Option<Duration> {
+        self.members
+            .iter()
+            .find(|m| m.name == name)
+            .map(|m| m.timeout)
+    }
+
+    /// Longest member timeout, i.e. how long one `fetch` may take.
+    pub fn max_timeout(&self) -> Duration {
+        self.members
+            .iter()
+            .map(|m| m.timeout)
+            .max()
+            .unwrap_or_default()
+    }
+
+    async fn fetch_member(member: &Member, fragment: &str) -> Vec<RequiredFact> {
+        match tokio::time::timeout(member.timeout, member.fetcher.fetch(fragment)).await {
+            Ok(Ok(facts)) => facts,
+            Ok(Err(err)) => {
+                eprintln!("composite: {} failed: {}", member.name, err);
+                Vec::new()
+            }
+            Err(_) => {
+                eprintln!(
+                    "composite: {} timed out after {:?}",
+                    member.name, member.timeout
+                );
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// `name=value` pairs from a `;`-separated env var; unparsable items are skipped.
+fn named_values<T: std::str::FromStr>(key: &str) -> HashMap<String, T> {
+    let mut values = HashMap::new();
+    if let Ok(raw) = env::var(key) {
+        for item in raw.split(';') {
+            let mut parts = item.splitn(2, '=');
+            if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+                if let Ok(v) = value.trim().parse::<T>() {
+                    values.insert(name.trim().to_string(), v);
+                }
+            }
+        }
+    }
+    values
+}
+
+/// Per-member timeout that never cuts off a web fetch the policy still allows:
+/// robots.txt and the page may each take `policy.timeout`, plus one throttle wait.
+pub fn source_timeout(policy: &FetchPolicy) -> Duration {
+    policy.timeout * 2 + policy.min_interval
+}
+
+#[async_trait]
+impl FactFetcher for CompositeFetcher {
+    async fn fetch(&self, fragment: &str) -> anyhow::Result<Vec<RequiredFact>> {
+        let results = join_all(
+            self.members
+                .iter()
+                .map(|member| Self::fetch_member(member, fragment)),
+        )
+        .await;
+
+        let mut tagged: Vec<(usize, RequiredFact)> = Vec::new();
+        for (idx, facts) in results.into_iter().enumerate() {
+            let member = &self.members[idx];
+            for mut fact in facts {
+                fact.origin = Some(FactOrigin {
+                    fetcher: member.name.clone(),
+                    weight: member.weight,
+                });
+                tagged.push((idx, fact));
+            }
+        }
+        // stable: ties keep registration order
+        tagged.sort_by(|a, b| {
+            self.members[b.0]
+                .weight
+                .total_cmp(&self.members[a.0].weight)
+        });
+
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut merged = Vec::new();
+        for (_, mut fact) in tagged {
+            let had_evidence = !fact.evidence.is_empty();
+            fact.evidence.retain(|e| {
+                let key = format!("{}\t{}", e.source.sha256_hex, e.snippet.trim());
+                seen.insert(sha256_hex(key.as_bytes()))
+            });
+            if had_evidence && fact.evidence.is_empty() {
+                continue;
+            }
+            merged.push(fact);
+        }
+        Ok(merged)
+    }
+}
@@ -0,0 +1,235 @@
+use crate::models::{Evidence, RequiredFact, SourceCategory, SourceCredibility};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+use url::Url;
+
+/// Trust for sources the registry knows nothing about.
+pub const DEFAULT_TRUST: f32 = 0.4;
+
+const BUILTIN: &[(&str, f32, SourceCategory, Option<&str>)] = &[
+    ("kantei.go.jp", 0.95, SourceCategory::Government, None),
+    ("go.jp", 0.9, SourceCategory::Government, None),
+    ("lg.jp", 0.85, SourceCategory::Government, None),
+    ("gov", 0.9, SourceCategory::Government, None),
+    ("ac.jp", 0.8, SourceCategory::Academic, None),
+    ("edu", 0.8, SourceCategory::Academic, None),
+    ("nhk.or.jp", 0.75, SourceCategory::News, None),
+    (
+        "wikipedia.org",
+        0.6,
+        SourceCategory::Reference,
+        Some("editable by anyone; check the cited sources"),
+    ),
+    ("hatenablog.com", 0.25, SourceCategory::Blog, None),
+    ("note.com", 0.25, SourceCategory::Blog, None),
+    ("blogspot.com", 0.25, SourceCategory::Blog, None),
+    ("x.com", 0.15, SourceCategory::Social, None),
+    ("twitter.com", 0.15, SourceCategory::Social, None),
+];
+
+#[derive(Clone, Debug)]
+struct Entry {
+    trust: f32,
+    category: SourceCategory,
+    bias_note: Option<String>,
+}
+
+/// Domain → trust score, category and optional bias note.
+///
+/// Lookups walk from the full host up to its parent domains, so an entry for
+/// `go.jp` covers `www.kantei.go.jp` unless a more specific entry exists.
+#[derive(Clone, Debug, Default)]
+pub struct CredibilityRegistry {
+    entries: HashMap<String, Entry>,
+}
+
+impl CredibilityRegistry {
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        for (domain, trust, category, note) in BUILTIN {
+            registry.insert(domain, *trust, *category, note.map(str::to_string));
+        }
+        registry
+    }
+
+    /// Built-in entries, overridden by `TUFF_SOURCE_CREDIBILITY=<file>` when set.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let mut registry = Self::builtin();
+        if let Ok(path) = env::var("TUFF_SOURCE_CREDIBILITY") {
+            if !path.trim().is_empty() {
+                registry.load_path(path.trim())?;
+            }
+        }
+        Ok(registry)
+    }
+
+    /// One entry per line: `domain<TAB>trust<TAB>category[<TAB>bias note]`.
+    /// Blank lines and `#` comments are ignored.
+    pub fn load_path(&mut self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let content = fs::read_to_string(path)?;
+        self.load_str(&content)
+    }
+
+    pub fn load_str(&mut self, content: &str) -> anyhow::Result<()> {
+        for (lineno, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let cols: Vec<&str> = line.split('\t').map(str::trim).collect();
+            if cols.len() < 3 {
+                anyhow::bail!(
+                    "credibility line {}: expected domain, trust, category",
+                    lineno + 1
+                );
+            }
+            let trust: f32 = cols[1].parse().map_err(|_| {
+                anyhow::anyhow!("credibility line {}: bad trust {:?}", lineno + 1, cols[1])
+            })?;
+            let category = parse_category(cols[2]);
+            let note = cols.get(3).filter(|n| !n.is_empty()).map(|n| n.to_string());
+            self.insert(cols[0], trust, category, note);
+        }
+        Ok(())
+    }
+
+    pub fn insert(
+        &mut self,
+        domain: &str,
+        trust: f32,
+        category: SourceCategory,
+        bias_note: Option<String>,
+    ) {
+        self.entries.insert(
+            normalize_domain(domain),
+            Entry {
+                trust: trust.clamp(0.0, 1.0),
+                category,
+                bias_note,
+            },
+        );
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn lookup(&self, url: &Url) -> SourceCredibility {
+        match url.scheme() {
+            // 承認済みの意味DB
+            "tuff-meaning" => {
+                return SourceCredibility {
+                    domain: url.to_string(),
+                    trust: 0.95,
+                    category: SourceCategory::Local,
+                    bias_note: None,
+                }
+            }
+            "file" => {
+                // 文書ごとに別ソースとして数える
+                return SourceCredibility {
+                    domain: url.to_string(),
+                    trust: 0.7,
+                    category: SourceCategory::Local,
+                    bias_note: None,
+                };
+            }
+            _ => {}
+        }
+
+        let host = normalize_domain(url.host_str().unwrap_or_default());
+        let mut candidate = host.as_str();
+        loop {
+            if let Some(entry) = self.entries.get(candidate) {
+                return SourceCredibility {
+                    domain: candidate.to_string(),
+                    trust: entry.trust,
+                    category: entry.category,
+                    bias_note: entry.bias_note.clone(),
+                };
+            }
+            match candidate.split_once('.') {
+                Some((_, parent)) => candidate = parent,
+                None => break,
+            }
+        }
+        SourceCredibility {
+            domain: host,
+            trust: DEFAULT_TRUST,
+            category: SourceCategory::Unknown,
+            bias_note: None,
+        }
+    }
+
+    /// Fill `Evidence::credibility` for every piece of evidence.
+    pub fn annotate(&self, facts: &mut [RequiredFact]) {
+        for fact in facts {
+            for evidence in &mut fact.evidence {
+                evidence.credibility = Some(self.lookup(&evidence.source.url));
+            }
+        }
+    }
+}
+
+fn normalize_domain(raw: &str) -> String {
+    let lower = raw.trim().trim_end_matches('.').to_lowercase();
+    lower
+        .strip_prefix("www.")
+        .map(str::to_string)
+        .unwrap_or(lower)
+}
+
+fn parse_category(raw: &str) -> SourceCategory {
+    match raw.trim().to_lowercase().as_str() {
+        "government" | "gov" => SourceCategory::Government,
+        "academic" | "edu" => SourceCategory::Academic,
+        "news" => SourceCategory::News,
+        "reference" | "encyclopedia" => SourceCategory::Reference,
+        "corporate" | "company" => SourceCategory::Corporate,
+        "blog" => SourceCategory::Blog,
+        "social" | "sns" => SourceCategory::Social,
+        "local" => SourceCategory::Local,
+        _ => SourceCategory::Unknown,
+    }
+}
+
+pub fn evidence_trust(evidence: &Evidence) -> f32 {
+    evidence
+        .credibility
+        .as_ref()
+        .map(|c| c.trust)
+        .unwrap_or(DEFAULT_TRUST)
+}
+
+/// Credibility-weighted amount of evidence: the best trust per domain, summed.
+/// Several passages from one site count once, so one government page (0.95)
+/// outweighs three blog posts (3 × 0.25).
+pub fn evidence_weight(facts: &[RequiredFact]) -> f32 {
+    let mut best: HashMap<String, f32> = HashMap::new();
+    for evidence in facts.iter().flat_map(|f| f.evidence.iter()) {
+        let domain = evidence
+            .credibility
+            .as_ref()
+            .map(|c| c.domain.clone())
+            .unwrap_or_else(|| {
+                evidence
+                    .source
+                    .url
+                    .host_str()
+                    .unwrap_or_default()
+                    .to_string()
+            });
+        let trust = evidence_trust(evidence);
+        let slot = best.entry(domain).or_insert(0.0);
+        if trust > *slot {
+            *slot = trust;
+        }
+    }
+    best.values().sum()
+}
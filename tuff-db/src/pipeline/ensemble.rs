@@ -0,0 +1,458 @@
+use crate::lightweight::{LightweightCheckStatus, LightweightVerifier};
+use crate::models::{EvidenceStance, RequiredFact, VerificationStatus, VerifierVote};
+use crate::pipeline::calibration::ConfidenceCalibration;
+use crate::pipeline::llm_client::LlmClient;
+use crate::pipeline::llm_verifier::LlmVerifier;
+use crate::pipeline::mock::DummyVerifier;
+use crate::pipeline::prompts::PromptSet;
+use crate::pipeline::rule_verifier::{PrecheckVerifier, RuleVerifier};
+use crate::pipeline::traits::{ClaimVerifier, IngestContext, VerificationResult};
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// How member votes become one verdict.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnsemblePolicy {
+    /// Status with the largest total member weight; ties give GRAY_MID.
+    Majority,
+    /// Lowest status any member reported.
+    MostPessimistic,
+    /// Statuses on a -1 (SMOKE) .. 1 (WHITE) scale, averaged by
+    /// weight x confidence.
+    WeightedConfidence,
+}
+
+impl EnsemblePolicy {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "majority" => Some(Self::Majority),
+            "pessimistic" | "most_pessimistic" => Some(Self::MostPessimistic),
+            "weighted" | "weighted_confidence" => Some(Self::WeightedConfidence),
+            _ => None,
+        }
+    }
+
+    /// `TUFF_ENSEMBLE_POLICY=majority|pessimistic|weighted`; unset disables
+    /// the ensemble.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        match env::var("TUFF_ENSEMBLE_POLICY") {
+            Ok(raw) if !raw.trim().is_empty() => Self::parse(&raw)
+                .map(Some)
+                .ok_or_else(|| anyhow::anyhow!("TUFF_ENSEMBLE_POLICY: unknown policy {:?}", raw)),
+            _ => Ok(None),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Majority => "majority",
+            Self::MostPessimistic => "most-pessimistic",
+            Self::WeightedConfidence => "weighted",
+        }
+    }
+}
+
+/// Member weights from `TUFF_ENSEMBLE_WEIGHTS="llm=1.0;lightweight=0.5"`.
+pub fn ensemble_weights_from_env() -> HashMap<String, f32> {
+    let mut weights = HashMap::new();
+    if let Ok(raw) = env::var("TUFF_ENSEMBLE_WEIGHTS") {
+        for item in raw.split(';') {
+            let mut parts = item.splitn(2, '=');
+            if let (Some(name), Some(weight)) = (parts.next(), parts.next()) {
+                if let Ok(w) = weight.trim().parse::<f32>() {
+                    weights.insert(name.trim().to_string(), w);
+                }
+            }
+        }
+    }
+    weights
+}
+
+struct Member {
+    name: String,
+    verifier: Box<dyn ClaimVerifier>,
+    weight: f32,
+}
+
+/// Runs several verifiers on the same fragment and combines their verdicts.
+///
+/// Members run concurrently. A member that fails, or reports confidence 0,
+/// abstains: its vote is recorded but not counted. When counted votes land
+/// on both sides (WHITE/GRAY_WHITE vs SMOKE/GRAY_BLACK) the result is forced
+/// to GRAY_MID unless `with_gray_on_disagreement(false)`. Every vote is
+/// returned in `VerificationResult.votes`.
+pub struct EnsembleVerifier {
+    members: Vec<Member>,
+    policy: EnsemblePolicy,
+    gray_on_disagreement: bool,
+}
+
+impl EnsembleVerifier {
+    pub fn new(policy: EnsemblePolicy) -> Self {
+        Self {
+            members: Vec::new(),
+            policy,
+            gray_on_disagreement: true,
+        }
+    }
+
+    pub fn with_member(
+        mut self,
+        name: &str,
+        verifier: Box<dyn ClaimVerifier>,
+        weight: f32,
+    ) -> Self {
+        self.members.push(Member {
+            name: name.to_string(),
+            verifier,
+            weight,
+        });
+        self
+    }
+
+    pub fn with_gray_on_disagreement(mut self, enabled: bool) -> Self {
+        self.gray_on_disagreement = enabled;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    fn combine(&self, counted: &[&VerifierVote]) -> (VerificationStatus, f32, String) {
+        match self.policy {
+            EnsemblePolicy::Majority => {
+                let mut totals: Vec<(VerificationStatus, f32)> = Vec::new();
+                for vote in counted {
+                    match totals.iter_mut().find(|(s, _)| *s == vote.status) {
+                        Some((_, total)) => *total += vote.weight,
+                        None => totals.push((vote.status, vote.weight)),
+                    }
+                }
+                totals.sort_by(|a, b| b.1.total_cmp(&a.1));
+                let (winner, top) = totals[0];
+                if totals
+                    .get(1)
+                    .is_some_and(|(_, w)| (top - w).abs() <= f32::EPSILON)
+                {
+                    return (
+                        VerificationStatus::GrayMid,
+                        mean_confidence(counted),
+                        "tie".to_string(),
+                    );
+                }
+                let winners: Vec<&VerifierVote> = counted
+                    .iter()
+                    .copied()
+                    .filter(|v| v.status == winner)
+                    .collect();
+                let voters: f32 = counted.iter().map(|v| v.weight).sum();
+                (
+                    winner,
+                    mean_confidence(&winners),
+                    format!("{:.1}/{:.1}", top, voters),
+                )
+            }
+            EnsemblePolicy::MostPessimistic => {
+                let worst = counted
+                    .iter()
+                    .min_by(|a, b| {
+                        a.status
+                            .cmp(&b.status)
+                            .then(b.confidence.total_cmp(&a.confidence))
+                    })
+                    .expect("counted votes");
+                (worst.status, worst.confidence, worst.verifier.clone())
+            }
+            EnsemblePolicy::WeightedConfidence => {
+                let mass: f32 = counted.iter().map(|v| v.weight * v.confidence).sum();
+                let weight: f32 = counted.iter().map(|v| v.weight).sum();
+                if mass <= f32::EPSILON || weight <= f32::EPSILON {
+                    return (VerificationStatus::GrayMid, 0.0, "no weight".to_string());
+                }
+                let score = counted
+                    .iter()
+                    .map(|v| v.weight * v.confidence * status_score(v.status))
+                    .sum::<f32>()
+                    / mass;
+                (
+                    status_from_score(score),
+                    mass / weight,
+                    format!("score {:.2}", score),
+                )
+            }
+        }
+    }
+}
+
+// SMOKE -1.0 .. WHITE 1.0
+fn status_score(status: VerificationStatus) -> f32 {
+    match status {
+        VerificationStatus::Smoke => -1.0,
+        VerificationStatus::Outdated | VerificationStatus::GrayBlack => -0.5,
+        VerificationStatus::GrayMid => 0.0,
+        VerificationStatus::GrayWhite => 0.5,
+        VerificationStatus::White => 1.0,
+    }
+}
+
+fn status_from_score(score: f32) -> VerificationStatus {
+    if score >= 0.75 {
+        VerificationStatus::White
+    } else if score >= 0.25 {
+        VerificationStatus::GrayWhite
+    } else if score > -0.25 {
+        VerificationStatus::GrayMid
+    } else if score > -0.75 {
+        VerificationStatus::GrayBlack
+    } else {
+        VerificationStatus::Smoke
+    }
+}
+
+fn mean_confidence(votes: &[&VerifierVote]) -> f32 {
+    let weight: f32 = votes.iter().map(|v| v.weight).sum();
+    if weight <= f32::EPSILON {
+        return 0.0;
+    }
+    votes.iter().map(|v| v.weight * v.confidence).sum::<f32>() / weight
+}
+
+fn summarize(votes: &[&VerifierVote]) -> String {
+    votes
+        .iter()
+        .map(|v| format!("{}={:?}", v.verifier, v.status))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[async_trait]
+impl ClaimVerifier for EnsembleVerifier {
+    async fn verify(
+        &self,
+        fragment: &str,
+        facts: &[RequiredFact],
+        ctx: &IngestContext,
+    ) -> anyhow::Result<VerificationResult> {
+        let results = join_all(
+            self.members
+                .iter()
+                .map(|member| member.verifier.verify(fragment, facts, ctx)),
+        )
+        .await;
+
+        let mut votes = Vec::new();
+        let mut stances: Vec<EvidenceStance> = Vec::new();
+        for (member, result) in self.members.iter().zip(results) {
+            match result {
+                Ok(result) => {
+                    for stance in result.stances {
+                        if !stances.iter().any(|s| s.evidence_id == stance.evidence_id) {
+                            stances.push(stance);
+                        }
+                    }
+                    votes.push(VerifierVote {
+                        verifier: member.name.clone(),
+                        status: result.status,
+                        confidence: result.confidence,
+                        weight: member.weight,
+                        reason: result.reason,
+                        abstained: result.confidence <= 0.0,
+                    });
+                }
+                Err(err) => {
+                    eprintln!("ensemble: {} failed: {}", member.name, err);
+                    votes.push(VerifierVote {
+                        verifier: member.name.clone(),
+                        status: VerificationStatus::GrayMid,
+                        confidence: 0.0,
+                        weight: member.weight,
+                        reason: format!("error: {}", err),
+                        abstained: true,
+                    });
+                }
+            }
+        }
+
+        let counted: Vec<&VerifierVote> = votes
+            .iter()
+            .filter(|v| !v.abstained && v.weight > 0.0)
+            .collect();
+        let (status, confidence, reason) = if counted.is_empty() {
+            (
+                VerificationStatus::GrayMid,
+                0.4,
+                "ensemble: no verifier voted".to_string(),
+            )
+        } else {
+            let supports = counted
+                .iter()
+                .any(|v| v.status > VerificationStatus::GrayMid);
+            let refutes = counted
+                .iter()
+                .any(|v| v.status < VerificationStatus::GrayMid);
+            if supports && refutes && self.gray_on_disagreement {
+                let low = counted
+                    .iter()
+                    .map(|v| v.confidence)
+                    .fold(f32::INFINITY, f32::min);
+                (
+                    VerificationStatus::GrayMid,
+                    low,
+                    format!("ensemble disagreement: {}", summarize(&counted)),
+                )
+            } else {
+                let (status, confidence, detail) = self.combine(&counted);
+                let lead = counted
+                    .iter()
+                    .filter(|v| v.status == status)
+                    .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+                    .map(|v| format!("; {}: {}", v.verifier, v.reason))
+                    .unwrap_or_default();
+                (
+                    status,
+                    confidence,
+                    format!("ensemble {} ({}){}", self.policy.label(), detail, lead),
+                )
+            }
+        };
+
+        Ok(VerificationResult {
+            status,
+            confidence: confidence.clamp(0.0, 1.0),
+            reason,
+            stances,
+            votes,
+        })
+    }
+}
+
+/// Meaning-DB fast path as an ensemble member: a tag hit is WHITE, a
+/// mismatch SMOKE, anything the DB does not know abstains.
+pub struct LightweightClaimVerifier {
+    inner: Arc<RwLock<LightweightVerifier>>,
+}
+
+impl LightweightClaimVerifier {
+    pub fn new(inner: Arc<RwLock<LightweightVerifier>>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl ClaimVerifier for LightweightClaimVerifier {
+    async fn verify(
+        &self,
+        fragment: &str,
+        _facts: &[RequiredFact],
+        _ctx: &IngestContext,
+    ) -> anyhow::Result<VerificationResult> {
+        let (status, confidence, reason) = match self.inner.read().await.check_fragment(fragment) {
+            LightweightCheckStatus::Hit => (VerificationStatus::White, 0.9, "meaning db hit"),
+            LightweightCheckStatus::Mismatch => {
+                (VerificationStatus::Smoke, 0.9, "meaning db mismatch")
+            }
+            LightweightCheckStatus::Unknown => {
+                (VerificationStatus::GrayMid, 0.0, "not in meaning db")
+            }
+        };
+        Ok(VerificationResult {
+            status,
+            confidence,
+            reason: reason.to_string(),
+            stances: Vec::new(),
+            votes: Vec::new(),
+        })
+    }
+}
+
+/// Verifier chosen by [`verifier_from_env`].
+pub enum ConfiguredVerifier {
+    Dummy(DummyVerifier),
+    Llm(Box<LlmVerifier>),
+    Ensemble(Box<EnsembleVerifier>),
+    Precheck(Box<PrecheckVerifier>),
+}
+
+impl ConfiguredVerifier {
+    pub fn is_ensemble(&self) -> bool {
+        matches!(self, Self::Ensemble(_))
+    }
+}
+
+#[async_trait]
+impl ClaimVerifier for ConfiguredVerifier {
+    async fn verify(
+        &self,
+        fragment: &str,
+        facts: &[RequiredFact],
+        ctx: &IngestContext,
+    ) -> anyhow::Result<VerificationResult> {
+        match self {
+            Self::Dummy(v) => v.verify(fragment, facts, ctx).await,
+            Self::Llm(v) => v.verify(fragment, facts, ctx).await,
+            Self::Ensemble(v) => v.verify(fragment, facts, ctx).await,
+            Self::Precheck(v) => v.verify(fragment, facts, ctx).await,
+        }
+    }
+}
+
+/// Builds the verifier both binaries use.
+///
+/// `TUFF_ENSEMBLE_POLICY` votes the date/number rules, the LLM verifier and
+/// `lightweight` (the meaning DB) against each other (weights:
+/// `TUFF_ENSEMBLE_WEIGHTS`). Otherwise `TUFF_RULE_PRECHECK=1` runs the rules
+/// before the LLM verifier. Without an LLM client the fallback is
+/// `DummyVerifier`.
+pub fn verifier_from_env(
+    llm: Option<&Arc<dyn LlmClient>>,
+    prompts: Arc<PromptSet>,
+    lightweight: Option<Arc<RwLock<LightweightVerifier>>>,
+) -> anyhow::Result<ConfiguredVerifier> {
+    let llm_verifier = match llm {
+        Some(client) => Some(
+            LlmVerifier::new(client.clone())
+                .with_prompts(prompts)
+                .with_calibration(ConfidenceCalibration::from_env()?),
+        ),
+        None => None,
+    };
+    let rule_precheck = env::var("TUFF_RULE_PRECHECK")
+        .map(|v| v.trim() == "1")
+        .unwrap_or(false);
+    Ok(match (EnsemblePolicy::from_env()?, llm_verifier) {
+        (Some(policy), llm_verifier) => {
+            let weights = ensemble_weights_from_env();
+            let weight = |name: &str| weights.get(name).copied().unwrap_or(1.0);
+            let mut ensemble = EnsembleVerifier::new(policy).with_member(
+                "rule",
+                Box::new(RuleVerifier::new()),
+                weight("rule"),
+            );
+            if let Some(v) = llm_verifier {
+                ensemble = ensemble.with_member("llm", Box::new(v), weight("llm"));
+            }
+            if let Some(lw) = lightweight {
+                ensemble = ensemble.with_member(
+                    "lightweight",
+                    Box::new(LightweightClaimVerifier::new(lw)),
+                    weight("lightweight"),
+                );
+            }
+            ConfiguredVerifier::Ensemble(Box::new(ensemble))
+        }
+        (None, Some(v)) if rule_precheck => ConfiguredVerifier::Precheck(Box::new(
+            PrecheckVerifier::new(RuleVerifier::new(), Box::new(v)),
+        )),
+        (None, Some(v)) => ConfiguredVerifier::Llm(Box::new(v)),
+        (None, None) => ConfiguredVerifier::Dummy(DummyVerifier),
+    })
+}
@@ -0,0 +1,221 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use scraper::{ElementRef, Html, Selector};
+use url::Url;
+
+/// Document-level metadata pulled from `<head>`.
+#[derive(Clone, Debug, Default)]
+pub struct PageMetadata {
+    pub title: Option<String>,
+    pub published_at: Option<String>,
+    pub modified_at: Option<String>,
+    pub canonical_url: Option<Url>,
+}
+
+/// Metadata and tables from a single parse of the page.
+#[derive(Clone, Debug, Default)]
+pub struct ExtractedPage {
+    pub metadata: PageMetadata,
+    pub tables: Vec<HtmlTable>,
+}
+
+#[derive(Clone, Debug)]
+pub struct HtmlTable {
+    pub caption: Option<String>,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl HtmlTable {
+    /// `header=cell` pairs for one row; cells without a header keep their column number.
+    pub fn row_pairs(&self, row: &[String]) -> Vec<(String, String)> {
+        row.iter()
+            .enumerate()
+            .filter(|(_, cell)| !cell.is_empty())
+            .map(|(i, cell)| {
+                let key = self
+                    .headers
+                    .get(i)
+                    .filter(|h| !h.is_empty())
+                    .cloned()
+                    .unwrap_or_else(|| format!("col{}", i + 1));
+                (key, cell.clone())
+            })
+            .collect()
+    }
+
+    pub fn row_text(&self, row: &[String]) -> String {
+        self.row_pairs(row)
+            .into_iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
+
+fn selector(css: &str) -> Selector {
+    Selector::parse(css).expect("static selector")
+}
+
+fn collapse_ws(raw: &str) -> String {
+    raw.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn element_text(el: ElementRef<'_>) -> String {
+    collapse_ws(&el.text().collect::<String>())
+}
+
+fn first_meta(doc: &Html, selectors: &[&str]) -> Option<String> {
+    selectors.iter().find_map(|css| {
+        doc.select(&selector(css))
+            .filter_map(|el| {
+                el.value()
+                    .attr("content")
+                    .or_else(|| el.value().attr("datetime"))
+            })
+            .map(|v| v.trim().to_string())
+            .find(|v| !v.is_empty())
+    })
+}
+
+/// RFC 3339 when the value can be read as a date, otherwise the raw value.
+pub fn normalize_date(raw: &str) -> String {
+    let raw = raw.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        return dt.with_timezone(&Utc).to_rfc3339();
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc2822(raw) {
+        return dt.with_timezone(&Utc).to_rfc3339();
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(raw.get(..10).unwrap_or(raw), "%Y-%m-%d") {
+        if let Some(dt) = date.and_hms_opt(0, 0, 0) {
+            return dt.and_utc().to_rfc3339();
+        }
+    }
+    raw.to_string()
+}
+
+pub fn extract_page(html: &str, base_url: &Url) -> ExtractedPage {
+    let doc = Html::parse_document(html);
+    ExtractedPage {
+        metadata: metadata_from(&doc, base_url),
+        tables: tables_from(&doc),
+    }
+}
+
+pub fn extract_metadata(html: &str, base_url: &Url) -> PageMetadata {
+    metadata_from(&Html::parse_document(html), base_url)
+}
+
+fn metadata_from(doc: &Html, base_url: &Url) -> PageMetadata {
+    let title = doc
+        .select(&selector("title"))
+        .map(element_text)
+        .find(|t| !t.is_empty())
+        .or_else(|| first_meta(doc, &[r#"meta[property="og:title"]"#]));
+
+    let published_at = first_meta(
+        doc,
+        &[
+            r#"meta[property="article:published_time"]"#,
+            r#"meta[itemprop="datePublished"]"#,
+            r#"meta[name="date"]"#,
+            r#"meta[name="dcterms.issued"]"#,
+            "time[datetime]",
+        ],
+    )
+    .map(|v| normalize_date(&v));
+
+    let modified_at = first_meta(
+        doc,
+        &[
+            r#"meta[property="article:modified_time"]"#,
+            r#"meta[property="og:updated_time"]"#,
+            r#"meta[itemprop="dateModified"]"#,
+            r#"meta[name="last-modified"]"#,
+            r#"meta[name="dcterms.modified"]"#,
+        ],
+    )
+    .map(|v| normalize_date(&v));
+
+    let canonical_url = doc
+        .select(&selector(r#"link[rel="canonical"]"#))
+        .filter_map(|el| el.value().attr("href"))
+        .find_map(|href| base_url.join(href.trim()).ok());
+
+    PageMetadata {
+        title,
+        published_at,
+        modified_at,
+        canonical_url,
+    }
+}
+
+/// Tables flattened into header + rows. Header cells come from `<thead>` or a
+/// leading row made only of `<th>`; nested tables are handled as their own table.
+pub fn extract_tables(html: &str) -> Vec<HtmlTable> {
+    tables_from(&Html::parse_document(html))
+}
+
+fn tables_from(doc: &Html) -> Vec<HtmlTable> {
+    let table_sel = selector("table");
+    let row_sel = selector("tr");
+    let cell_sel = selector("th, td");
+    let caption_sel = selector("caption");
+
+    let mut tables = Vec::new();
+    for table in doc.select(&table_sel) {
+        let mut headers: Vec<String> = Vec::new();
+        let mut rows: Vec<Vec<String>> = Vec::new();
+
+        for row in table.select(&row_sel) {
+            // skip rows that belong to a nested table
+            let owner = row
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .find(|a| a.value().name() == "table");
+            if owner.map(|o| o.id()) != Some(table.id()) {
+                continue;
+            }
+
+            let cells: Vec<ElementRef<'_>> = row
+                .select(&cell_sel)
+                .filter(|c| {
+                    c.ancestors()
+                        .filter_map(ElementRef::wrap)
+                        .find(|a| a.value().name() == "tr")
+                        .map(|tr| tr.id())
+                        == Some(row.id())
+                })
+                .collect();
+            if cells.is_empty() {
+                continue;
+            }
+            let texts: Vec<String> = cells.iter().map(|c| element_text(*c)).collect();
+            let in_thead = row
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|a| a.value().name() == "thead");
+            let all_th = cells.iter().all(|c| c.value().name() == "th");
+
+            if headers.is_empty() && rows.is_empty() && (in_thead || all_th) {
+                headers = texts;
+            } else if texts.iter().any(|t| !t.is_empty()) {
+                rows.push(texts);
+            }
+        }
+
+        if rows.is_empty() {
+            continue;
+        }
+        let caption = table
+            .select(&caption_sel)
+            .map(element_text)
+            .find(|c| !c.is_empty());
+        tables.push(HtmlTable {
+            caption,
+            headers,
+            rows,
+        });
+    }
+    tables
+}
@@ -1,60 +1,479 @@
-use crate::models::{Evidence, Id, RequiredFact, SourceMeta};
+use crate::models::{Evidence, Id, RequiredFact, SourceMeta, TextSpan};
+use crate::pipeline::extract::{extract_page, normalize_date, HtmlTable};
+use crate::pipeline::http_cache::{sha256_hex, CachedResponse, HttpCache};
+use crate::pipeline::passage::{
+    rank_passages, ranker_from_name, select_passages, Passage, PassageRanker,
+};
+use crate::pipeline::politeness::{
+    host_key, FetchPolicy, HostThrottle, PolicyError, RobotsRules, USER_AGENT,
+};
+use crate::pipeline::search::{
+    search_fragment, FileSearchProvider, SearchProvider, StaticSearchProvider,
+};
+use crate::pipeline::temporal::table_row_validity;
 use crate::pipeline::traits::FactFetcher;
 use async_trait::async_trait;
 use chrono::Utc;
-use reqwest::Client;
-use sha2::{Digest, Sha256};
+use futures_util::future::join_all;
+use reqwest::header::{
+    HeaderMap, HeaderValue, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+    LOCATION,
+};
+use reqwest::{redirect, Client, Response, StatusCode};
+use std::collections::HashMap;
 use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{Mutex, OwnedSemaphorePermit};
 use url::Url;
 
+const DEFAULT_MAX_SOURCES: usize = 3;
+const DEFAULT_PASSAGES_PER_SOURCE: usize = 3;
+const DEFAULT_CACHE_TTL_SECS: i64 = 3600;
+
+struct Download {
+    body: String,
+    sha256_hex: String,
+    previous_sha256_hex: Option<String>,
+    last_modified: Option<String>,
+}
+
 pub struct WebFetcher {
     client: Client,
+    provider: Arc<dyn SearchProvider>,
+    max_sources: usize,
+    ranker: Arc<dyn PassageRanker>,
+    passages_per_source: usize,
+    cache: Option<HttpCache>,
+    policy: FetchPolicy,
+    throttle: HostThrottle,
+    robots: Mutex<HashMap<String, Arc<RobotsRules>>>,
 }
 
 impl WebFetcher {
+    /// Provider is chosen from the environment:
+    /// `TUFF_SEARCH_INDEX` (local URL/keyword index) or `TARGET_URL` as a fixed source.
     pub fn new() -> Self {
+        let provider: Arc<dyn SearchProvider> = match env::var("TUFF_SEARCH_INDEX") {
+            Ok(path) => match FileSearchProvider::from_path(&path) {
+                Ok(p) => Arc::new(p),
+                Err(err) => {
+                    eprintln!(
+                        "search index {} unavailable ({}), using TARGET_URL",
+                        path, err
+                    );
+                    Arc::new(StaticSearchProvider::new(Self::target_url()))
+                }
+            },
+            Err(_) => Arc::new(StaticSearchProvider::new(Self::target_url())),
+        };
+        let max_sources = env::var("TUFF_FETCH_TOP_N")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_MAX_SOURCES);
+        let mut fetcher =
+            Self::with_provider(provider, max_sources).with_policy(FetchPolicy::from_env());
+        if let Ok(name) = env::var("TUFF_PASSAGE_RANKER") {
+            fetcher.ranker = Arc::from(ranker_from_name(&name));
+        }
+        if let Some(k) = env::var("TUFF_PASSAGE_TOP_K")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+        {
+            fetcher.passages_per_source = k;
+        }
+        // TUFF_HTTP_CACHE=0 disables the on-disk response cache
+        let cache_enabled = env::var("TUFF_HTTP_CACHE")
+            .map(|v| v.trim() != "0")
+            .unwrap_or(true);
+        if cache_enabled {
+            let dir = env::var("TUFF_HTTP_CACHE_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from("_tuffdb").join("http_cache"));
+            let ttl_secs = env::var("TUFF_HTTP_CACHE_TTL_SECS")
+                .ok()
+                .and_then(|v| v.parse::<i64>().ok())
+                .unwrap_or(DEFAULT_CACHE_TTL_SECS);
+            fetcher.cache = Some(HttpCache::new(dir, chrono::Duration::seconds(ttl_secs)));
+        }
+        fetcher
+    }
+
+    pub fn with_provider(provider: Arc<dyn SearchProvider>, max_sources: usize) -> Self {
+        let policy = FetchPolicy::default();
         Self {
-            client: Client::builder()
-                .user_agent("TUFF-DB/0.1")
-                .build()
-                .expect("reqwest client"),
+            client: Self::build_client(&policy),
+            provider,
+            max_sources,
+            ranker: Arc::from(ranker_from_name("bm25")),
+            passages_per_source: DEFAULT_PASSAGES_PER_SOURCE,
+            cache: None,
+            throttle: HostThrottle::new(policy.max_concurrent_per_host, policy.min_interval),
+            policy,
+            robots: Mutex::new(HashMap::new()),
         }
     }
 
-    fn target_url() -> anyhow::Result<Url> {
-        let raw = env::var("TARGET_URL")
-            .unwrap_or_else(|_| "https://www.kantei.go.jp/jp/rekidai/index.html".to_string());
-        Ok(Url::parse(&raw)?)
+    pub fn with_policy(mut self, policy: FetchPolicy) -> Self {
+        self.client = Self::build_client(&policy);
+        self.throttle = HostThrottle::new(policy.max_concurrent_per_host, policy.min_interval);
+        self.policy = policy;
+        self
     }
-}
 
-#[async_trait]
-impl FactFetcher for WebFetcher {
-    async fn fetch(&self, _fragment: &str) -> anyhow::Result<Vec<RequiredFact>> {
-        let url = Self::target_url()?;
-        let raw_html = self.client.get(url.clone()).send().await?.text().await?;
-        let clean_text = html2text::from_read(raw_html.as_bytes(), 80);
+    // redirects are followed manually so every hop goes through robots/throttle checks
+    fn build_client(policy: &FetchPolicy) -> Client {
+        Client::builder()
+            .user_agent(USER_AGENT)
+            .redirect(redirect::Policy::none())
+            .timeout(policy.timeout)
+            .build()
+            .expect("reqwest client")
+    }
+
+    pub fn with_ranker(
+        mut self,
+        ranker: Arc<dyn PassageRanker>,
+        passages_per_source: usize,
+    ) -> Self {
+        self.ranker = ranker;
+        self.passages_per_source = passages_per_source;
+        self
+    }
+
+    pub fn with_cache(mut self, cache: HttpCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    fn target_url() -> Url {
+        let default_url = "https://www.kantei.go.jp/jp/rekidai/index.html";
+        let raw = env::var("TARGET_URL").unwrap_or_else(|_| default_url.to_string());
+        Url::parse(&raw).unwrap_or_else(|err| {
+            eprintln!("invalid TARGET_URL {} ({}), using default", raw, err);
+            Url::parse(default_url).expect("default url")
+        })
+    }
+
+    async fn robots_for(&self, url: &Url) -> Arc<RobotsRules> {
+        let origin = format!("{}://{}", url.scheme(), host_key(url));
+        if let Some(rules) = self.robots.lock().await.get(&origin) {
+            return Arc::clone(rules);
+        }
+
+        let rules = match url.join("/robots.txt") {
+            Ok(robots_url) => {
+                let _permit = self.throttle.acquire(&host_key(url)).await;
+                match self.client.get(robots_url).send().await {
+                    Ok(resp) if resp.status().is_success() => match resp.text().await {
+                        Ok(body) => RobotsRules::parse(&body, USER_AGENT),
+                        Err(_) => RobotsRules::allow_all(),
+                    },
+                    // missing robots.txt (or unreachable) means no restrictions
+                    _ => RobotsRules::allow_all(),
+                }
+            }
+            Err(_) => RobotsRules::allow_all(),
+        };
+        let rules = Arc::new(rules);
+        self.robots.lock().await.insert(origin, Arc::clone(&rules));
+        rules
+    }
+
+    /// GET honouring robots.txt, per-host limits and the redirect hop limit.
+    /// The returned permit keeps the host slot busy until the body is read.
+    async fn polite_get(
+        &self,
+        url: &Url,
+        headers: HeaderMap,
+    ) -> anyhow::Result<(Response, OwnedSemaphorePermit)> {
+        let mut current = url.clone();
+        for _ in 0..=self.policy.max_redirects {
+            if self.policy.respect_robots {
+                let mut path = current.path().to_string();
+                if let Some(query) = current.query() {
+                    path.push('?');
+                    path.push_str(query);
+                }
+                if !self.robots_for(&current).await.allows(&path) {
+                    return Err(PolicyError::RobotsDisallowed(current).into());
+                }
+            }
+
+            let permit = self.throttle.acquire(&host_key(&current)).await;
+            let response = self
+                .client
+                .get(current.clone())
+                .headers(headers.clone())
+                .send()
+                .await?;
+            let status = response.status();
+            if !status.is_redirection() || status == StatusCode::NOT_MODIFIED {
+                return Ok((response, permit));
+            }
+            current = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|loc| current.join(loc).ok())
+                .ok_or_else(|| PolicyError::BadRedirect(current.clone()))?;
+        }
+        Err(PolicyError::TooManyRedirects {
+            url: url.clone(),
+            max: self.policy.max_redirects,
+        }
+        .into())
+    }
+
+    async fn read_body(&self, url: &Url, mut response: Response) -> anyhow::Result<String> {
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+        if !self.policy.content_type_allowed(content_type.as_deref()) {
+            return Err(PolicyError::UnsupportedContentType {
+                url: url.clone(),
+                content_type,
+            }
+            .into());
+        }
+
+        let max = self.policy.max_body_bytes;
+        let too_large = || PolicyError::BodyTooLarge {
+            url: url.clone(),
+            max,
+        };
+        if response
+            .content_length()
+            .is_some_and(|len| len as usize > max)
+        {
+            return Err(too_large().into());
+        }
+        let mut buf: Vec<u8> = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if buf.len() + chunk.len() > max {
+                return Err(too_large().into());
+            }
+            buf.extend_from_slice(&chunk);
+        }
+        Ok(String::from_utf8_lossy(&buf).into_owned())
+    }
+
+    /// GET with the response cache: fresh entries are served from disk,
+    /// stale ones are revalidated with ETag / Last-Modified.
+    async fn download(&self, url: &Url) -> anyhow::Result<Download> {
+        let now = Utc::now();
+        let cached = match self.cache.as_ref() {
+            Some(cache) => cache.load(url).await,
+            None => None,
+        };
+        if let (Some(cache), Some(entry)) = (self.cache.as_ref(), cached.as_ref()) {
+            if entry.is_fresh(cache.ttl(), now) {
+                return Ok(Download {
+                    body: entry.body.clone(),
+                    sha256_hex: entry.sha256_hex.clone(),
+                    previous_sha256_hex: Some(entry.sha256_hex.clone()),
+                    last_modified: entry.last_modified.clone(),
+                });
+            }
+        }
+
+        let mut headers = HeaderMap::new();
+        if let Some(entry) = cached.as_ref() {
+            if let Some(v) = entry
+                .etag
+                .as_deref()
+                .and_then(|v| HeaderValue::from_str(v).ok())
+            {
+                headers.insert(IF_NONE_MATCH, v);
+            }
+            if let Some(v) = entry
+                .last_modified
+                .as_deref()
+                .and_then(|v| HeaderValue::from_str(v).ok())
+            {
+                headers.insert(IF_MODIFIED_SINCE, v);
+            }
+        }
+        let (response, _permit) = self.polite_get(url, headers).await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let (Some(cache), Some(mut entry)) = (self.cache.as_ref(), cached.clone()) {
+                entry.fetched_at = now;
+                if let Err(err) = cache.store(&entry).await {
+                    eprintln!("http cache write failed: {}", err);
+                }
+                return Ok(Download {
+                    sha256_hex: entry.sha256_hex.clone(),
+                    previous_sha256_hex: Some(entry.sha256_hex),
+                    last_modified: entry.last_modified,
+                    body: entry.body,
+                });
+            }
+        }
 
-        let mut hasher = Sha256::new();
-        hasher.update(raw_html.as_bytes());
-        let sha256_hex = format!("{:x}", hasher.finalize());
+        let response = response.error_for_status()?;
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let body = self.read_body(url, response).await?;
+        let entry = CachedResponse {
+            url: url.clone(),
+            etag,
+            last_modified,
+            fetched_at: now,
+            sha256_hex: sha256_hex(body.as_bytes()),
+            body,
+        };
+        if let Some(cache) = self.cache.as_ref() {
+            if let Err(err) = cache.store(&entry).await {
+                eprintln!("http cache write failed: {}", err);
+            }
+        }
+        Ok(Download {
+            sha256_hex: entry.sha256_hex,
+            previous_sha256_hex: cached.map(|c| c.sha256_hex),
+            last_modified: entry.last_modified,
+            body: entry.body,
+        })
+    }
+
+    /// Passage evidence for the page plus one `header`/cell fact per cell of the
+    /// relevant table rows.
+    async fn fetch_source(&self, fragment: &str, url: Url) -> anyhow::Result<Vec<RequiredFact>> {
+        let download = self.download(&url).await?;
+        let clean_text = html2text::from_read(download.body.as_bytes(), 80);
+        let page = extract_page(&download.body, &url);
+        let metadata = page.metadata;
 
         let source = SourceMeta {
             url: url.clone(),
             retrieved_at_rfc3339: Utc::now().to_rfc3339(),
-            sha256_hex,
+            sha256_hex: download.sha256_hex,
+            previous_sha256_hex: download.previous_sha256_hex,
+            title: metadata.title,
+            published_at: metadata.published_at,
+            modified_at: metadata
+                .modified_at
+                .or_else(|| download.last_modified.as_deref().map(normalize_date)),
+            canonical_url: metadata.canonical_url,
         };
 
-        let evidence = Evidence {
+        let evidence = select_passages(
+            self.ranker.as_ref(),
+            fragment,
+            &clean_text,
+            self.passages_per_source,
+        )
+        .into_iter()
+        .map(|passage| Evidence {
             evidence_id: Id::new(),
-            source,
-            snippet: clean_text.chars().take(1200).collect(),
-        };
+            source: source.clone(),
+            snippet: passage.text,
+            span: Some(TextSpan {
+                char_start: passage.char_start,
+                char_end: passage.char_end,
+            }),
+            credibility: None,
+        })
+        .collect();
 
-        Ok(vec![RequiredFact {
-            key: "target_url".to_string(),
-            value: url.to_string(),
-            evidence: vec![evidence],
-        }])
+        let mut facts = vec![RequiredFact::new("source_url", url.to_string(), evidence)];
+        facts.extend(self.table_facts(fragment, &page.tables, &source));
+        Ok(facts)
+    }
+
+    fn table_facts(
+        &self,
+        fragment: &str,
+        tables: &[HtmlTable],
+        source: &SourceMeta,
+    ) -> Vec<RequiredFact> {
+        let mut located = Vec::new();
+        let mut rows = Vec::new();
+        for (t_idx, table) in tables.iter().enumerate() {
+            let label = table
+                .caption
+                .clone()
+                .unwrap_or_else(|| format!("table{}", t_idx + 1));
+            for (r_idx, row) in table.rows.iter().enumerate() {
+                let text = table.row_text(row);
+                if text.is_empty() {
+                    continue;
+                }
+                located.push((table, row, format!("{}[{}]", label, r_idx + 1)));
+                rows.push(Passage {
+                    text,
+                    char_start: 0,
+                    char_end: 0,
+                });
+            }
+        }
+
+        let mut facts = Vec::new();
+        for idx in rank_passages(
+            self.ranker.as_ref(),
+            fragment,
+            &rows,
+            self.passages_per_source,
+        ) {
+            let (table, row, qualifier) = &located[idx];
+            // 表の行は在任期間などをそのまま持つことが多い
+            let (valid_from, valid_to) = table_row_validity(&rows[idx].text).unwrap_or_default();
+            // 行全体を根拠に、セルごとに 見出し=値 の事実を返す
+            let evidence = Evidence::new(source.clone(), rows[idx].text.clone());
+            facts.extend(table.row_pairs(row).into_iter().map(|(header, cell)| {
+                RequiredFact::new(header, cell, vec![evidence.clone()])
+                    .with_qualifier(qualifier.clone())
+                    .with_validity(valid_from, valid_to)
+            }));
+        }
+        facts
+    }
+}
+
+impl Default for WebFetcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl FactFetcher for WebFetcher {
+    async fn fetch(&self, fragment: &str) -> anyhow::Result<Vec<RequiredFact>> {
+        let hits = search_fragment(self.provider.as_ref(), fragment, self.max_sources).await?;
+
+        let results = join_all(
+            hits.into_iter()
+                .map(|hit| self.fetch_source(fragment, hit.url)),
+        )
+        .await;
+
+        let mut facts = Vec::new();
+        let mut last_err = None;
+        for result in results {
+            match result {
+                Ok(source_facts) => facts.extend(source_facts),
+                Err(err) => {
+                    eprintln!("fetch failed: {}", err);
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        // every candidate failed: surface the error instead of "no evidence"
+        if facts.is_empty() {
+            if let Some(err) = last_err {
+                return Err(err);
+            }
+        }
+        Ok(facts)
     }
 }
@@ -1,39 +1,84 @@
+use anyhow::Result;
 use async_trait::async_trait;
-use anyhow::{Context, Result};
-use async_openai::types::{
-    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
-    CreateChatCompletionRequestArgs,
-};
-use async_openai::{config::OpenAIConfig, Client};
+use schemars::JsonSchema;
 use serde::Deserialize;
+use std::sync::Arc;
 
-use crate::models::{AgentIdentity, Claim, Evidence, Id, IsoDateTime, Transition};
-use crate::pipeline::traits::GapResolver;
+use crate::models::{
+    AgentIdentity, Claim, EntityRegistry, Evidence, Id, IsoDateTime, SharedEntityRegistry,
+    StateValue, Transition,
+};
+use crate::pipeline::llm_client::{LlmClient, LlmRequest};
+use crate::pipeline::prompts::PromptSet;
+use crate::pipeline::structured::complete_json;
+use crate::pipeline::traits::{GapResolver, IngestContext};
 
 pub struct LlmGapResolver {
-    client: Client<OpenAIConfig>,
-    model: String,
+    client: Arc<dyn LlmClient>,
+    prompts: Arc<PromptSet>,
+    entities: Option<SharedEntityRegistry>,
 }
 
 impl LlmGapResolver {
-    pub fn new(api_key: &str, model: &str) -> Self {
-        let mut config = OpenAIConfig::new().with_api_key(api_key);
-        if let Ok(base_url) = std::env::var("OPENAI_API_BASE") {
-            config = config.with_api_base(base_url);
-        }
+    pub fn new(client: Arc<dyn LlmClient>) -> Self {
         Self {
-            client: Client::with_config(config),
-            model: model.to_string(),
+            client,
+            prompts: Arc::new(PromptSet::builtin()),
+            entities: None,
         }
     }
+
+    pub fn with_prompts(mut self, prompts: Arc<PromptSet>) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Write states with canonical entity names ("Sanae Takaichi" -> 高市早苗).
+    pub fn with_entities(mut self, entities: SharedEntityRegistry) -> Self {
+        self.entities = Some(entities);
+        self
+    }
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 struct LlmGapResponse {
     event_name: String,
     occurred_at: Option<String>,
-    from_state: String,
-    to_state: String,
+    #[serde(default)]
+    from: Option<StateValue>,
+    #[serde(default)]
+    to: Option<StateValue>,
+    // 旧プロンプトの文字列形式
+    #[serde(default)]
+    from_state: Option<String>,
+    #[serde(default)]
+    to_state: Option<String>,
+}
+
+// 構造化された値を優先し、文字列しか無ければそれを解析する
+fn state_of(
+    typed: Option<StateValue>,
+    legacy: Option<String>,
+    registry: &EntityRegistry,
+) -> Option<(String, StateValue)> {
+    match (typed, legacy) {
+        (Some(value), _) if !value.object.trim().is_empty() => Some((value.render(), value)),
+        (_, Some(text)) if !text.trim().is_empty() => {
+            let value = StateValue::parse_in(&text, registry);
+            Some((text, value))
+        }
+        _ => None,
+    }
+}
+
+// RFC 3339 の日時、または日付のみ ("2025-10-21") なら UTC の 0 時として読む
+fn parse_occurred_at(raw: &str) -> Option<IsoDateTime> {
+    let raw = raw.trim();
+    if let Ok(at) = raw.parse::<chrono::DateTime<chrono::Utc>>() {
+        return Some(IsoDateTime(at));
+    }
+    let date = chrono::NaiveDate::parse_from_str(raw, "%Y-%m-%d").ok()?;
+    Some(IsoDateTime(date.and_time(chrono::NaiveTime::MIN).and_utc()))
 }
 
 #[async_trait]
@@ -43,6 +88,7 @@ impl GapResolver for LlmGapResolver {
         claim: &Claim,
         internal_state: &str,
         external_evidence: &[Evidence],
+        ctx: &IngestContext,
     ) -> Result<Option<Transition>> {
         if external_evidence.is_empty() {
             return Ok(None);
@@ -54,59 +100,67 @@ impl GapResolver for LlmGapResolver {
             .collect::<Vec<_>>()
             .join("\n");
 
-        let system_prompt = r#"You are a Historian AI.
-Identify the EVENT that caused a change from the Internal State to the External Evidence.
-Output JSON only: { \"event_name\": string, \"occurred_at\": string(ISO8601 or null), \"from_state\": string, \"to_state\": string }"#;
-
-        let user_prompt = format!(
-            "Internal State: {}\nExternal Evidence: {}\nClaim: {}\n\nWhat event connects these states?",
-            internal_state, evidence_text, claim.statement
-        );
-
-        let request = CreateChatCompletionRequestArgs::default()
-            .model(&self.model)
-            .messages([
-                ChatCompletionRequestSystemMessageArgs::default()
-                    .content(system_prompt)
-                    .build()?
-                    .into(),
-                ChatCompletionRequestUserMessageArgs::default()
-                    .content(user_prompt)
-                    .build()?
-                    .into(),
-            ])
-            .build()?;
+        let vars = [
+            ("claim", claim.statement.as_str()),
+            ("evidence", evidence_text.as_str()),
+            ("internal_state", internal_state),
+            ("locale", ctx.locale_tag()),
+        ];
+        let locale = ctx.locale.as_deref();
+        let system_prompt = self
+            .prompts
+            .render_for("gap_resolver.system", locale, &vars)?;
+        let user_prompt = self
+            .prompts
+            .render_for("gap_resolver.user", locale, &vars)?;
 
-        let response = self.client.chat().create(request).await?;
-        let content = response
-            .choices
-            .first()
-            .and_then(|c| c.message.content.clone())
-            .context("No content")?;
-
-        let res: LlmGapResponse = match serde_json::from_str(&content) {
+        let request = LlmRequest::new(system_prompt, user_prompt);
+        let res = match complete_json::<LlmGapResponse>(self.client.as_ref(), request, "transition")
+            .await?
+        {
             Ok(v) => v,
             Err(_) => return Ok(None),
         };
 
-        let occurred_at = res
-            .occurred_at
-            .as_deref()
-            .and_then(|s| s.parse::<chrono::DateTime<chrono::Utc>>().ok())
-            .map(IsoDateTime);
+        let guard = self
+            .entities
+            .as_ref()
+            .map(|entities| entities.read().expect("entity registry lock"));
+        let registry = guard.as_deref();
+        let parse_with = registry.unwrap_or(EntityRegistry::builtin_ref());
+        let (Some((mut from_state, mut from)), Some((mut to_state, mut to))) = (
+            state_of(res.from, res.from_state, parse_with),
+            state_of(res.to, res.to_state, parse_with),
+        ) else {
+            return Ok(None);
+        };
+        // 登録済みの実体は正規名に揃える
+        if let Some(registry) = registry {
+            from = from.canonicalized(registry);
+            to = to.canonicalized(registry);
+            from_state = from.render();
+            to_state = to.render();
+        }
+
+        let occurred_at = res.occurred_at.as_deref().and_then(parse_occurred_at);
 
         Ok(Some(Transition {
             transition_id: Id::new(),
             observed_at: IsoDateTime::now(),
             agent: AgentIdentity::current(),
-            from_state: res.from_state,
-            to_state: res.to_state,
+            from_state,
+            to_state,
+            from: Some(from),
+            to: Some(to),
             event: res.event_name,
             occurred_at,
             evidence_ids: external_evidence
                 .iter()
                 .map(|e| e.evidence_id.clone())
                 .collect(),
+            prompt_version: Some(self.prompts.version().to_string()),
+            abstract_id: None,
+            signature: None,
         }))
     }
 }
@@ -0,0 +1,69 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// One cached response body with the validators needed for revalidation.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub url: Url,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+    pub sha256_hex: String,
+    pub body: String,
+}
+
+impl CachedResponse {
+    pub fn is_fresh(&self, ttl: Duration, now: DateTime<Utc>) -> bool {
+        now - self.fetched_at < ttl
+    }
+}
+
+/// On-disk response cache, one JSON file per URL.
+#[derive(Clone, Debug)]
+pub struct HttpCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl HttpCache {
+    pub fn new(dir: impl AsRef<Path>, ttl: Duration) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            ttl,
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    fn path_for(&self, url: &Url) -> PathBuf {
+        self.dir
+            .join(format!("{}.json", sha256_hex(url.as_str().as_bytes())))
+    }
+
+    /// Unreadable or corrupt entries are treated as a miss.
+    pub async fn load(&self, url: &Url) -> Option<CachedResponse> {
+        let raw = tokio::fs::read_to_string(self.path_for(url)).await.ok()?;
+        let entry: CachedResponse = serde_json::from_str(&raw).ok()?;
+        (entry.url == *url).then_some(entry)
+    }
+
+    pub async fn store(&self, entry: &CachedResponse) -> anyhow::Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let path = self.path_for(&entry.url);
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, serde_json::to_vec(entry)?).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
@@ -1,8 +1,15 @@
-use crate::db::{OpLog, TuffDb};
-use crate::models::Abstract;
+use crate::db::{OpLog, SelectQuery, TuffDb};
+use crate::models::{
+    Abstract, Claim, EntityRegistry, Id, RequiredFact, SharedEntityRegistry, StateValue,
+    TemporalQualifier, Transition, VerificationStatus,
+};
+use crate::pipeline::credibility::CredibilityRegistry;
+use crate::pipeline::temporal::{extract_temporal, TemporalCheck};
 use crate::pipeline::traits::{
-    AbstractGenerator, ClaimVerifier, FactFetcher, InputSplitter, VerificationResult,
+    AbstractGenerator, AsyncInputSplitter, ClaimVerifier, FactFetcher, GapResolver, IngestContext,
+    VerificationResult,
 };
+use std::sync::Arc;
 
 pub struct IngestOutcome {
     pub op: OpLog,
@@ -10,11 +17,13 @@ pub struct IngestOutcome {
     pub confidence: f32,
     pub evidence_count: usize,
     pub reason: String,
+    /// `InsertTransition` op when the gap resolver explained a state change.
+    pub transition: Option<OpLog>,
 }
 
 pub struct IngestPipeline<S, F, V, G, D>
 where
-    S: InputSplitter,
+    S: AsyncInputSplitter,
     F: FactFetcher,
     V: ClaimVerifier,
     G: AbstractGenerator,
@@ -25,44 +34,211 @@ where
     pub verifier: V,
     pub generator: G,
     pub db: D,
+    /// Called on SMOKE / OUTDATED, when the verdict contradicts the stored
+    /// state of the topic, or when a source of a known topic changed since
+    /// the last fetch; `None` disables transition tracking.
+    pub gap_resolver: Option<Arc<dyn GapResolver>>,
+    /// Attaches source credibility to the evidence of every fetcher before
+    /// verification; `None` leaves `Evidence::credibility` as fetched.
+    pub credibility: Option<CredibilityRegistry>,
+    /// Aliases used to match a claim against the stored state of its topic;
+    /// `None` uses the built-in registry.
+    pub entities: Option<SharedEntityRegistry>,
 }
 
 impl<S, F, V, G, D> IngestPipeline<S, F, V, G, D>
 where
-    S: InputSplitter,
+    S: AsyncInputSplitter,
     F: FactFetcher,
     V: ClaimVerifier,
     G: AbstractGenerator,
     D: TuffDb,
 {
     pub async fn ingest(&self, input: &str) -> anyhow::Result<Vec<IngestOutcome>> {
-        let parts = self.splitter.split(input);
+        self.ingest_with(input, &IngestContext::default()).await
+    }
+
+    /// Like `ingest`, with request context (e.g. the page locale) passed to
+    /// the verifier and abstract generator.
+    pub async fn ingest_with(
+        &self,
+        input: &str,
+        ctx: &IngestContext,
+    ) -> anyhow::Result<Vec<IngestOutcome>> {
+        let parts = self.splitter.split(input, ctx).await?;
+        let temporal = TemporalCheck::new();
         let mut ops = Vec::new();
         for fragment in parts {
-            let facts = self.fetcher.fetch(&fragment).await?;
+            let mut facts = self.fetcher.fetch(&fragment).await?;
+            if let Some(registry) = &self.credibility {
+                registry.annotate(&mut facts);
+            }
             let evidence_count = facts.iter().map(|f| f.evidence.len()).sum();
+            // 前回取得時から内容が変わったソースは状態変化の兆候
+            let source_changed = facts
+                .iter()
+                .flat_map(|f| &f.evidence)
+                .any(|e| e.source.content_changed());
+            let mut result = self.verifier.verify(&fragment, &facts, ctx).await?;
+            // 「かつて正しかった」と「現在正しい」を区別する
+            let assessment = temporal.apply(&fragment, &facts, &mut result);
             let VerificationResult {
                 status,
                 confidence,
                 reason,
-            } = self.verifier.verify(&fragment, &facts).await?;
-            let abstract_ = self
+                stances,
+                votes,
+            } = result;
+            let mut abstract_ = self
                 .generator
-                .generate(&fragment, &facts, status)
+                .generate(&fragment, &facts, status, ctx)
                 .await?;
+            abstract_.stances = stances;
+            abstract_.votes = votes;
+            abstract_.valid_from = assessment.valid_from;
+            abstract_.valid_to = assessment.valid_to;
+            if abstract_.claims.is_empty() {
+                abstract_.claims.push(Claim {
+                    statement: fragment.clone(),
+                    sources: Vec::new(),
+                    temporal: TemporalQualifier::default(),
+                });
+            }
+            // 主張ごとの時間指定。主張自体に無ければ断片のものを引き継ぐ
+            for claim in &mut abstract_.claims {
+                if claim.temporal == TemporalQualifier::default() {
+                    let own = extract_temporal(&claim.statement);
+                    claim.temporal = if own == TemporalQualifier::default() {
+                        assessment.qualifier.clone()
+                    } else {
+                        own
+                    };
+                }
+            }
+            // 保存前に同じトピックの既知の状態を引く
+            let prior = match &self.gap_resolver {
+                Some(_) => self.prior_state(&abstract_, &abstract_.claims[0]).await?,
+                None => None,
+            };
+            let claim = abstract_.claims[0].clone();
+            let abstract_id = abstract_.id;
             let op = self.db.append_abstract(abstract_).await?;
+            let transition = match &self.gap_resolver {
+                Some(resolver) if needs_resolution(status, prior.as_ref(), source_changed) => {
+                    // 既知の状態が肯定されていればそれを、無ければ主張そのものを内部状態とする
+                    let internal_state = match &prior {
+                        Some(p) if polarity(p.verification) == Some(true) => p.summary.clone(),
+                        _ => fragment.clone(),
+                    };
+                    self.resolve_gap(resolver.as_ref(), &claim, &internal_state, &facts, ctx)
+                        .await?
+                        .map(|mut t| {
+                            t.abstract_id = Some(Id(abstract_id.0));
+                            t
+                        })
+                }
+                _ => None,
+            };
+            let transition = match transition {
+                Some(t) => Some(self.db.append_transition(t).await?),
+                None => None,
+            };
             ops.push(IngestOutcome {
                 op,
                 status,
                 confidence,
                 evidence_count,
                 reason,
+                transition,
             });
         }
         Ok(ops)
     }
 
+    /// Most recent stored abstract with the same tag set (the repo's topic key)
+    /// that states something about the same subject and role as `claim`.
+    async fn prior_state(
+        &self,
+        abstract_: &Abstract,
+        claim: &Claim,
+    ) -> anyhow::Result<Option<Abstract>> {
+        // ダミーや解析失敗時のタグはトピックを表さないので、無関係な Abstract を拾わない
+        if abstract_.tags.is_placeholder() {
+            return Ok(None);
+        }
+        let prior = self
+            .db
+            .select(SelectQuery {
+                tag_key: Some(abstract_.tags.to_key()),
+                min_verification: None,
+            })
+            .await?;
+        let guard = self
+            .entities
+            .as_ref()
+            .map(|entities| entities.read().expect("entity registry lock"));
+        let registry = guard.as_deref().unwrap_or(EntityRegistry::builtin_ref());
+        let state = StateValue::parse_in(&claim.statement, registry);
+        Ok(prior
+            .into_iter()
+            .filter(|a| {
+                a.claims.iter().any(|c| {
+                    StateValue::parse_in(&c.statement, registry).same_slot_in(&state, registry)
+                })
+            })
+            .max_by_key(|a| a.created_at))
+    }
+
+    async fn resolve_gap(
+        &self,
+        resolver: &dyn GapResolver,
+        claim: &Claim,
+        internal_state: &str,
+        facts: &[RequiredFact],
+        ctx: &IngestContext,
+    ) -> anyhow::Result<Option<Transition>> {
+        let evidence: Vec<_> = facts.iter().flat_map(|f| f.evidence.clone()).collect();
+        resolver
+            .resolve(claim, internal_state, &evidence, ctx)
+            .await
+    }
+
     pub async fn select_all(&self) -> anyhow::Result<Vec<Abstract>> {
         self.db.select(crate::db::SelectQuery::default()).await
     }
 }
+
+// true: 肯定 / false: 否定 / None: 判断保留
+fn polarity(status: VerificationStatus) -> Option<bool> {
+    match status {
+        VerificationStatus::White | VerificationStatus::GrayWhite => Some(true),
+        VerificationStatus::Smoke
+        | VerificationStatus::Outdated
+        | VerificationStatus::GrayBlack => Some(false),
+        VerificationStatus::GrayMid => None,
+    }
+}
+
+fn needs_resolution(
+    status: VerificationStatus,
+    prior: Option<&Abstract>,
+    source_changed: bool,
+) -> bool {
+    if matches!(
+        status,
+        VerificationStatus::Smoke | VerificationStatus::Outdated
+    ) {
+        return true;
+    }
+    // 既知のトピックのソースが更新されていれば、判定が同じでも遷移を確認する
+    if source_changed && prior.is_some() {
+        return true;
+    }
+    match (
+        prior.and_then(|p| polarity(p.verification)),
+        polarity(status),
+    ) {
+        (Some(before), Some(now)) => before != now,
+        _ => false,
+    }
+}
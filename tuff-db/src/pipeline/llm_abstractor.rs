@@ -1,33 +1,67 @@
-use crate::models::{Abstract, TagBits, TagGroupId, TopicId, VerificationStatus};
-use crate::pipeline::traits::AbstractGenerator;
-use anyhow::{Context, Result};
-use async_trait::async_trait;
-use async_openai::types::{
-    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
-    CreateChatCompletionRequestArgs,
+use crate::models::{
+    Abstract, EntityRegistry, SharedEntityRegistry, TagBits, TagGroupId, TopicId,
+    VerificationStatus, FALLBACK_TAG,
 };
-use async_openai::{config::OpenAIConfig, Client};
+use crate::pipeline::llm_client::{LlmClient, LlmRequest};
+use crate::pipeline::prompts::{language_name, PromptSet};
+use crate::pipeline::structured::complete_json;
+use crate::pipeline::traits::{AbstractGenerator, IngestContext};
+use anyhow::Result;
+use async_trait::async_trait;
+use schemars::JsonSchema;
 use serde::Deserialize;
+use std::sync::Arc;
 
 pub struct LlmAbstractor {
-    client: Client<OpenAIConfig>,
-    model: String,
+    client: Arc<dyn LlmClient>,
+    prompts: Arc<PromptSet>,
+    output_locale: Option<String>,
+    entities: Option<SharedEntityRegistry>,
 }
 
 impl LlmAbstractor {
-    pub fn new(api_key: &str, model: &str) -> Self {
-        let config = OpenAIConfig::new().with_api_key(api_key);
-        let client = Client::with_config(config);
+    pub fn new(client: Arc<dyn LlmClient>) -> Self {
         Self {
             client,
-            model: model.to_string(),
+            prompts: Arc::new(PromptSet::builtin()),
+            output_locale: None,
+            entities: None,
         }
     }
 
-    fn normalize_tags(tags: Vec<String>) -> TagBits {
+    pub fn with_prompts(mut self, prompts: Arc<PromptSet>) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Fix the language of summaries and tags (e.g. `ja`). Without it they
+    /// follow the request locale, or the claim's language when that is unknown.
+    pub fn with_output_locale(mut self, locale: Option<String>) -> Self {
+        self.output_locale = locale.filter(|l| !l.trim().is_empty());
+        self
+    }
+
+    /// Rewrite tags naming a known entity to its canonical name, so
+    /// "Sanae Takaichi" and "高市早苗" land on the same topic.
+    pub fn with_entities(mut self, entities: SharedEntityRegistry) -> Self {
+        self.entities = Some(entities);
+        self
+    }
+
+    fn output_language(&self, ctx: &IngestContext) -> String {
+        match self.output_locale.as_deref().or(ctx.locale.as_deref()) {
+            Some(locale) => language_name(locale),
+            None => "the same language as CLAIM".to_string(),
+        }
+    }
+
+    fn normalize_tags(tags: Vec<String>, entities: Option<&EntityRegistry>) -> TagBits {
         let mut cleaned: Vec<String> = tags
             .into_iter()
-            .map(|t| t.trim().to_string())
+            .map(|t| match entities {
+                Some(registry) => registry.canonical_name(&t),
+                None => t.trim().to_string(),
+            })
             .filter(|t| !t.is_empty())
             .collect();
         cleaned.sort();
@@ -36,7 +70,7 @@ impl LlmAbstractor {
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 struct LlmAbstractResponse {
     summary: String,
     tags: Vec<String>,
@@ -49,6 +83,7 @@ impl AbstractGenerator for LlmAbstractor {
         fragment: &str,
         facts: &[crate::models::RequiredFact],
         status: VerificationStatus,
+        ctx: &IngestContext,
     ) -> Result<Abstract> {
         let mut evidence_blocks = Vec::new();
         for fact in facts {
@@ -67,48 +102,45 @@ impl AbstractGenerator for LlmAbstractor {
             evidence_blocks.join("\n\n")
         };
 
-        let system_prompt = "You are a strict abstractor for a fact-checking database. \
-Given CLAIM, EVIDENCE, and STATUS, output JSON with keys: summary, tags. \
-summary must be brief and neutral. tags must be 3-8 short tags.";
-
-        let user_prompt = format!(
-            "CLAIM:\n{}\n\nSTATUS:\n{:?}\n\nEVIDENCE:\n{}",
-            fragment, status, evidence_text
-        );
+        let status_text = format!("{:?}", status);
+        let output_language = self.output_language(ctx);
+        let vars = [
+            ("claim", fragment),
+            ("evidence", evidence_text.as_str()),
+            ("status", status_text.as_str()),
+            ("locale", ctx.locale_tag()),
+            ("output_language", output_language.as_str()),
+        ];
+        let locale = ctx.locale.as_deref();
+        let system_prompt = self
+            .prompts
+            .render_for("abstractor.system", locale, &vars)?;
+        let user_prompt = self.prompts.render_for("abstractor.user", locale, &vars)?;
 
-        let request = CreateChatCompletionRequestArgs::default()
-            .model(&self.model)
-            .messages([
-                ChatCompletionRequestSystemMessageArgs::default()
-                    .content(system_prompt)
-                    .build()?
-                    .into(),
-                ChatCompletionRequestUserMessageArgs::default()
-                    .content(user_prompt)
-                    .build()?
-                    .into(),
-            ])
-            .build()?;
+        let request = LlmRequest::new(system_prompt, user_prompt);
+        let parsed =
+            complete_json::<LlmAbstractResponse>(self.client.as_ref(), request, "abstract")
+                .await?
+                .unwrap_or_else(|failure| LlmAbstractResponse {
+                    summary: format!(
+                        "LLM parse error. Raw: {}",
+                        failure.raw.chars().take(80).collect::<String>()
+                    ),
+                    tags: vec![FALLBACK_TAG.to_string()],
+                });
 
-        let response = self.client.chat().create(request).await?;
-        let content = response
-            .choices
-            .first()
-            .and_then(|c| c.message.content.clone())
-            .context("LLM response missing content")?;
-
-        let parsed: LlmAbstractResponse = serde_json::from_str(&content).unwrap_or_else(|_| {
-            LlmAbstractResponse {
-                summary: format!("LLM parse error. Raw: {}", content.chars().take(80).collect::<String>()),
-                tags: vec!["UNKNOWN".to_string()],
+        let tags = match &self.entities {
+            Some(entities) => {
+                let registry = entities.read().expect("entity registry lock");
+                Self::normalize_tags(parsed.tags, Some(&registry))
             }
-        });
-
-        let tags = Self::normalize_tags(parsed.tags);
+            None => Self::normalize_tags(parsed.tags, None),
+        };
 
         let mut abstract_ = Abstract::new(TopicId::new(), TagGroupId::new(), tags);
         abstract_.summary = parsed.summary;
         abstract_.verification = status;
+        abstract_.prompt_version = Some(self.prompts.version().to_string());
         Ok(abstract_)
     }
 }
@@ -0,0 +1,523 @@
+use crate::pipeline::llm_fixture::{RecordingLlmClient, ReplayLlmClient};
+use anyhow::{Context, Result};
+use async_openai::types::{
+    ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestMessage,
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequestArgs, ResponseFormat, ResponseFormatJsonSchema,
+};
+use async_openai::{config::OpenAIConfig, Client};
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::env;
+use std::sync::{Arc, Mutex};
+
+const ANTHROPIC_API_BASE: &str = "https://api.anthropic.com";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const DEFAULT_MAX_TOKENS: u32 = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LlmRole {
+    System,
+    User,
+    Assistant,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LlmMessage {
+    pub role: LlmRole,
+    pub content: String,
+}
+
+/// JSON schema the response must follow (see `structured`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResponseSchema {
+    pub name: String,
+    pub schema: serde_json::Value,
+}
+
+/// Provider-neutral chat request.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LlmRequest {
+    pub messages: Vec<LlmMessage>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<ResponseSchema>,
+}
+
+impl LlmRequest {
+    pub fn new(system: impl Into<String>, user: impl Into<String>) -> Self {
+        Self {
+            messages: vec![
+                LlmMessage {
+                    role: LlmRole::System,
+                    content: system.into(),
+                },
+                LlmMessage {
+                    role: LlmRole::User,
+                    content: user.into(),
+                },
+            ],
+            temperature: None,
+            max_tokens: None,
+            response_schema: None,
+        }
+    }
+
+    /// Ask for output matching the JSON schema of `T`.
+    pub fn with_schema<T: JsonSchema>(mut self, name: &str) -> Self {
+        let schema = serde_json::to_value(schemars::schema_for!(T)).unwrap_or_default();
+        self.response_schema = Some(ResponseSchema {
+            name: name.to_string(),
+            schema,
+        });
+        self
+    }
+
+    /// System prompts with the schema spelled out, for providers without a
+    /// native JSON-schema response format.
+    fn system_with_schema(&self) -> Vec<String> {
+        let mut system: Vec<String> = self
+            .messages
+            .iter()
+            .filter(|m| m.role == LlmRole::System)
+            .map(|m| m.content.clone())
+            .collect();
+        if let Some(schema) = &self.response_schema {
+            system.push(format!(
+                "Respond with a single JSON object (no markdown, no prose) matching this JSON schema:\n{}",
+                schema.schema
+            ));
+        }
+        system
+    }
+
+    pub fn system(&self) -> Option<&str> {
+        self.messages
+            .iter()
+            .find(|m| m.role == LlmRole::System)
+            .map(|m| m.content.as_str())
+    }
+}
+
+/// A chat-completion backend. Returns the text of the first choice.
+#[async_trait]
+pub trait LlmClient: Send + Sync {
+    fn model(&self) -> &str;
+    async fn complete(&self, request: &LlmRequest) -> Result<String>;
+}
+
+/// OpenAI chat completions, or any compatible server (llama.cpp, Ollama, vLLM)
+/// through `api_base`.
+pub struct OpenAiClient {
+    client: Client<OpenAIConfig>,
+    model: String,
+    // false: the server ignores `response_format`, describe the schema in the prompt instead
+    native_schema: bool,
+    // true: send `max_tokens` (compatible servers) instead of `max_completion_tokens`
+    legacy_max_tokens: bool,
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
+}
+
+impl OpenAiClient {
+    pub fn new(api_key: &str, model: &str) -> Self {
+        Self::with_base(api_key, model, None)
+    }
+
+    pub fn with_base(api_key: &str, model: &str, api_base: Option<&str>) -> Self {
+        let mut config = OpenAIConfig::new().with_api_key(api_key);
+        if let Some(base) = api_base {
+            config = config.with_api_base(base);
+        }
+        Self {
+            client: Client::with_config(config),
+            model: model.to_string(),
+            native_schema: true,
+            legacy_max_tokens: api_base.is_some_and(|base| !base.contains("api.openai.com")),
+            max_tokens: None,
+            temperature: None,
+        }
+    }
+
+    pub fn with_native_schema(mut self, enabled: bool) -> Self {
+        self.native_schema = enabled;
+        self
+    }
+
+    /// Send the token limit as `max_tokens` rather than `max_completion_tokens`.
+    /// On by default for any `api_base` other than api.openai.com.
+    pub fn with_legacy_max_tokens(mut self, enabled: bool) -> Self {
+        self.legacy_max_tokens = enabled;
+        self
+    }
+
+    /// Token limit for requests that do not set one.
+    pub fn with_max_tokens(mut self, max_tokens: Option<u32>) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Temperature for requests that do not set one.
+    pub fn with_temperature(mut self, temperature: Option<f32>) -> Self {
+        self.temperature = temperature;
+        self
+    }
+}
+
+#[async_trait]
+impl LlmClient for OpenAiClient {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn complete(&self, request: &LlmRequest) -> Result<String> {
+        let mut messages: Vec<ChatCompletionRequestMessage> = Vec::new();
+        if !self.native_schema {
+            for content in request.system_with_schema() {
+                messages.push(
+                    ChatCompletionRequestSystemMessageArgs::default()
+                        .content(content)
+                        .build()?
+                        .into(),
+                );
+            }
+        }
+        for message in &request.messages {
+            let content = message.content.as_str();
+            messages.push(match message.role {
+                LlmRole::System if !self.native_schema => continue,
+                LlmRole::System => ChatCompletionRequestSystemMessageArgs::default()
+                    .content(content)
+                    .build()?
+                    .into(),
+                LlmRole::User => ChatCompletionRequestUserMessageArgs::default()
+                    .content(content)
+                    .build()?
+                    .into(),
+                LlmRole::Assistant => ChatCompletionRequestAssistantMessageArgs::default()
+                    .content(content)
+                    .build()?
+                    .into(),
+            });
+        }
+
+        let mut args = CreateChatCompletionRequestArgs::default();
+        args.model(&self.model).messages(messages);
+        if let Some(t) = request.temperature.or(self.temperature) {
+            args.temperature(t);
+        }
+        match request.max_tokens.or(self.max_tokens) {
+            // 互換サーバの多くは max_completion_tokens を解釈しない
+            Some(max) if self.legacy_max_tokens => {
+                args.max_tokens(max);
+            }
+            Some(max) => {
+                args.max_completion_tokens(max);
+            }
+            None => {}
+        }
+        if let (true, Some(schema)) = (self.native_schema, &request.response_schema) {
+            args.response_format(ResponseFormat::JsonSchema {
+                json_schema: ResponseFormatJsonSchema {
+                    description: None,
+                    name: schema.name.clone(),
+                    schema: Some(schema.schema.clone()),
+                    strict: None,
+                },
+            });
+        }
+        let response = self.client.chat().create(args.build()?).await?;
+        response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.clone())
+            .context("LLM response missing content")
+    }
+}
+
+/// Anthropic Messages API (`POST /v1/messages`).
+pub struct AnthropicClient {
+    http: reqwest::Client,
+    api_key: String,
+    api_base: String,
+    model: String,
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
+}
+
+impl AnthropicClient {
+    pub fn new(api_key: &str, model: &str) -> Self {
+        Self::with_base(api_key, model, None)
+    }
+
+    pub fn with_base(api_key: &str, model: &str, api_base: Option<&str>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            api_key: api_key.to_string(),
+            api_base: api_base
+                .unwrap_or(ANTHROPIC_API_BASE)
+                .trim_end_matches('/')
+                .to_string(),
+            model: model.to_string(),
+            max_tokens: None,
+            temperature: None,
+        }
+    }
+
+    /// Token limit for requests that do not set one (default 1024; the
+    /// Messages API requires a limit).
+    pub fn with_max_tokens(mut self, max_tokens: Option<u32>) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Temperature for requests that do not set one.
+    pub fn with_temperature(mut self, temperature: Option<f32>) -> Self {
+        self.temperature = temperature;
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct AnthropicMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Serialize)]
+struct AnthropicRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    messages: Vec<AnthropicMessage<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+}
+
+#[derive(Deserialize)]
+struct AnthropicContent {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct AnthropicResponse {
+    content: Vec<AnthropicContent>,
+}
+
+#[async_trait]
+impl LlmClient for AnthropicClient {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn complete(&self, request: &LlmRequest) -> Result<String> {
+        // system prompts go in a top-level field, not in the message list;
+        // there is no response_format, so the schema is spelled out there too
+        let system = request.system_with_schema();
+        let messages = request
+            .messages
+            .iter()
+            .filter_map(|m| match m.role {
+                LlmRole::System => None,
+                LlmRole::User => Some(AnthropicMessage {
+                    role: "user",
+                    content: &m.content,
+                }),
+                LlmRole::Assistant => Some(AnthropicMessage {
+                    role: "assistant",
+                    content: &m.content,
+                }),
+            })
+            .collect();
+        let body = AnthropicRequest {
+            model: &self.model,
+            max_tokens: request
+                .max_tokens
+                .or(self.max_tokens)
+                .unwrap_or(DEFAULT_MAX_TOKENS),
+            system: (!system.is_empty()).then(|| system.join("\n\n")),
+            messages,
+            temperature: request.temperature.or(self.temperature),
+        };
+
+        let response = self
+            .http
+            .post(format!("{}/v1/messages", self.api_base))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(&body)
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("anthropic API error {}: {}", status, text);
+        }
+        let parsed: AnthropicResponse = response.json().await?;
+        let text: String = parsed
+            .content
+            .into_iter()
+            .filter(|c| c.kind == "text")
+            .map(|c| c.text)
+            .collect();
+        if text.is_empty() {
+            anyhow::bail!("LLM response missing content");
+        }
+        Ok(text)
+    }
+}
+
+/// Deterministic client for tests and demos. Rules (first match on any message
+/// containing the needle) win over the queue; queued responses are served in order.
+pub struct ScriptedLlmClient {
+    model: String,
+    rules: Vec<(String, String)>,
+    queue: Mutex<VecDeque<String>>,
+    requests: Mutex<Vec<LlmRequest>>,
+}
+
+impl Default for ScriptedLlmClient {
+    fn default() -> Self {
+        Self {
+            model: "scripted".to_string(),
+            rules: Vec::new(),
+            queue: Mutex::new(VecDeque::new()),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl ScriptedLlmClient {
+    pub fn new<I, T>(responses: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self {
+            queue: Mutex::new(responses.into_iter().map(Into::into).collect()),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_rule(mut self, needle: &str, response: &str) -> Self {
+        self.rules.push((needle.to_string(), response.to_string()));
+        self
+    }
+
+    /// Requests received so far, in order.
+    pub fn requests(&self) -> Vec<LlmRequest> {
+        self.requests.lock().map(|r| r.clone()).unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl LlmClient for ScriptedLlmClient {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn complete(&self, request: &LlmRequest) -> Result<String> {
+        if let Ok(mut log) = self.requests.lock() {
+            log.push(request.clone());
+        }
+        for (needle, response) in &self.rules {
+            if request.messages.iter().any(|m| m.content.contains(needle)) {
+                return Ok(response.clone());
+            }
+        }
+        self.queue
+            .lock()
+            .map_err(|_| anyhow::anyhow!("scripted LLM lock poisoned"))?
+            .pop_front()
+            .context("scripted LLM: no response left")
+    }
+}
+
+/// Rejects empty keys and `sk-...` style placeholders.
+pub fn valid_api_key(key: &str) -> bool {
+    let trimmed = key.trim();
+    !trimmed.is_empty() && !trimmed.contains("...")
+}
+
+/// Client selected by `TUFF_LLM_PROVIDER` (`openai` | `anthropic`, default `openai`).
+///
+/// - openai: `OPENAI_API_KEY`, `OPENAI_MODEL` (gpt-4o), `OPENAI_API_BASE`. With a
+///   base URL set the key is optional, for local OpenAI-compatible servers.
+/// - anthropic: `ANTHROPIC_API_KEY`, `ANTHROPIC_MODEL`, `ANTHROPIC_API_BASE`.
+///
+/// `TUFF_LLM_REPLAY=<cassette>` serves recorded responses instead of calling a
+/// provider; `TUFF_LLM_RECORD=<cassette>` records every exchange of the live client.
+///
+/// Returns `None` when no usable credentials are configured.
+pub fn client_from_env() -> Option<Arc<dyn LlmClient>> {
+    if let Ok(path) = env::var("TUFF_LLM_REPLAY") {
+        return match ReplayLlmClient::from_file(path.trim()) {
+            Ok(client) => Some(Arc::new(client)),
+            Err(err) => {
+                eprintln!("llm: replay disabled ({})", err);
+                None
+            }
+        };
+    }
+    let client = provider_from_env()?;
+    match env::var("TUFF_LLM_RECORD") {
+        Ok(path) if !path.trim().is_empty() => {
+            Some(Arc::new(RecordingLlmClient::new(client, path.trim())))
+        }
+        _ => Some(client),
+    }
+}
+
+fn provider_from_env() -> Option<Arc<dyn LlmClient>> {
+    let provider = env::var("TUFF_LLM_PROVIDER")
+        .map(|v| v.trim().to_lowercase())
+        .unwrap_or_else(|_| "openai".to_string());
+    let var = |name: &str| env::var(name).ok().filter(|v| !v.trim().is_empty());
+    // 全ステージ共通の既定値 (リクエスト側の指定が優先)
+    let max_tokens = var("TUFF_LLM_MAX_TOKENS").and_then(|v| v.trim().parse::<u32>().ok());
+    let temperature = var("TUFF_LLM_TEMPERATURE").and_then(|v| v.trim().parse::<f32>().ok());
+
+    match provider.as_str() {
+        "anthropic" | "claude" => {
+            let key = var("ANTHROPIC_API_KEY").filter(|k| valid_api_key(k))?;
+            let model =
+                var("ANTHROPIC_MODEL").unwrap_or_else(|| "claude-3-5-sonnet-latest".to_string());
+            let base = var("ANTHROPIC_API_BASE");
+            Some(Arc::new(
+                AnthropicClient::with_base(&key, &model, base.as_deref())
+                    .with_max_tokens(max_tokens)
+                    .with_temperature(temperature),
+            ))
+        }
+        _ => {
+            let base = var("OPENAI_API_BASE");
+            let key = match var("OPENAI_API_KEY").filter(|k| valid_api_key(k)) {
+                Some(key) => key,
+                // local servers usually ignore the key
+                None if base.is_some() => "local".to_string(),
+                None => return None,
+            };
+            let model = var("OPENAI_MODEL").unwrap_or_else(|| "gpt-4o".to_string());
+            // TUFF_LLM_JSON_SCHEMA=0 for servers without json_schema response_format
+            let native_schema = env::var("TUFF_LLM_JSON_SCHEMA")
+                .map(|v| v.trim() != "0")
+                .unwrap_or(true);
+            Some(Arc::new(
+                OpenAiClient::with_base(&key, &model, base.as_deref())
+                    .with_native_schema(native_schema)
+                    .with_max_tokens(max_tokens)
+                    .with_temperature(temperature),
+            ))
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::pipeline::llm_client::{LlmClient, LlmRequest, LlmRole};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LlmExchange {
+    pub request: LlmRequest,
+    pub response: String,
+}
+
+/// Recorded request/response pairs for one scenario, stored as pretty JSON.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    #[serde(default)]
+    pub model: String,
+    pub exchanges: Vec<LlmExchange>,
+}
+
+impl Cassette {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|err| anyhow::anyhow!("cassette {}: {}", path.display(), err))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)? + "\n")?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+}
+
+/// Passes requests to `inner` and writes every exchange to a cassette file.
+/// The file is rewritten after each call, so an aborted run keeps what it got.
+pub struct RecordingLlmClient {
+    inner: Arc<dyn LlmClient>,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+}
+
+impl RecordingLlmClient {
+    pub fn new(inner: Arc<dyn LlmClient>, path: impl Into<PathBuf>) -> Self {
+        let cassette = Cassette {
+            model: inner.model().to_string(),
+            exchanges: Vec::new(),
+        };
+        Self {
+            inner,
+            path: path.into(),
+            cassette: Mutex::new(cassette),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmClient for RecordingLlmClient {
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    async fn complete(&self, request: &LlmRequest) -> Result<String> {
+        let response = self.inner.complete(request).await?;
+        let mut cassette = self
+            .cassette
+            .lock()
+            .map_err(|_| anyhow::anyhow!("cassette lock poisoned"))?;
+        cassette.exchanges.push(LlmExchange {
+            request: request.clone(),
+            response: response.clone(),
+        });
+        cassette.save(&self.path)?;
+        Ok(response)
+    }
+}
+
+/// Serves responses from a cassette. By default a request must equal a recorded
+/// one (each exchange is used once); `sequential` ignores request contents and
+/// replays in order, which is how fixtures are re-recorded after prompt edits.
+pub struct ReplayLlmClient {
+    model: String,
+    exchanges: Vec<LlmExchange>,
+    used: Mutex<Vec<bool>>,
+    match_requests: bool,
+}
+
+impl ReplayLlmClient {
+    pub fn new(cassette: Cassette) -> Self {
+        let used = vec![false; cassette.exchanges.len()];
+        Self {
+            model: cassette.model,
+            exchanges: cassette.exchanges,
+            used: Mutex::new(used),
+            match_requests: true,
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+
+    pub fn sequential(mut self) -> Self {
+        self.match_requests = false;
+        self
+    }
+
+    /// Exchanges not served yet.
+    pub fn remaining(&self) -> usize {
+        self.used
+            .lock()
+            .map(|u| u.iter().filter(|v| !**v).count())
+            .unwrap_or(0)
+    }
+}
+
+#[async_trait]
+impl LlmClient for ReplayLlmClient {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn complete(&self, request: &LlmRequest) -> Result<String> {
+        let mut used = self
+            .used
+            .lock()
+            .map_err(|_| anyhow::anyhow!("replay lock poisoned"))?;
+        let found = self
+            .exchanges
+            .iter()
+            .enumerate()
+            .find(|(idx, ex)| !used[*idx] && (!self.match_requests || ex.request == *request));
+        match found {
+            Some((idx, ex)) => {
+                used[idx] = true;
+                Ok(ex.response.clone())
+            }
+            None => {
+                let user: String = request
+                    .messages
+                    .iter()
+                    .rev()
+                    .find(|m| m.role == LlmRole::User)
+                    .map(|m| m.content.chars().take(200).collect())
+                    .unwrap_or_default();
+                anyhow::bail!("no recorded response for request: {}", user)
+            }
+        }
+    }
+}
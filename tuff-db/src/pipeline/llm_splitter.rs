@@ -0,0 +1,69 @@
+use crate::pipeline::llm_client::{LlmClient, LlmRequest};
+use crate::pipeline::mock::DummySplitter;
+use crate::pipeline::prompts::PromptSet;
+use crate::pipeline::structured::complete_json;
+use crate::pipeline::traits::{AsyncInputSplitter, IngestContext, InputSplitter};
+use anyhow::Result;
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Uses the LLM to extract atomic, self-contained factual claims from a paragraph.
+/// Falls back to line splitting when the response cannot be parsed.
+pub struct LlmClaimSplitter {
+    client: Arc<dyn LlmClient>,
+    prompts: Arc<PromptSet>,
+    fallback: DummySplitter,
+}
+
+impl LlmClaimSplitter {
+    pub fn new(client: Arc<dyn LlmClient>) -> Self {
+        Self {
+            client,
+            prompts: Arc::new(PromptSet::builtin()),
+            fallback: DummySplitter,
+        }
+    }
+
+    pub fn with_prompts(mut self, prompts: Arc<PromptSet>) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    fn normalize_claims(claims: Vec<String>) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        for claim in claims {
+            let trimmed = claim.trim().to_string();
+            if !trimmed.is_empty() && !out.contains(&trimmed) {
+                out.push(trimmed);
+            }
+        }
+        out
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct LlmClaimsResponse {
+    claims: Vec<String>,
+}
+
+#[async_trait]
+impl AsyncInputSplitter for LlmClaimSplitter {
+    async fn split(&self, input: &str, ctx: &IngestContext) -> Result<Vec<String>> {
+        if input.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let vars = [("input", input), ("locale", ctx.locale_tag())];
+        let locale = ctx.locale.as_deref();
+        let system_prompt = self.prompts.render_for("splitter.system", locale, &vars)?;
+        let user_prompt = self.prompts.render_for("splitter.user", locale, &vars)?;
+
+        let request = LlmRequest::new(system_prompt, user_prompt);
+        match complete_json::<LlmClaimsResponse>(self.client.as_ref(), request, "claims").await? {
+            Ok(parsed) => Ok(Self::normalize_claims(parsed.claims)),
+            Err(_) => Ok(InputSplitter::split(&self.fallback, input)),
+        }
+    }
+}
@@ -1,32 +1,92 @@
-use crate::models::{RequiredFact, VerificationStatus};
-use crate::pipeline::traits::{ClaimVerifier, VerificationResult};
-use anyhow::{Context, Result};
+use crate::models::{Evidence, EvidenceStance, RequiredFact, VerificationStatus};
+use crate::pipeline::calibration::{validate_confidence, ConfidenceCalibration};
+use crate::pipeline::credibility::{evidence_trust, evidence_weight};
+use crate::pipeline::llm_client::{LlmClient, LlmRequest};
+use crate::pipeline::passage::PASSAGE_MAX_CHARS;
+use crate::pipeline::prompts::PromptSet;
+use crate::pipeline::stance::{parse_stance, StanceAggregation};
+use crate::pipeline::structured::complete_json;
+use crate::pipeline::temporal::{evidence_validity, format_validity};
+use crate::pipeline::traits::{ClaimVerifier, IngestContext, VerificationResult};
+use anyhow::Result;
 use async_trait::async_trait;
-use async_openai::types::{
-    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
-    CreateChatCompletionRequestArgs,
-};
-use async_openai::{config::OpenAIConfig, Client};
+use schemars::JsonSchema;
 use serde::Deserialize;
+use std::sync::Arc;
 
 pub struct LlmVerifier {
-    client: Client<OpenAIConfig>,
-    model: String,
+    client: Arc<dyn LlmClient>,
+    prompts: Arc<PromptSet>,
+    calibration: ConfidenceCalibration,
+    aggregation: StanceAggregation,
 }
 
+// モデルが confidence を返さない / 範囲外のときの値
+const UNRATED_CONFIDENCE: f32 = 0.4;
+
 impl LlmVerifier {
-    pub fn new(api_key: &str, model: &str) -> Self {
-        let config = OpenAIConfig::new().with_api_key(api_key);
-        let client = Client::with_config(config);
+    pub fn new(client: Arc<dyn LlmClient>) -> Self {
         Self {
             client,
-            model: model.to_string(),
+            prompts: Arc::new(PromptSet::builtin()),
+            calibration: ConfidenceCalibration::identity(),
+            aggregation: StanceAggregation::default(),
+        }
+    }
+
+    pub fn with_prompts(mut self, prompts: Arc<PromptSet>) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Map the model's raw confidence before evidence weighting, so the
+    /// result compared against `TUFF_STOP_CONFIDENCE` is calibrated.
+    pub fn with_calibration(mut self, calibration: ConfidenceCalibration) -> Self {
+        self.calibration = calibration;
+        self
+    }
+
+    /// Policy turning per-evidence stances into the overall status.
+    pub fn with_aggregation(mut self, aggregation: StanceAggregation) -> Self {
+        self.aggregation = aggregation;
+        self
+    }
+
+    // "E2" -> evidence[1]; quotes not found in the snippet are dropped
+    fn resolve_stances(raw: Vec<LlmStance>, evidence: &[&Evidence]) -> Vec<EvidenceStance> {
+        let mut out: Vec<EvidenceStance> = Vec::new();
+        for item in raw {
+            let Some(target) = item
+                .evidence
+                .trim()
+                .trim_start_matches(['E', 'e'])
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| n.checked_sub(1))
+                .and_then(|idx| evidence.get(idx))
+            else {
+                continue;
+            };
+            if out.iter().any(|s| s.evidence_id == target.evidence_id) {
+                continue;
+            }
+            let quote = item
+                .quote
+                .map(|q| q.trim().to_string())
+                .filter(|q| !q.is_empty() && target.snippet.contains(q.as_str()));
+            out.push(EvidenceStance {
+                evidence_id: target.evidence_id.clone(),
+                stance: parse_stance(&item.stance),
+                quote,
+            });
         }
+        out
     }
 
     fn parse_status(raw: &str) -> VerificationStatus {
         match raw.trim().to_uppercase().as_str() {
             "SMOKE" => VerificationStatus::Smoke,
+            "OUTDATED" => VerificationStatus::Outdated,
             "GRAY_BLACK" => VerificationStatus::GrayBlack,
             "GRAY_MID" => VerificationStatus::GrayMid,
             "GRAY_WHITE" => VerificationStatus::GrayWhite,
@@ -45,8 +105,9 @@ impl LlmVerifier {
         }
     }
 
-    fn confidence_adjust(conf_llm: f32, evidence_count: usize) -> f32 {
-        let factor = Self::clamp(evidence_count as f32 / 3.0, 0.4, 1.0);
+    // evidence_weight: 信頼度で重み付けしたエビデンス量 (credibility::evidence_weight)
+    fn confidence_adjust(conf_llm: f32, evidence_weight: f32) -> f32 {
+        let factor = Self::clamp(evidence_weight, 0.4, 1.0);
         Self::clamp(conf_llm * factor, 0.0, 1.0)
     }
 
@@ -61,11 +122,26 @@ impl LlmVerifier {
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 struct LlmResponse {
     status: String,
-    confidence: f32,
+    /// Probability (0.0-1.0) that `status` is correct.
+    #[serde(default)]
+    confidence: Option<f32>,
     reasoning: String,
+    #[serde(default)]
+    stances: Vec<LlmStance>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct LlmStance {
+    /// Evidence id as shown in the prompt, e.g. "E1".
+    evidence: String,
+    /// supports, refutes or irrelevant.
+    stance: String,
+    /// Exact short span from the snippet backing the stance.
+    #[serde(default)]
+    quote: Option<String>,
 }
 
 #[async_trait]
@@ -74,26 +150,48 @@ impl ClaimVerifier for LlmVerifier {
         &self,
         fragment: &str,
         facts: &[RequiredFact],
+        ctx: &IngestContext,
     ) -> Result<VerificationResult> {
         if facts.is_empty() {
             return Ok(VerificationResult {
                 status: VerificationStatus::GrayMid,
                 confidence: 0.4,
                 reason: "no evidence".to_string(),
+                stances: Vec::new(),
+                votes: Vec::new(),
             });
         }
 
+        // most credible sources first
+        let mut ranked: Vec<_> = facts
+            .iter()
+            .flat_map(|f| f.evidence.iter().map(move |e| (f, e)))
+            .collect();
+        ranked.sort_by(|(_, a), (_, b)| evidence_trust(b).total_cmp(&evidence_trust(a)));
+        let evidence: Vec<&Evidence> = ranked.iter().map(|(_, e)| *e).collect();
         let mut evidence_blocks = Vec::new();
-        let mut evidence_count = 0usize;
-        for fact in facts {
-            for evidence in &fact.evidence {
-                let snippet: String = evidence.snippet.chars().take(300).collect();
-                evidence_blocks.push(format!(
-                    "[URL: {}] [SHA256: {}]\n{}",
-                    evidence.source.url, evidence.source.sha256_hex, snippet
-                ));
-                evidence_count += 1;
-            }
+        for (idx, (fact, evidence)) in ranked.iter().enumerate() {
+            let snippet: String = evidence.snippet.chars().take(PASSAGE_MAX_CHARS).collect();
+            let credibility = match &evidence.credibility {
+                Some(c) => match &c.bias_note {
+                    Some(note) => format!("{:?} {:.2}; {}", c.category, c.trust, note),
+                    None => format!("{:?} {:.2}", c.category, c.trust),
+                },
+                None => "unknown".to_string(),
+            };
+            let validity = match evidence_validity(fact, evidence) {
+                Some((from, to)) => format!(" [VALID: {}]", format_validity(from, to)),
+                None => String::new(),
+            };
+            evidence_blocks.push(format!(
+                "[ID: E{}] [URL: {}] [SHA256: {}] [CREDIBILITY: {}]{}\n{}",
+                idx + 1,
+                evidence.source.url,
+                evidence.source.sha256_hex,
+                credibility,
+                validity,
+                snippet
+            ));
         }
 
         let evidence_text = if evidence_blocks.is_empty() {
@@ -102,46 +200,51 @@ impl ClaimVerifier for LlmVerifier {
             evidence_blocks.join("\n\n")
         };
 
-        let system_prompt = "You are a strict verification engine. Compare CLAIM to EVIDENCE only. \
-Output JSON with keys: status, reasoning. status must be one of SMOKE, GRAY_BLACK, GRAY_MID, GRAY_WHITE, WHITE. \
-SMOKE if evidence contradicts claim. WHITE if evidence supports claim. Use GRAY_* if insufficient.";
-
-        let user_prompt = format!("CLAIM:\n{}\n\nEVIDENCE:\n{}", fragment, evidence_text);
-
-        let request = CreateChatCompletionRequestArgs::default()
-            .model(&self.model)
-            .messages([
-                ChatCompletionRequestSystemMessageArgs::default()
-                    .content(system_prompt)
-                    .build()?
-                    .into(),
-                ChatCompletionRequestUserMessageArgs::default()
-                    .content(user_prompt)
-                    .build()?
-                    .into(),
-            ])
-            .build()?;
-
-        let response = self.client.chat().create(request).await?;
-        let content = response
-            .choices
-            .first()
-            .and_then(|c| c.message.content.clone())
-            .context("LLM response missing content")?;
-
-        let parsed: LlmResponse = serde_json::from_str(&content).unwrap_or(LlmResponse {
-            status: "GRAY_MID".to_string(),
-            confidence: 0.4,
-            reasoning: format!("Parse error: {}", content),
-        });
-
-        let status = Self::parse_status(&parsed.status);
-        let confidence = Self::confidence_adjust(parsed.confidence, evidence_count);
+        let vars = [
+            ("claim", fragment),
+            ("evidence", evidence_text.as_str()),
+            ("locale", ctx.locale_tag()),
+        ];
+        let locale = ctx.locale.as_deref();
+        let system_prompt = self.prompts.render_for("verifier.system", locale, &vars)?;
+        let user_prompt = self.prompts.render_for("verifier.user", locale, &vars)?;
+
+        let request = LlmRequest::new(system_prompt, user_prompt);
+        let parsed = complete_json::<LlmResponse>(self.client.as_ref(), request, "verification")
+            .await?
+            .unwrap_or_else(|failure| LlmResponse {
+                status: "GRAY_MID".to_string(),
+                confidence: None,
+                reasoning: format!("Parse error: {}", failure.raw),
+                stances: Vec::new(),
+            });
+
+        let stances = Self::resolve_stances(parsed.stances, &evidence);
+        let model_status = Self::parse_status(&parsed.status);
+        let status = if stances.is_empty() {
+            model_status
+        } else {
+            // OUTDATED は立場の集計では表せないのでモデルの判定を残す。
+            // それ以外もモデルの方が否定寄りならそちらを採る
+            let aggregate = self.aggregation.aggregate(&stances, &evidence);
+            if model_status == VerificationStatus::Outdated {
+                model_status
+            } else {
+                model_status.min(aggregate)
+            }
+        };
+        let raw_confidence = match parsed.confidence.and_then(validate_confidence) {
+            Some(raw) => self.calibration.apply(raw),
+            None => UNRATED_CONFIDENCE,
+        };
+        let confidence = Self::confidence_adjust(raw_confidence, evidence_weight(facts));
         let reason = Self::summarize_reasoning(&parsed.reasoning);
         Ok(VerificationResult {
             status,
             confidence,
             reason,
+            stances,
+            votes: Vec::new(),
         })
     }
 }
@@ -0,0 +1,229 @@
+use crate::models::{Evidence, RequiredFact, SourceMeta, TextSpan};
+use crate::pipeline::extract::extract_metadata;
+use crate::pipeline::http_cache::sha256_hex;
+use crate::pipeline::passage::{
+    rank_passages, split_passages, top_scored, Bm25Index, Bm25Ranker, Passage, PassageRanker,
+    PASSAGE_MAX_CHARS,
+};
+use crate::pipeline::traits::FactFetcher;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use url::Url;
+
+const DEFAULT_TOP_K: usize = 5;
+
+/// Offline `FactFetcher` over a local directory of HTML, Markdown, text and JSON files.
+/// The corpus is read and BM25-indexed once at construction; evidence points at
+/// `file://` URLs.
+pub struct LocalCorpusFetcher {
+    docs: Vec<SourceMeta>,
+    passages: Vec<Passage>,
+    // document index for each entry of `passages`
+    passage_doc: Vec<usize>,
+    index: Bm25Index,
+    // None: 事前計算した BM25 インデックスで順位付けする
+    ranker: Option<Arc<dyn PassageRanker>>,
+    top_k: usize,
+}
+
+impl LocalCorpusFetcher {
+    pub fn from_dir(dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let root = dir.as_ref().canonicalize()?;
+        let mut files = Vec::new();
+        collect_files(&root, &mut files)?;
+        files.sort();
+
+        let indexed_at = Utc::now().to_rfc3339();
+        let mut fetcher = Self {
+            docs: Vec::new(),
+            passages: Vec::new(),
+            passage_doc: Vec::new(),
+            index: Bm25Index::build(Bm25Ranker::default(), &[]),
+            ranker: None,
+            top_k: DEFAULT_TOP_K,
+        };
+        for path in files {
+            if let Err(err) = fetcher.index_file(&path, &indexed_at) {
+                eprintln!("corpus: skipped {} ({})", path.display(), err);
+            }
+        }
+        fetcher.index = Bm25Index::build(Bm25Ranker::default(), &fetcher.passages);
+        Ok(fetcher)
+    }
+
+    /// Rank with `ranker` instead of the precomputed BM25 index.
+    pub fn with_ranker(mut self, ranker: Arc<dyn PassageRanker>, top_k: usize) -> Self {
+        self.ranker = Some(ranker);
+        self.top_k = top_k;
+        self
+    }
+
+    pub fn with_top_k(mut self, top_k: usize) -> Self {
+        self.top_k = top_k;
+        self
+    }
+
+    pub fn doc_count(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn passage_count(&self) -> usize {
+        self.passages.len()
+    }
+
+    fn index_file(&mut self, path: &Path, indexed_at: &str) -> anyhow::Result<()> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+        let bytes = fs::read(path)?;
+        let raw = String::from_utf8_lossy(&bytes);
+        let url = Url::from_file_path(path)
+            .map_err(|_| anyhow::anyhow!("not an absolute path: {}", path.display()))?;
+
+        let mut title = None;
+        let mut published_at = None;
+        let mut modified_at = None;
+        let text = match ext.as_str() {
+            "html" | "htm" => {
+                let meta = extract_metadata(&raw, &url);
+                title = meta.title;
+                published_at = meta.published_at;
+                modified_at = meta.modified_at;
+                html2text::from_read(raw.as_bytes(), 80)
+            }
+            "json" => flatten_json(&serde_json::from_str(&raw)?),
+            _ => raw.to_string(),
+        };
+        if modified_at.is_none() {
+            modified_at = fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(|t| DateTime::<Utc>::from(t).to_rfc3339());
+        }
+
+        let doc = self.docs.len();
+        self.docs.push(SourceMeta {
+            url,
+            retrieved_at_rfc3339: indexed_at.to_string(),
+            sha256_hex: sha256_hex(&bytes),
+            previous_sha256_hex: None,
+            title,
+            published_at,
+            modified_at,
+            canonical_url: None,
+        });
+        for passage in split_passages(&text, PASSAGE_MAX_CHARS) {
+            self.passages.push(passage);
+            self.passage_doc.push(doc);
+        }
+        Ok(())
+    }
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, out)?;
+            continue;
+        }
+        let supported = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .is_some_and(|e| {
+                matches!(
+                    e.as_str(),
+                    "html" | "htm" | "md" | "markdown" | "txt" | "json"
+                )
+            });
+        if supported {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// One `path: value` line per scalar so JSON records can be ranked like text.
+fn flatten_json(value: &Value) -> String {
+    fn walk(prefix: &str, value: &Value, out: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (k, v) in map {
+                    let key = if prefix.is_empty() {
+                        k.clone()
+                    } else {
+                        format!("{}.{}", prefix, k)
+                    };
+                    walk(&key, v, out);
+                }
+            }
+            Value::Array(items) => {
+                for (i, v) in items.iter().enumerate() {
+                    walk(&format!("{}[{}]", prefix, i), v, out);
+                }
+                // keep array elements apart when packed into passages
+                out.push(String::new());
+            }
+            Value::Null => {}
+            Value::String(s) => out.push(format!("{}: {}", prefix, s)),
+            other => out.push(format!("{}: {}", prefix, other)),
+        }
+    }
+
+    let mut lines = Vec::new();
+    walk("", value, &mut lines);
+    lines.join("\n")
+}
+
+#[async_trait]
+impl FactFetcher for LocalCorpusFetcher {
+    async fn fetch(&self, fragment: &str) -> anyhow::Result<Vec<RequiredFact>> {
+        let ranked = match &self.ranker {
+            Some(ranker) => rank_passages(ranker.as_ref(), fragment, &self.passages, self.top_k),
+            None => top_scored(self.index.score(fragment), self.top_k),
+        };
+
+        // one fact per document, evidence in rank order
+        let mut by_doc: BTreeMap<usize, Vec<Evidence>> = BTreeMap::new();
+        let mut doc_order = Vec::new();
+        for idx in ranked {
+            let doc = self.passage_doc[idx];
+            let passage = &self.passages[idx];
+            if !by_doc.contains_key(&doc) {
+                doc_order.push(doc);
+            }
+            by_doc.entry(doc).or_default().push(
+                Evidence::new(self.docs[doc].clone(), passage.text.clone()).with_span(TextSpan {
+                    char_start: passage.char_start,
+                    char_end: passage.char_end,
+                }),
+            );
+        }
+
+        Ok(doc_order
+            .into_iter()
+            .map(|doc| {
+                RequiredFact::new(
+                    "source_url",
+                    self.docs[doc].url.to_string(),
+                    by_doc.remove(&doc).unwrap_or_default(),
+                )
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,56 @@
+use crate::lightweight::{normalize_tag_key, MeaningDb};
+use crate::models::{Evidence, RequiredFact, SourceMeta};
+use crate::pipeline::http_cache::sha256_hex;
+use crate::pipeline::traits::FactFetcher;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::path::PathBuf;
+use url::Url;
+
+/// Serves approved `tag=meaning` entries of the lightweight meaning DB as facts.
+/// The file is re-read on every fetch so approvals through the bridge apply immediately.
+pub struct MeaningDbFetcher {
+    path: PathBuf,
+}
+
+impl MeaningDbFetcher {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl FactFetcher for MeaningDbFetcher {
+    async fn fetch(&self, fragment: &str) -> anyhow::Result<Vec<RequiredFact>> {
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(v) => v,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let db = MeaningDb::parse(&content);
+        let fragment_key = normalize_tag_key(fragment).unwrap_or_default();
+        let retrieved_at = Utc::now().to_rfc3339();
+
+        let mut facts = Vec::new();
+        for (tag, meaning) in db.iter() {
+            let mentioned = fragment.contains(meaning.trim())
+                || (!fragment_key.is_empty() && fragment_key.contains(tag));
+            if !mentioned {
+                continue;
+            }
+            let line = format!("{}={}", tag, meaning);
+            let source = SourceMeta::new(
+                Url::parse(&format!("tuff-meaning:{}", tag))?,
+                sha256_hex(line.as_bytes()),
+            )
+            .with_retrieved_at(&retrieved_at);
+            facts.push(RequiredFact::new(
+                tag,
+                meaning,
+                vec![Evidence::new(source, line)],
+            ));
+        }
+        facts.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(facts)
+    }
+}
@@ -1,6 +1,8 @@
-use crate::models::{Abstract, RequiredFact, TagBits, TagGroupId, TopicId, VerificationStatus};
+use crate::models::{
+    Abstract, RequiredFact, TagBits, TagGroupId, TopicId, VerificationStatus, DUMMY_TAGS,
+};
 use crate::pipeline::traits::{
-    AbstractGenerator, ClaimVerifier, FactFetcher, InputSplitter, VerificationResult,
+    AbstractGenerator, ClaimVerifier, FactFetcher, IngestContext, InputSplitter, VerificationResult,
 };
 use async_trait::async_trait;
 
@@ -21,11 +23,7 @@ pub struct DummyFetcher;
 #[async_trait]
 impl FactFetcher for DummyFetcher {
     async fn fetch(&self, fragment: &str) -> anyhow::Result<Vec<RequiredFact>> {
-        Ok(vec![RequiredFact {
-            key: "mock".to_string(),
-            value: fragment.to_string(),
-            evidence: Vec::new(),
-        }])
+        Ok(vec![RequiredFact::new("mock", fragment, Vec::new())])
     }
 }
 
@@ -37,18 +35,23 @@ impl ClaimVerifier for DummyVerifier {
         &self,
         _fragment: &str,
         facts: &[RequiredFact],
+        _ctx: &IngestContext,
     ) -> anyhow::Result<VerificationResult> {
         if facts.is_empty() {
             Ok(VerificationResult {
                 status: VerificationStatus::GrayMid,
                 confidence: 0.4,
                 reason: "no evidence".to_string(),
+                stances: Vec::new(),
+                votes: Vec::new(),
             })
         } else {
             Ok(VerificationResult {
                 status: VerificationStatus::White,
                 confidence: 0.8,
                 reason: "dummy verifier".to_string(),
+                stances: Vec::new(),
+                votes: Vec::new(),
             })
         }
     }
@@ -63,12 +66,13 @@ impl AbstractGenerator for DummyAbstractGenerator {
         fragment: &str,
         _facts: &[RequiredFact],
         status: VerificationStatus,
+        _ctx: &IngestContext,
     ) -> anyhow::Result<Abstract> {
         let mut abstract_ = Abstract::new(
             TopicId::new(),
             TagGroupId::new(),
             TagBits {
-                tags: vec!["smoke".to_string(), "sanity".to_string()],
+                tags: DUMMY_TAGS.iter().map(|t| t.to_string()).collect(),
             },
         );
         abstract_.summary = format!("SMOKE: {}", fragment);
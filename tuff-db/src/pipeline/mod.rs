@@ -1,15 +1,67 @@
+pub mod calibration;
+pub mod composite;
+pub mod credibility;
+pub mod ensemble;
+pub mod extract;
 pub mod fetch;
 pub mod gap_resolver;
+pub mod http_cache;
 pub mod ingest;
 pub mod llm_abstractor;
+pub mod llm_client;
+pub mod llm_fixture;
+pub mod llm_splitter;
 pub mod llm_verifier;
+pub mod local_fetch;
+pub mod meaning_fetch;
 pub mod mock;
+pub mod passage;
+pub mod politeness;
+pub mod prompts;
+pub mod rule_verifier;
+pub mod search;
+pub mod stance;
+pub mod structured;
+pub mod temporal;
 pub mod traits;
 
+pub use calibration::ConfidenceCalibration;
+pub use composite::CompositeFetcher;
+pub use credibility::CredibilityRegistry;
+pub use ensemble::{
+    ensemble_weights_from_env, verifier_from_env, ConfiguredVerifier, EnsemblePolicy,
+    EnsembleVerifier, LightweightClaimVerifier,
+};
+pub use extract::{ExtractedPage, HtmlTable, PageMetadata};
 pub use fetch::WebFetcher;
 pub use gap_resolver::LlmGapResolver;
+pub use http_cache::{CachedResponse, HttpCache};
 pub use ingest::IngestPipeline;
 pub use llm_abstractor::LlmAbstractor;
+pub use llm_client::{
+    client_from_env, AnthropicClient, LlmClient, LlmMessage, LlmRequest, LlmRole, OpenAiClient,
+    ResponseSchema, ScriptedLlmClient,
+};
+pub use llm_fixture::{Cassette, LlmExchange, RecordingLlmClient, ReplayLlmClient};
+pub use llm_splitter::LlmClaimSplitter;
 pub use llm_verifier::LlmVerifier;
+pub use local_fetch::LocalCorpusFetcher;
+pub use meaning_fetch::MeaningDbFetcher;
 pub use mock::{DummyAbstractGenerator, DummyFetcher, DummySplitter, DummyVerifier};
-pub use traits::{AbstractGenerator, ClaimVerifier, FactFetcher, GapResolver, InputSplitter};
+pub use passage::{Bm25Index, Bm25Ranker, KeywordOverlapRanker, Passage, PassageRanker};
+pub use politeness::{FetchPolicy, PolicyError, RobotsRules};
+pub use prompts::PromptSet;
+pub use rule_verifier::{
+    extract_quantities, PartialDate, PrecheckVerifier, Quantity, QuantityValue, RuleVerifier,
+};
+pub use search::{FileSearchProvider, SearchHit, SearchProvider, StaticSearchProvider};
+pub use stance::StanceAggregation;
+pub use structured::{complete_json, extract_json, ParseFailure};
+pub use temporal::{
+    evidence_validity, extract_temporal, infer_validity, table_row_validity, TemporalAssessment,
+    TemporalCheck,
+};
+pub use traits::{
+    AbstractGenerator, AsyncInputSplitter, ClaimVerifier, FactFetcher, GapResolver, IngestContext,
+    InputSplitter,
+};
@@ -0,0 +1,298 @@
+use std::collections::{HashMap, HashSet};
+
+pub const PASSAGE_MAX_CHARS: usize = 400;
+
+#[derive(Clone, Debug)]
+pub struct Passage {
+    pub text: String,
+    /// Character offsets (not bytes) into the cleaned page text.
+    pub char_start: usize,
+    pub char_end: usize,
+}
+
+/// Scores passages against a query; higher is more relevant.
+pub trait PassageRanker: Send + Sync {
+    fn score(&self, query: &str, passages: &[Passage]) -> Vec<f32>;
+}
+
+/// Fraction of query terms present in the passage.
+pub struct KeywordOverlapRanker;
+
+impl PassageRanker for KeywordOverlapRanker {
+    fn score(&self, query: &str, passages: &[Passage]) -> Vec<f32> {
+        let terms: HashSet<String> = tokenize(query).into_iter().collect();
+        if terms.is_empty() {
+            return vec![0.0; passages.len()];
+        }
+        passages
+            .iter()
+            .map(|p| {
+                let doc: HashSet<String> = tokenize(&p.text).into_iter().collect();
+                terms.iter().filter(|t| doc.contains(*t)).count() as f32 / terms.len() as f32
+            })
+            .collect()
+    }
+}
+
+/// Okapi BM25 with the passages of one page as the corpus.
+#[derive(Clone, Copy, Debug)]
+pub struct Bm25Ranker {
+    pub k1: f32,
+    pub b: f32,
+}
+
+impl Default for Bm25Ranker {
+    fn default() -> Self {
+        Self { k1: 1.2, b: 0.75 }
+    }
+}
+
+impl PassageRanker for Bm25Ranker {
+    fn score(&self, query: &str, passages: &[Passage]) -> Vec<f32> {
+        Bm25Index::build(*self, passages).score(query)
+    }
+}
+
+/// Term and document frequencies of a fixed passage set, computed once so a
+/// corpus can be queried repeatedly without re-tokenizing it.
+#[derive(Clone, Debug)]
+pub struct Bm25Index {
+    params: Bm25Ranker,
+    docs: Vec<HashMap<String, usize>>,
+    lengths: Vec<f32>,
+    df: HashMap<String, usize>,
+    avg_len: f32,
+}
+
+impl Bm25Index {
+    pub fn build(params: Bm25Ranker, passages: &[Passage]) -> Self {
+        let docs: Vec<HashMap<String, usize>> = passages
+            .iter()
+            .map(|p| {
+                let mut tf = HashMap::new();
+                for token in tokenize(&p.text) {
+                    *tf.entry(token).or_insert(0) += 1;
+                }
+                tf
+            })
+            .collect();
+        let lengths: Vec<f32> = docs
+            .iter()
+            .map(|d| d.values().sum::<usize>() as f32)
+            .collect();
+        let mut df = HashMap::new();
+        for doc in &docs {
+            for term in doc.keys() {
+                *df.entry(term.clone()).or_insert(0) += 1;
+            }
+        }
+        let avg_len = if docs.is_empty() {
+            1.0
+        } else {
+            (lengths.iter().sum::<f32>() / docs.len() as f32).max(1.0)
+        };
+        Self {
+            params,
+            docs,
+            lengths,
+            df,
+            avg_len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    /// One score per indexed passage, in index order.
+    pub fn score(&self, query: &str) -> Vec<f32> {
+        let terms: HashSet<String> = tokenize(query).into_iter().collect();
+        if terms.is_empty() || self.docs.is_empty() {
+            return vec![0.0; self.docs.len()];
+        }
+
+        let n = self.docs.len() as f32;
+        let idf: HashMap<&String, f32> = terms
+            .iter()
+            .map(|t| {
+                let df = *self.df.get(t).unwrap_or(&0) as f32;
+                (t, ((n - df + 0.5) / (df + 0.5) + 1.0).ln())
+            })
+            .collect();
+
+        let Bm25Ranker { k1, b } = self.params;
+        self.docs
+            .iter()
+            .zip(self.lengths.iter())
+            .map(|(doc, len)| {
+                terms
+                    .iter()
+                    .map(|t| {
+                        let tf = *doc.get(t).unwrap_or(&0) as f32;
+                        if tf == 0.0 {
+                            return 0.0;
+                        }
+                        let norm = k1 * (1.0 - b + b * len / self.avg_len);
+                        idf[t] * tf * (k1 + 1.0) / (tf + norm)
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+/// Pick a ranker by name (`bm25` | `keyword`); unknown names fall back to BM25.
+pub fn ranker_from_name(name: &str) -> Box<dyn PassageRanker> {
+    match name.trim().to_lowercase().as_str() {
+        "keyword" | "overlap" => Box::new(KeywordOverlapRanker),
+        _ => Box::new(Bm25Ranker::default()),
+    }
+}
+
+/// Lowercased ASCII words plus character bigrams for runs of other letters
+/// (Japanese text has no word boundaries).
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut run: Vec<char> = Vec::new();
+
+    let flush_run = |run: &mut Vec<char>, tokens: &mut Vec<String>| {
+        if run.len() == 1 {
+            tokens.push(run[0].to_string());
+        } else {
+            for pair in run.windows(2) {
+                tokens.push(pair.iter().collect());
+            }
+        }
+        run.clear();
+    };
+
+    for ch in text.chars() {
+        if ch.is_ascii_alphanumeric() {
+            if !run.is_empty() {
+                flush_run(&mut run, &mut tokens);
+            }
+            word.push(ch.to_ascii_lowercase());
+        } else if ch.is_alphanumeric() {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            run.push(ch);
+        } else {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            if !run.is_empty() {
+                flush_run(&mut run, &mut tokens);
+            }
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    if !run.is_empty() {
+        flush_run(&mut run, &mut tokens);
+    }
+    tokens
+}
+
+/// Split cleaned page text into passages of at most `max_chars` characters.
+/// Lines are packed together until the limit; longer lines are cut at sentence ends.
+pub fn split_passages(text: &str, max_chars: usize) -> Vec<Passage> {
+    let max_chars = max_chars.max(1);
+    let chars: Vec<char> = text.chars().collect();
+    let mut passages = Vec::new();
+    let mut start = 0usize;
+    let mut pos = 0usize;
+    let mut last_break: Option<usize> = None;
+
+    while pos < chars.len() {
+        let ch = chars[pos];
+        pos += 1;
+        if matches!(ch, '\n' | '。' | '．' | '！' | '？' | '.' | '!' | '?') {
+            last_break = Some(pos);
+        }
+        let blank_line = ch == '\n' && pos >= 2 && chars[pos - 2] == '\n';
+        if blank_line || pos - start >= max_chars {
+            let end = if blank_line {
+                pos
+            } else {
+                last_break.filter(|b| *b > start).unwrap_or(pos)
+            };
+            push_passage(&chars, start, end, &mut passages);
+            start = end;
+            pos = end;
+            last_break = None;
+        }
+    }
+    push_passage(&chars, start, chars.len(), &mut passages);
+    passages
+}
+
+fn push_passage(chars: &[char], start: usize, end: usize, out: &mut Vec<Passage>) {
+    if start >= end {
+        return;
+    }
+    let raw: String = chars[start..end].iter().collect();
+    let leading = raw.chars().take_while(|c| c.is_whitespace()).count();
+    let text = raw.trim();
+    if text.is_empty() {
+        return;
+    }
+    let char_start = start + leading;
+    out.push(Passage {
+        text: text.to_string(),
+        char_start,
+        char_end: char_start + text.chars().count(),
+    });
+}
+
+/// Indices of the `k` best-scoring passages (score > 0), best first.
+pub fn rank_passages(
+    ranker: &dyn PassageRanker,
+    query: &str,
+    passages: &[Passage],
+    k: usize,
+) -> Vec<usize> {
+    if passages.is_empty() || k == 0 {
+        return Vec::new();
+    }
+    top_scored(ranker.score(query, passages), k)
+}
+
+/// Indices of the `k` best positive scores, best first (ties keep input order).
+pub fn top_scored(scores: Vec<f32>, k: usize) -> Vec<usize> {
+    let mut ranked: Vec<(usize, f32)> = scores
+        .into_iter()
+        .enumerate()
+        .filter(|(_, s)| *s > 0.0)
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranked.into_iter().take(k).map(|(idx, _)| idx).collect()
+}
+
+/// Top `k` passages for `query`, best first. When nothing matches, the
+/// leading passage is returned so the caller still has something to show.
+pub fn select_passages(
+    ranker: &dyn PassageRanker,
+    query: &str,
+    text: &str,
+    k: usize,
+) -> Vec<Passage> {
+    let passages = split_passages(text, PASSAGE_MAX_CHARS);
+    if k == 0 {
+        return Vec::new();
+    }
+    let ranked = rank_passages(ranker, query, &passages, k);
+    if ranked.is_empty() {
+        return passages.into_iter().take(1).collect();
+    }
+    ranked
+        .into_iter()
+        .map(|idx| passages[idx].clone())
+        .collect()
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+use url::Url;
+
+pub const USER_AGENT: &str = "TUFF-DB/0.1";
+
+/// Crawl politeness knobs for `WebFetcher`.
+#[derive(Clone, Debug)]
+pub struct FetchPolicy {
+    pub respect_robots: bool,
+    pub max_concurrent_per_host: usize,
+    /// Minimum delay between two requests to the same host.
+    pub min_interval: Duration,
+    pub max_redirects: usize,
+    pub max_body_bytes: usize,
+    /// Accepted `Content-Type` prefixes (compared case-insensitively).
+    pub allowed_content_types: Vec<String>,
+    pub timeout: Duration,
+}
+
+impl Default for FetchPolicy {
+    fn default() -> Self {
+        Self {
+            respect_robots: true,
+            max_concurrent_per_host: 2,
+            min_interval: Duration::from_millis(500),
+            max_redirects: 5,
+            max_body_bytes: 4 * 1024 * 1024,
+            allowed_content_types: vec![
+                "text/html".to_string(),
+                "application/xhtml+xml".to_string(),
+            ],
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl FetchPolicy {
+    /// Defaults overridden by `TUFF_FETCH_*` environment variables.
+    pub fn from_env() -> Self {
+        fn parse<T: std::str::FromStr>(key: &str) -> Option<T> {
+            env::var(key).ok().and_then(|v| v.trim().parse::<T>().ok())
+        }
+
+        let mut policy = Self::default();
+        if let Ok(v) = env::var("TUFF_FETCH_RESPECT_ROBOTS") {
+            policy.respect_robots = v.trim() != "0";
+        }
+        if let Some(v) = parse::<usize>("TUFF_FETCH_MAX_PER_HOST") {
+            policy.max_concurrent_per_host = v.max(1);
+        }
+        if let Some(v) = parse::<u64>("TUFF_FETCH_MIN_INTERVAL_MS") {
+            policy.min_interval = Duration::from_millis(v);
+        }
+        if let Some(v) = parse::<usize>("TUFF_FETCH_MAX_REDIRECTS") {
+            policy.max_redirects = v;
+        }
+        if let Some(v) = parse::<usize>("TUFF_FETCH_MAX_BYTES") {
+            policy.max_body_bytes = v;
+        }
+        if let Ok(v) = env::var("TUFF_FETCH_CONTENT_TYPES") {
+            let types: Vec<String> = v
+                .split(',')
+                .map(|t| t.trim().to_lowercase())
+                .filter(|t| !t.is_empty())
+                .collect();
+            if !types.is_empty() {
+                policy.allowed_content_types = types;
+            }
+        }
+        if let Some(v) = parse::<u64>("TUFF_FETCH_TIMEOUT_SECS") {
+            policy.timeout = Duration::from_secs(v);
+        }
+        policy
+    }
+
+    pub fn content_type_allowed(&self, content_type: Option<&str>) -> bool {
+        let Some(raw) = content_type else {
+            return false;
+        };
+        let mime = raw.split(';').next().unwrap_or("").trim().to_lowercase();
+        self.allowed_content_types
+            .iter()
+            .any(|allowed| mime.starts_with(allowed.as_str()))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PolicyError {
+    #[error("disallowed by robots.txt: {0}")]
+    RobotsDisallowed(Url),
+    #[error("too many redirects (max {max}) starting at {url}")]
+    TooManyRedirects { url: Url, max: usize },
+    #[error("redirect without valid Location from {0}")]
+    BadRedirect(Url),
+    #[error("response from {url} exceeds {max} bytes")]
+    BodyTooLarge { url: Url, max: usize },
+    #[error("unsupported content type {content_type:?} from {url}")]
+    UnsupportedContentType {
+        url: Url,
+        content_type: Option<String>,
+    },
+}
+
+/// Allow/Disallow rules from robots.txt that apply to our user agent.
+#[derive(Clone, Debug, Default)]
+pub struct RobotsRules {
+    // (allow, path pattern)
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Uses the group naming `user_agent` (product token, case-insensitive)
+    /// when present, otherwise the `*` group.
+    pub fn parse(body: &str, user_agent: &str) -> Self {
+        let token = user_agent
+            .split('/')
+            .next()
+            .unwrap_or(user_agent)
+            .trim()
+            .to_lowercase();
+
+        let mut specific: Vec<(bool, String)> = Vec::new();
+        let mut wildcard: Vec<(bool, String)> = Vec::new();
+        let mut has_specific = false;
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in body.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let field = field.trim().to_lowercase();
+            let value = value.trim();
+            match field.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        group_agents.clear();
+                        in_rules = false;
+                    }
+                    let agent = value.to_lowercase();
+                    if agent != "*" && token.starts_with(&agent) {
+                        has_specific = true;
+                    }
+                    group_agents.push(agent);
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // an empty Disallow means "allow everything"
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = (field == "allow", value.to_string());
+                    for agent in &group_agents {
+                        if agent == "*" {
+                            wildcard.push(rule.clone());
+                        } else if token.starts_with(agent.as_str()) {
+                            specific.push(rule.clone());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            rules: if has_specific { specific } else { wildcard },
+        }
+    }
+
+    /// Longest matching pattern wins; Allow wins ties. Patterns follow
+    /// RFC 9309: `*` matches any run of characters and a trailing `$`
+    /// anchors the end of the path.
+    pub fn allows(&self, path: &str) -> bool {
+        let mut best: Option<(usize, bool)> = None;
+        for (allow, pattern) in &self.rules {
+            if pattern_matches(pattern, path) {
+                let len = pattern.len();
+                match best {
+                    Some((best_len, best_allow))
+                        if best_len > len || (best_len == len && best_allow) => {}
+                    _ => best = Some((len, *allow)),
+                }
+            }
+        }
+        best.map(|(_, allow)| allow).unwrap_or(true)
+    }
+}
+
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let mut pieces = pattern.split('*');
+    // '*' を含まない先頭部分は前方一致
+    let Some(mut rest) = pieces.next().and_then(|head| path.strip_prefix(head)) else {
+        return false;
+    };
+    let pieces: Vec<&str> = pieces.collect();
+    let Some((last, middle)) = pieces.split_last() else {
+        return !anchored || rest.is_empty();
+    };
+    // 途中の部分は最も左の出現に合わせる（後続の照合範囲を最大に残す）
+    for piece in middle {
+        match rest.find(piece) {
+            Some(i) => rest = &rest[i + piece.len()..],
+            None => return false,
+        }
+    }
+    if anchored {
+        rest.ends_with(last)
+    } else {
+        rest.contains(last)
+    }
+}
+
+struct HostGate {
+    permits: Arc<Semaphore>,
+    next_slot: Mutex<Instant>,
+}
+
+/// Per-host concurrency and request-rate limiting.
+pub struct HostThrottle {
+    max_concurrent: usize,
+    min_interval: Duration,
+    gates: Mutex<HashMap<String, Arc<HostGate>>>,
+}
+
+impl HostThrottle {
+    pub fn new(max_concurrent: usize, min_interval: Duration) -> Self {
+        Self {
+            max_concurrent: max_concurrent.max(1),
+            min_interval,
+            gates: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Waits for a free slot on the host; the request may run while the permit is held.
+    pub async fn acquire(&self, host: &str) -> OwnedSemaphorePermit {
+        let gate = {
+            let mut gates = self.gates.lock().await;
+            Arc::clone(gates.entry(host.to_string()).or_insert_with(|| {
+                Arc::new(HostGate {
+                    permits: Arc::new(Semaphore::new(self.max_concurrent)),
+                    next_slot: Mutex::new(Instant::now()),
+                })
+            }))
+        };
+        let permit = Arc::clone(&gate.permits)
+            .acquire_owned()
+            .await
+            .expect("host semaphore closed");
+
+        let wait_until = {
+            let mut next_slot = gate.next_slot.lock().await;
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.min_interval;
+            slot
+        };
+        tokio::time::sleep_until(wait_until).await;
+        permit
+    }
+}
+
+pub fn host_key(url: &Url) -> String {
+    match url.port_or_known_default() {
+        Some(port) => format!("{}:{}", url.host_str().unwrap_or(""), port),
+        None => url.host_str().unwrap_or("").to_string(),
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+const BUILTIN: &[(&str, &str)] = &[
+    (
+        "verifier.system",
+        include_str!("../../prompts/default/verifier.system.txt"),
+    ),
+    (
+        "verifier.user",
+        include_str!("../../prompts/default/verifier.user.txt"),
+    ),
+    (
+        "abstractor.system",
+        include_str!("../../prompts/default/abstractor.system.txt"),
+    ),
+    (
+        "verifier.system.ja",
+        include_str!("../../prompts/default/verifier.system.ja.txt"),
+    ),
+    (
+        "abstractor.user",
+        include_str!("../../prompts/default/abstractor.user.txt"),
+    ),
+    (
+        "abstractor.system.ja",
+        include_str!("../../prompts/default/abstractor.system.ja.txt"),
+    ),
+    (
+        "gap_resolver.system",
+        include_str!("../../prompts/default/gap_resolver.system.txt"),
+    ),
+    (
+        "gap_resolver.user",
+        include_str!("../../prompts/default/gap_resolver.user.txt"),
+    ),
+    (
+        "gap_resolver.system.ja",
+        include_str!("../../prompts/default/gap_resolver.system.ja.txt"),
+    ),
+    (
+        "splitter.system",
+        include_str!("../../prompts/default/splitter.system.txt"),
+    ),
+    (
+        "splitter.system.ja",
+        include_str!("../../prompts/default/splitter.system.ja.txt"),
+    ),
+    (
+        "splitter.user",
+        include_str!("../../prompts/default/splitter.user.txt"),
+    ),
+];
+const BUILTIN_VERSION: &str = include_str!("../../prompts/default/VERSION");
+
+/// A versioned set of prompt templates.
+///
+/// Templates use `{{name}}` placeholders: `claim`, `evidence`, `status`,
+/// `locale` (BCP 47 tag, `und` when unknown), `output_language`,
+/// `internal_state`, `input`. A template may have locale variants named
+/// `<template>.<locale>` (e.g. `verifier.system.ja`), picked by `render_for`.
+/// The version id ends up on every `Abstract` / `Transition` produced with the set.
+#[derive(Clone, Debug)]
+pub struct PromptSet {
+    version: String,
+    templates: HashMap<String, String>,
+}
+
+impl Default for PromptSet {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl PromptSet {
+    /// The set shipped in `tuff-db/prompts/default`, compiled in.
+    pub fn builtin() -> Self {
+        Self {
+            version: BUILTIN_VERSION.trim().to_string(),
+            templates: BUILTIN
+                .iter()
+                .map(|(name, body)| (name.to_string(), strip_newline(body)))
+                .collect(),
+        }
+    }
+
+    /// Load `<dir>/VERSION` and `<dir>/<template>.txt`. Templates missing from
+    /// the directory keep the built-in text (and built-in locale variants).
+    pub fn from_dir(dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let dir = dir.as_ref();
+        let version = fs::read_to_string(dir.join("VERSION"))
+            .map_err(|err| anyhow::anyhow!("prompt set {}: VERSION: {}", dir.display(), err))?
+            .trim()
+            .to_string();
+        if version.is_empty() {
+            anyhow::bail!("prompt set {}: empty VERSION", dir.display());
+        }
+        let mut loaded = HashMap::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(name) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(".txt"))
+            else {
+                continue;
+            };
+            loaded.insert(name.to_string(), strip_newline(&fs::read_to_string(&path)?));
+        }
+        let mut set = Self::builtin();
+        set.version = version;
+        // an overridden template drops the built-in locale variants of it
+        set.templates.retain(|name, _| {
+            !loaded
+                .keys()
+                .any(|base| name.starts_with(&format!("{}.", base)))
+        });
+        set.templates.extend(loaded);
+        Ok(set)
+    }
+
+    /// `TUFF_PROMPT_DIR=<dir>` selects a prompt set; otherwise the built-in one.
+    pub fn from_env() -> anyhow::Result<Self> {
+        match env::var("TUFF_PROMPT_DIR") {
+            Ok(dir) if !dir.trim().is_empty() => Self::from_dir(dir.trim()),
+            _ => Ok(Self::builtin()),
+        }
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Render the variant of `name` for `locale`: `name.<tag>` (`ja-jp`),
+    /// then `name.<language>` (`ja`), then `name` itself.
+    pub fn render_for(
+        &self,
+        name: &str,
+        locale: Option<&str>,
+        vars: &[(&str, &str)],
+    ) -> anyhow::Result<String> {
+        self.render(&self.resolve_name(name, locale), vars)
+    }
+
+    fn resolve_name(&self, name: &str, locale: Option<&str>) -> String {
+        if let Some(tag) = locale.map(|l| l.trim().replace('_', "-").to_ascii_lowercase()) {
+            let language = tag.split('-').next().unwrap_or_default();
+            for suffix in [tag.as_str(), language] {
+                if suffix.is_empty() || suffix == "und" {
+                    continue;
+                }
+                let candidate = format!("{}.{}", name, suffix);
+                if self.templates.contains_key(&candidate) {
+                    return candidate;
+                }
+            }
+        }
+        name.to_string()
+    }
+
+    /// Fill `{{var}}` placeholders. Unknown templates and placeholders without
+    /// a value are errors, so a broken prompt set fails loudly.
+    pub fn render(&self, name: &str, vars: &[(&str, &str)]) -> anyhow::Result<String> {
+        let template = self
+            .templates
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("prompt set {}: no template {}", self.version, name))?;
+
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template.as_str();
+        while let Some(open) = rest.find("{{") {
+            out.push_str(&rest[..open]);
+            let after = &rest[open + 2..];
+            let close = after.find("}}").ok_or_else(|| {
+                anyhow::anyhow!("prompt {}/{}: unclosed placeholder", self.version, name)
+            })?;
+            let var = after[..close].trim();
+            let value = vars
+                .iter()
+                .find(|(k, _)| *k == var)
+                .map(|(_, v)| *v)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "prompt {}/{}: no value for {{{{{}}}}}",
+                        self.version,
+                        name,
+                        var
+                    )
+                })?;
+            out.push_str(value);
+            rest = &after[close + 2..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+}
+
+/// English name of a locale's language for use inside prompts
+/// (`ja-JP` -> `Japanese`); unknown languages keep the tag itself.
+pub fn language_name(locale: &str) -> String {
+    let language = locale
+        .trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let name = match language.as_str() {
+        "ja" => "Japanese",
+        "en" => "English",
+        "zh" => "Chinese",
+        "ko" => "Korean",
+        "fr" => "French",
+        "de" => "German",
+        "es" => "Spanish",
+        _ => return locale.trim().to_string(),
+    };
+    name.to_string()
+}
+
+fn strip_newline(body: &str) -> String {
+    body.strip_suffix('\n').unwrap_or(body).to_string()
+}
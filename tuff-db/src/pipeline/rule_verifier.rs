@@ -0,0 +1,888 @@
+use crate::models::{
+    Evidence, EvidenceStance, RequiredFact, Stance, VerificationStatus, VerifierVote,
+};
+use crate::pipeline::passage::tokenize;
+use crate::pipeline::traits::{ClaimVerifier, IngestContext, VerificationResult};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use std::collections::HashSet;
+use std::fmt;
+
+// 和暦の元年 (西暦)
+const ERAS: &[(&str, i32)] = &[
+    ("令和", 2019),
+    ("平成", 1989),
+    ("昭和", 1926),
+    ("大正", 1912),
+    ("明治", 1868),
+];
+
+const MONTHS: &[&str] = &[
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+// (表記, 正規化後の単位) 長いものから照合する
+const UNITS: &[(&str, &str)] = &[
+    ("パーセント", "%"),
+    ("percent", "%"),
+    ("dollars", "ドル"),
+    ("people", "人"),
+    ("議席", "議席"),
+    ("ドル", "ドル"),
+    ("yen", "円"),
+    ("usd", "ドル"),
+    ("km", "km"),
+    ("kg", "kg"),
+    ("%", "%"),
+    ("円", "円"),
+    ("人", "人"),
+    ("名", "人"),
+    ("代", "代"),
+    ("位", "位"),
+    ("回", "回"),
+    ("歳", "歳"),
+    ("件", "件"),
+    ("票", "票"),
+    ("年", "年"),
+];
+
+// 序数・個数: 「約」等が無ければ完全一致
+const EXACT_UNITS: &[&str] = &["代", "位", "回", "歳", "議席", "票"];
+
+const APPROX_MARKERS: &[&str] = &[
+    "約",
+    "およそ",
+    "ほぼ",
+    "about",
+    "approximately",
+    "around",
+    "roughly",
+    "nearly",
+];
+
+// 数値の前後を見る範囲 (文字数) と、そこで打ち切る節の区切り
+const CONTEXT_CHARS: usize = 12;
+const CLAUSE_DELIMITERS: &[char] = &[
+    '。', '、', ',', '.', ';', '；', '・', '\n', '(', ')', '（', '）', '「', '」', '!', '?', '！',
+    '？',
+];
+
+const MULTIPLIERS: &[(&str, f64)] = &[
+    ("thousand", 1e3),
+    ("million", 1e6),
+    ("billion", 1e9),
+    ("trillion", 1e12),
+];
+
+/// A calendar date known to year, month or day precision.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PartialDate {
+    pub year: i32,
+    pub month: Option<u32>,
+    pub day: Option<u32>,
+}
+
+impl PartialDate {
+    fn precision(&self) -> u8 {
+        match (self.month, self.day) {
+            (Some(_), Some(_)) => 3,
+            (Some(_), None) => 2,
+            _ => 1,
+        }
+    }
+
+    fn to_naive(self) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(self.year, self.month?, self.day?)
+    }
+
+    /// First calendar day the date could refer to (`2025` -> 2025-01-01).
+    pub fn first_day(self) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(self.year, self.month.unwrap_or(1), self.day.unwrap_or(1))
+    }
+
+    /// Last calendar day the date could refer to (`2025-02` -> 2025-02-28).
+    pub fn last_day(self) -> Option<NaiveDate> {
+        match (self.month, self.day) {
+            (Some(month), Some(day)) => NaiveDate::from_ymd_opt(self.year, month, day),
+            (Some(12), None) | (None, _) => NaiveDate::from_ymd_opt(self.year, 12, 31),
+            (Some(month), None) => NaiveDate::from_ymd_opt(self.year, month + 1, 1)?.pred_opt(),
+        }
+    }
+}
+
+impl fmt::Display for PartialDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.month, self.day) {
+            (Some(m), Some(d)) => write!(f, "{:04}-{:02}-{:02}", self.year, m, d),
+            (Some(m), None) => write!(f, "{:04}-{:02}", self.year, m),
+            _ => write!(f, "{:04}", self.year),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum QuantityValue {
+    Date(PartialDate),
+    Number {
+        value: f64,
+        unit: Option<String>,
+        approx: bool,
+    },
+}
+
+impl fmt::Display for QuantityValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuantityValue::Date(date) => write!(f, "date {}", date),
+            QuantityValue::Number { value, unit, .. } => {
+                if value.fract() == 0.0 && value.abs() < 1e15 {
+                    write!(f, "number {}", *value as i64)?;
+                } else {
+                    write!(f, "number {}", value)?;
+                }
+                match unit {
+                    Some(unit) => write!(f, " {}", unit),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// A date or number found in text, with the text it was read from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quantity {
+    pub value: QuantityValue,
+    pub text: String,
+    /// Character offsets (not bytes) of the quantity in the source text.
+    pub char_start: usize,
+    pub char_end: usize,
+}
+
+/// Dates (ISO, `2025年10月21日`, `令和7年10月21日`, `October 21, 2025`) and
+/// numbers with optional unit (`1億2500万人`, `一億二千五百万人`, `125 million`,
+/// `第102代`). Month/day without a year is skipped.
+pub fn extract_quantities(text: &str) -> Vec<Quantity> {
+    let chars: Vec<char> = text.chars().map(normalize_char).collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let boundary =
+            i == 0 || !(is_numeral(chars[i - 1]) || chars[i - 1].is_ascii_alphanumeric());
+        let found = if boundary {
+            era_date(&chars, i)
+                .or_else(|| iso_date(&chars, i))
+                .or_else(|| english_date(&chars, i))
+                .or_else(|| number_or_date(&chars, i))
+        } else {
+            None
+        };
+        match found {
+            Some((quantity, end)) => {
+                if let Some(value) = quantity {
+                    out.push(Quantity {
+                        value,
+                        text: chars[i..end].iter().collect::<String>().trim().to_string(),
+                        char_start: i,
+                        char_end: end,
+                    });
+                }
+                i = end.max(i + 1);
+            }
+            None => i += 1,
+        }
+    }
+    out
+}
+
+fn normalize_char(c: char) -> char {
+    match c {
+        '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+        '％' => '%',
+        '．' => '.',
+        '，' => ',',
+        '／' => '/',
+        '－' => '-',
+        '　' => ' ',
+        _ => c,
+    }
+}
+
+fn kanji_digit(c: char) -> Option<u32> {
+    Some(match c {
+        '〇' | '零' => 0,
+        '一' => 1,
+        '二' => 2,
+        '三' => 3,
+        '四' => 4,
+        '五' => 5,
+        '六' => 6,
+        '七' => 7,
+        '八' => 8,
+        '九' => 9,
+        _ => return None,
+    })
+}
+
+fn is_numeral(c: char) -> bool {
+    c.is_ascii_digit()
+        || kanji_digit(c).is_some()
+        || matches!(c, '十' | '百' | '千' | '万' | '億' | '兆')
+}
+
+struct Numeral {
+    value: f64,
+    end: usize,
+    kanji_only: bool,
+    has_large_unit: bool,
+}
+
+/// Arabic and/or kanji numeral starting at `start`: `1,234.5`, `二〇二五`,
+/// `二十五`, `1億2500万`.
+fn numeral(chars: &[char], start: usize) -> Option<Numeral> {
+    let mut total = 0.0f64; // 万/億/兆 で確定した部分
+    let mut section = 0.0f64; // 十/百/千 で確定した部分
+    let mut current: Option<f64> = None;
+    let mut fraction: Option<f64> = None; // 小数部の桁
+    let mut kanji_only = true;
+    let mut has_large_unit = false;
+    let mut i = start;
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some(d) = c.to_digit(10).or_else(|| kanji_digit(c)) {
+            if c.is_ascii_digit() {
+                kanji_only = false;
+            }
+            let d = d as f64;
+            current = Some(match fraction {
+                Some(scale) => {
+                    fraction = Some(scale / 10.0);
+                    current.unwrap_or(0.0) + d * scale
+                }
+                None => current.unwrap_or(0.0) * 10.0 + d,
+            });
+        } else if c == ','
+            && current.is_some()
+            && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())
+        {
+            // 桁区切り
+        } else if c == '.'
+            && current.is_some()
+            && fraction.is_none()
+            && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())
+        {
+            fraction = Some(0.1);
+        } else if let Some(mult) = match c {
+            '十' => Some(10.0),
+            '百' => Some(100.0),
+            '千' => Some(1000.0),
+            _ => None,
+        } {
+            section += current.take().unwrap_or(1.0) * mult;
+            fraction = None;
+        } else if let Some(mult) = match c {
+            '万' => Some(1e4),
+            '億' => Some(1e8),
+            '兆' => Some(1e12),
+            _ => None,
+        } {
+            if current.is_none() && section == 0.0 {
+                break;
+            }
+            total += (section + current.take().unwrap_or(0.0)) * mult;
+            section = 0.0;
+            fraction = None;
+            has_large_unit = true;
+        } else {
+            break;
+        }
+        i += 1;
+    }
+    if i == start {
+        return None;
+    }
+    Some(Numeral {
+        value: total + section + current.unwrap_or(0.0),
+        end: i,
+        kanji_only,
+        has_large_unit,
+    })
+}
+
+fn starts_with_at(chars: &[char], at: usize, word: &str) -> bool {
+    let mut idx = at;
+    for w in word.chars() {
+        match chars.get(idx) {
+            Some(c) if c.to_ascii_lowercase() == w => idx += 1,
+            _ => return false,
+        }
+    }
+    true
+}
+
+fn skip_spaces(chars: &[char], mut i: usize) -> usize {
+    while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+        i += 1;
+    }
+    i
+}
+
+/// `<n>月[<m>日]` after a year; returns (month, day, end).
+fn month_day(chars: &[char], i: usize) -> (Option<u32>, Option<u32>, usize) {
+    let Some(month) = numeral(chars, i).filter(|n| chars.get(n.end) == Some(&'月')) else {
+        return (None, None, i);
+    };
+    let m = month.value as u32;
+    if !(1..=12).contains(&m) {
+        return (None, None, i);
+    }
+    let after_month = month.end + 1;
+    match numeral(chars, after_month).filter(|n| chars.get(n.end) == Some(&'日')) {
+        Some(day) if (1..=31).contains(&(day.value as u32)) => {
+            (Some(m), Some(day.value as u32), day.end + 1)
+        }
+        _ => (Some(m), None, after_month),
+    }
+}
+
+fn era_date(chars: &[char], i: usize) -> Option<(Option<QuantityValue>, usize)> {
+    let (name, base) = ERAS
+        .iter()
+        .find(|(name, _)| starts_with_at(chars, i, name))?;
+    let at = i + name.chars().count();
+    let (year, after) = if chars.get(at) == Some(&'元') {
+        (1, at + 1)
+    } else {
+        let n = numeral(chars, at)?;
+        (n.value as i32, n.end)
+    };
+    if chars.get(after) != Some(&'年') || year < 1 {
+        return None;
+    }
+    let (month, day, end) = month_day(chars, after + 1);
+    Some((
+        Some(QuantityValue::Date(PartialDate {
+            year: base + year - 1,
+            month,
+            day,
+        })),
+        end,
+    ))
+}
+
+fn iso_date(chars: &[char], i: usize) -> Option<(Option<QuantityValue>, usize)> {
+    let digits = |from: usize, min: usize, max: usize| -> Option<(u32, usize)> {
+        let mut end = from;
+        while end < chars.len() && end - from < max && chars[end].is_ascii_digit() {
+            end += 1;
+        }
+        if end - from < min {
+            return None;
+        }
+        let s: String = chars[from..end].iter().collect();
+        Some((s.parse().ok()?, end))
+    };
+    let (year, end) = digits(i, 4, 4)?;
+    let sep = *chars.get(end)?;
+    if sep != '-' && sep != '/' {
+        return None;
+    }
+    let (month, end) = digits(end + 1, 1, 2)?;
+    if chars.get(end) != Some(&sep) {
+        return None;
+    }
+    let (day, end) = digits(end + 1, 1, 2)?;
+    NaiveDate::from_ymd_opt(year as i32, month, day)?;
+    Some((
+        Some(QuantityValue::Date(PartialDate {
+            year: year as i32,
+            month: Some(month),
+            day: Some(day),
+        })),
+        end,
+    ))
+}
+
+/// `October 21, 2025` / `October 2025`.
+fn english_date(chars: &[char], i: usize) -> Option<(Option<QuantityValue>, usize)> {
+    let (idx, name) = MONTHS
+        .iter()
+        .enumerate()
+        .find(|(_, name)| starts_with_at(chars, i, name))?;
+    let mut at = i + name.chars().count();
+    if chars.get(at).is_some_and(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    at = skip_spaces(chars, at);
+    let first = numeral(chars, at).filter(|n| !n.kanji_only)?;
+    let (day, year_start) = if first.value < 100.0 {
+        let mut next = first.end;
+        if chars.get(next) == Some(&',') {
+            next += 1;
+        }
+        (Some(first.value as u32), skip_spaces(chars, next))
+    } else {
+        (None, at)
+    };
+    let year = numeral(chars, year_start).filter(|n| !n.kanji_only && n.value >= 1000.0)?;
+    Some((
+        Some(QuantityValue::Date(PartialDate {
+            year: year.value as i32,
+            month: Some(idx as u32 + 1),
+            day,
+        })),
+        year.end,
+    ))
+}
+
+fn number_or_date(chars: &[char], i: usize) -> Option<(Option<QuantityValue>, usize)> {
+    let n = numeral(chars, i)?;
+    match chars.get(n.end) {
+        Some('年') if (1000.0..3000.0).contains(&n.value) && n.value.fract() == 0.0 => {
+            let (month, day, end) = month_day(chars, n.end + 1);
+            return Some((
+                Some(QuantityValue::Date(PartialDate {
+                    year: n.value as i32,
+                    month,
+                    day,
+                })),
+                end,
+            ));
+        }
+        Some('月') => {
+            // 年の無い月日は比較できないので読み飛ばす
+            let (_, _, end) = month_day(chars, i);
+            return Some((None, end.max(n.end + 1)));
+        }
+        _ => {}
+    }
+
+    let mut value = n.value;
+    let mut end = n.end;
+    let after = skip_spaces(chars, end);
+    if let Some((word, mult)) = MULTIPLIERS
+        .iter()
+        .find(|(word, _)| starts_with_at(chars, after, word))
+    {
+        value *= mult;
+        end = after + word.chars().count();
+    }
+    let after = skip_spaces(chars, end);
+    let unit = UNITS
+        .iter()
+        .find(|(word, _)| {
+            starts_with_at(chars, after, word)
+                && !(word.is_ascii()
+                    && chars
+                        .get(after + word.chars().count())
+                        .is_some_and(|c| c.is_ascii_alphabetic()))
+        })
+        .map(|(word, unit)| {
+            end = after + word.chars().count();
+            unit.to_string()
+        });
+    if n.kanji_only && unit.is_none() && !n.has_large_unit {
+        // 「統一」「一般」などの漢字は数値として扱わない
+        return None;
+    }
+    let before: String = chars[..i].iter().collect::<String>().to_lowercase();
+    let before = before.trim_end();
+    let approx = APPROX_MARKERS.iter().any(|m| before.ends_with(m));
+    Some((
+        Some(QuantityValue::Number {
+            value,
+            unit,
+            approx,
+        }),
+        end,
+    ))
+}
+
+/// Words around a quantity, up to the clause boundary on each side.
+struct QuantityContext {
+    before: HashSet<String>,
+    after: HashSet<String>,
+}
+
+impl QuantityContext {
+    fn of(text: &str, q: &Quantity) -> Self {
+        let chars: Vec<char> = text.chars().collect();
+        let before: String = chars[..q.char_start.min(chars.len())]
+            .iter()
+            .rev()
+            .take(CONTEXT_CHARS)
+            .take_while(|c| !CLAUSE_DELIMITERS.contains(c))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        let after: String = chars[q.char_end.min(chars.len())..]
+            .iter()
+            .take(CONTEXT_CHARS)
+            .take_while(|c| !CLAUSE_DELIMITERS.contains(c))
+            .collect();
+        Self {
+            before: context_terms(&before),
+            after: context_terms(&after),
+        }
+    }
+
+    /// Context spanning the whole claim, for judging support.
+    fn whole(text: &str) -> Self {
+        Self {
+            before: context_terms(text),
+            after: HashSet::new(),
+        }
+    }
+
+    fn terms(&self) -> impl Iterator<Item = &String> {
+        self.before.iter().chain(self.after.iter())
+    }
+
+    /// Whether an evidence quantity is about the same subject/predicate as the
+    /// claim's: the two share a word, and every word next to the evidence
+    /// number relates to the claim (「10月4日の総裁選で選出」 is not about
+    /// 「10月21日に首相に就任」).
+    fn same_topic(&self, evidence: &QuantityContext) -> bool {
+        let claim: HashSet<&String> = self.terms().collect();
+        let shares = |side: &HashSet<String>| side.iter().any(|t| claim.contains(t));
+        (shares(&evidence.before) || shares(&evidence.after))
+            && (evidence.before.is_empty() || shares(&evidence.before))
+            && (evidence.after.is_empty() || shares(&evidence.after))
+    }
+
+    /// Whether an evidence quantity equal to the claim's can count as
+    /// support: each side of it shares a word with the claim. This is
+    /// looser than `same_topic`, since a table row puts the subject far from
+    /// the number; words in a script the claim does not use (a Japanese page
+    /// for an English claim) cannot be judged and are skipped.
+    fn relates_to(&self, evidence: &QuantityContext) -> bool {
+        let claim: HashSet<&String> = self.terms().collect();
+        let ascii = claim.iter().any(|t| t.is_ascii());
+        let non_ascii = claim.iter().any(|t| !t.is_ascii());
+        let related = |side: &HashSet<String>| {
+            let judged: Vec<&String> = side
+                .iter()
+                .filter(|t| if t.is_ascii() { ascii } else { non_ascii })
+                .collect();
+            judged.is_empty() || judged.iter().any(|t| claim.contains(t))
+        };
+        related(&evidence.before) && related(&evidence.after)
+    }
+}
+
+// 数字・1 文字の語・ひらがなを含む bigram (「は第」「に就」など助詞をまたぐもの) は手掛かりにしない
+fn context_terms(text: &str) -> HashSet<String> {
+    tokenize(text)
+        .into_iter()
+        .filter(|t| {
+            t.chars().count() >= 2
+                && !t
+                    .chars()
+                    .any(|c| c.is_ascii_digit() || ('\u{3041}'..='\u{309f}').contains(&c))
+        })
+        .collect()
+}
+
+/// Deterministic verifier for dates and numbers.
+///
+/// Every date/number in the claim is looked up among the evidence quantities
+/// of the same kind (dates at the claim's precision, numbers with the same
+/// unit). All found and equal within tolerance gives WHITE. Evidence that
+/// disagrees gives SMOKE only when its number sits next to the claim's
+/// subject or predicate; a different date or count elsewhere in the snippet
+/// is ignored. Claims without dates or numbers, or with nothing comparable in
+/// the evidence, abstain (GRAY_MID, confidence 0).
+#[derive(Clone, Debug)]
+pub struct RuleVerifier {
+    tolerance: f64,
+    approx_tolerance: f64,
+    date_tolerance_days: i64,
+}
+
+impl Default for RuleVerifier {
+    fn default() -> Self {
+        Self {
+            tolerance: 0.01,
+            approx_tolerance: 0.05,
+            date_tolerance_days: 0,
+        }
+    }
+}
+
+enum Finding<'a> {
+    Match(&'a Evidence, Quantity),
+    Conflict(&'a Evidence, Quantity, String),
+    Unknown,
+}
+
+impl RuleVerifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Relative tolerance for numbers (default 1%). Ordinals and counts such
+    /// as 代, 位, 票 must match exactly unless the claim says 約 / about.
+    pub fn with_tolerance(mut self, relative: f64) -> Self {
+        self.tolerance = relative;
+        self
+    }
+
+    /// Relative tolerance for numbers marked 約 / about (default 5%).
+    pub fn with_approx_tolerance(mut self, relative: f64) -> Self {
+        self.approx_tolerance = relative;
+        self
+    }
+
+    pub fn with_date_tolerance_days(mut self, days: i64) -> Self {
+        self.date_tolerance_days = days;
+        self
+    }
+
+    /// None when the two quantities are not comparable; otherwise whether
+    /// they agree, with the size of the difference.
+    fn compare(&self, claim: &QuantityValue, evidence: &QuantityValue) -> Option<(bool, String)> {
+        match (claim, evidence) {
+            (QuantityValue::Date(c), QuantityValue::Date(e)) => {
+                if e.precision() < c.precision() {
+                    return None;
+                }
+                if let (Some(cd), Some(ed)) = (c.to_naive(), e.to_naive()) {
+                    let days = (ed - cd).num_days().abs();
+                    return Some((
+                        days <= self.date_tolerance_days,
+                        format!("{} days apart", days),
+                    ));
+                }
+                let same = c.year == e.year && (c.month.is_none() || c.month == e.month);
+                Some((same, "different date".to_string()))
+            }
+            (
+                QuantityValue::Number {
+                    value: cv,
+                    unit: cu,
+                    approx,
+                },
+                QuantityValue::Number {
+                    value: ev,
+                    unit: eu,
+                    ..
+                },
+            ) => {
+                // 単位の無い主張 ("125 million") は単位付きの一致だけを採る
+                let cross_unit = cu.is_none() && eu.is_some();
+                if cu != eu && !cross_unit {
+                    return None;
+                }
+                let exact = !approx && cu.as_deref().is_some_and(|u| EXACT_UNITS.contains(&u));
+                let tolerance = if *approx {
+                    self.approx_tolerance
+                } else if exact {
+                    0.0
+                } else {
+                    self.tolerance
+                };
+                let diff = (cv - ev).abs();
+                let relative = if *cv == 0.0 { diff } else { diff / cv.abs() };
+                if cross_unit && relative > tolerance + 1e-9 {
+                    return None;
+                }
+                Some((
+                    relative <= tolerance + 1e-9,
+                    format!(
+                        "off by {:.1}%, tolerance {:.1}%",
+                        relative * 100.0,
+                        tolerance * 100.0
+                    ),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn check<'a>(
+        &self,
+        fragment: &str,
+        claim: &Quantity,
+        evidence: &[(&'a Evidence, Vec<Quantity>)],
+    ) -> Finding<'a> {
+        let claim_context = QuantityContext::of(fragment, claim);
+        let claim_terms = QuantityContext::whole(fragment);
+        let mut conflict: Option<(&'a Evidence, Quantity, String)> = None;
+        for (ev, quantities) in evidence {
+            for q in quantities {
+                let ev_context = QuantityContext::of(&ev.snippet, q);
+                // 別の話題の値は一致・矛盾どちらにも数えず、後続の値を見続ける
+                match self.compare(&claim.value, &q.value) {
+                    Some((true, _)) if claim_terms.relates_to(&ev_context) => {
+                        return Finding::Match(ev, q.clone())
+                    }
+                    Some((false, detail))
+                        if conflict.is_none() && claim_context.same_topic(&ev_context) =>
+                    {
+                        conflict = Some((ev, q.clone(), detail));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        match conflict {
+            Some((ev, q, detail)) => Finding::Conflict(ev, q, detail),
+            None => Finding::Unknown,
+        }
+    }
+}
+
+fn source_label(evidence: &Evidence) -> String {
+    evidence
+        .source
+        .url
+        .host_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| evidence.source.url.to_string())
+}
+
+fn abstain(reason: &str) -> VerificationResult {
+    VerificationResult {
+        status: VerificationStatus::GrayMid,
+        confidence: 0.0,
+        reason: reason.to_string(),
+        stances: Vec::new(),
+        votes: Vec::new(),
+    }
+}
+
+#[async_trait]
+impl ClaimVerifier for RuleVerifier {
+    async fn verify(
+        &self,
+        fragment: &str,
+        facts: &[RequiredFact],
+        _ctx: &IngestContext,
+    ) -> anyhow::Result<VerificationResult> {
+        let claimed = extract_quantities(fragment);
+        if claimed.is_empty() {
+            return Ok(abstain("rule: no date or number in claim"));
+        }
+        let evidence: Vec<(&Evidence, Vec<Quantity>)> = facts
+            .iter()
+            .flat_map(|f| f.evidence.iter())
+            .map(|e| (e, extract_quantities(&e.snippet)))
+            .filter(|(_, q)| !q.is_empty())
+            .collect();
+
+        let mut matched = Vec::new();
+        let mut conflicts = Vec::new();
+        let mut stances: Vec<EvidenceStance> = Vec::new();
+        let mut add_stance = |ev: &Evidence, stance: Stance, quote: &str| {
+            if !stances.iter().any(|s| s.evidence_id == ev.evidence_id) {
+                stances.push(EvidenceStance {
+                    evidence_id: ev.evidence_id.clone(),
+                    stance,
+                    quote: ev.snippet.contains(quote).then(|| quote.to_string()),
+                });
+            }
+        };
+        for claim in &claimed {
+            match self.check(fragment, claim, &evidence) {
+                Finding::Match(ev, q) => {
+                    matched.push(format!(
+                        "{} matches {} ({})",
+                        claim.value,
+                        q.text,
+                        source_label(ev)
+                    ));
+                    add_stance(ev, Stance::Supports, &q.text);
+                }
+                Finding::Conflict(ev, q, detail) => {
+                    conflicts.push(format!(
+                        "{} differs from {} ({}): {}",
+                        claim.value,
+                        q.text,
+                        source_label(ev),
+                        detail
+                    ));
+                    add_stance(ev, Stance::Refutes, &q.text);
+                }
+                Finding::Unknown => {}
+            }
+        }
+
+        let (status, confidence, reason) = if !conflicts.is_empty() {
+            (VerificationStatus::Smoke, 0.9, conflicts.join("; "))
+        } else if matched.is_empty() {
+            return Ok(abstain("rule: nothing comparable in evidence"));
+        } else if matched.len() == claimed.len() {
+            (VerificationStatus::White, 0.95, matched.join("; "))
+        } else {
+            (VerificationStatus::GrayWhite, 0.6, matched.join("; "))
+        };
+        Ok(VerificationResult {
+            status,
+            confidence,
+            reason: format!("rule: {}", reason),
+            stances,
+            votes: Vec::new(),
+        })
+    }
+}
+
+/// Runs the rule verifier before another verifier. A deterministic SMOKE
+/// (a differing number next to the claim's subject or predicate) is
+/// returned as is, without calling the inner verifier; otherwise the inner
+/// result is returned with both verdicts recorded as votes.
+pub struct PrecheckVerifier {
+    rule: RuleVerifier,
+    inner: Box<dyn ClaimVerifier>,
+}
+
+impl PrecheckVerifier {
+    pub fn new(rule: RuleVerifier, inner: Box<dyn ClaimVerifier>) -> Self {
+        Self { rule, inner }
+    }
+}
+
+fn vote(name: &str, result: &VerificationResult) -> VerifierVote {
+    VerifierVote {
+        verifier: name.to_string(),
+        status: result.status,
+        confidence: result.confidence,
+        weight: 1.0,
+        reason: result.reason.clone(),
+        abstained: result.confidence <= 0.0,
+    }
+}
+
+#[async_trait]
+impl ClaimVerifier for PrecheckVerifier {
+    async fn verify(
+        &self,
+        fragment: &str,
+        facts: &[RequiredFact],
+        ctx: &IngestContext,
+    ) -> anyhow::Result<VerificationResult> {
+        let rule = self.rule.verify(fragment, facts, ctx).await?;
+        if rule.status == VerificationStatus::Smoke {
+            let mut result = rule;
+            result.votes = vec![vote("rule", &result)];
+            return Ok(result);
+        }
+        let mut result = self.inner.verify(fragment, facts, ctx).await?;
+        if result.votes.is_empty() {
+            result.votes.push(vote("inner", &result));
+        }
+        result.votes.insert(0, vote("rule", &rule));
+        Ok(result)
+    }
+}
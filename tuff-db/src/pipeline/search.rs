@@ -0,0 +1,180 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use url::Url;
+
+#[derive(Clone, Debug)]
+pub struct SearchHit {
+    pub url: Url,
+    pub score: f32,
+}
+
+/// Returns candidate source URLs for a query derived from a fragment.
+#[async_trait]
+pub trait SearchProvider: Send + Sync {
+    async fn search(&self, query: &str, limit: usize) -> anyhow::Result<Vec<SearchHit>>;
+}
+
+/// Always returns the same URL (the historical `TARGET_URL` behaviour).
+pub struct StaticSearchProvider {
+    url: Url,
+}
+
+impl StaticSearchProvider {
+    pub fn new(url: Url) -> Self {
+        Self { url }
+    }
+}
+
+#[async_trait]
+impl SearchProvider for StaticSearchProvider {
+    async fn search(&self, _query: &str, limit: usize) -> anyhow::Result<Vec<SearchHit>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        Ok(vec![SearchHit {
+            url: self.url.clone(),
+            score: 1.0,
+        }])
+    }
+}
+
+#[derive(Clone, Debug)]
+struct IndexEntry {
+    url: Url,
+    keywords: Vec<String>,
+}
+
+/// Local URL/keyword index, one entry per line:
+/// `https://example.com/page<TAB>keyword1 keyword2,keyword3`
+/// Blank lines and lines starting with `#` are ignored.
+#[derive(Clone, Debug)]
+pub struct FileSearchProvider {
+    entries: Vec<IndexEntry>,
+}
+
+impl FileSearchProvider {
+    pub fn from_path(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Ok(Self::from_str_index(&content))
+    }
+
+    pub fn from_str_index(content: &str) -> Self {
+        let mut entries = Vec::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (raw_url, raw_keywords) = match line.split_once('\t') {
+                Some((u, k)) => (u.trim(), k),
+                None => (line, ""),
+            };
+            let Ok(url) = Url::parse(raw_url) else {
+                continue;
+            };
+            let keywords = raw_keywords
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .map(|k| k.trim().to_lowercase())
+                .filter(|k| !k.is_empty())
+                .collect();
+            entries.push(IndexEntry { url, keywords });
+        }
+        Self { entries }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[async_trait]
+impl SearchProvider for FileSearchProvider {
+    async fn search(&self, query: &str, limit: usize) -> anyhow::Result<Vec<SearchHit>> {
+        let query = query.to_lowercase();
+        let mut hits: Vec<SearchHit> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                if entry.keywords.is_empty() {
+                    return None;
+                }
+                // substring match so that unsegmented Japanese text still hits
+                let matched = entry
+                    .keywords
+                    .iter()
+                    .filter(|k| query.contains(k.as_str()))
+                    .count();
+                if matched == 0 {
+                    return None;
+                }
+                Some(SearchHit {
+                    url: entry.url.clone(),
+                    score: matched as f32 / entry.keywords.len() as f32,
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(limit);
+        Ok(hits)
+    }
+}
+
+/// Derive search queries from a fragment: the whole fragment first,
+/// then each clause when the fragment contains several.
+pub fn queries_from_fragment(fragment: &str) -> Vec<String> {
+    let trimmed = fragment
+        .trim()
+        .trim_end_matches(['。', '.', '！', '!', '？', '?'])
+        .trim();
+    if trimmed.is_empty() {
+        return Vec::new();
+    }
+    let mut queries = vec![trimmed.to_string()];
+    let clauses: Vec<&str> = trimmed
+        .split(['。', '、', ',', ';', '；'])
+        .map(|c| c.trim())
+        .filter(|c| !c.is_empty())
+        .collect();
+    if clauses.len() > 1 {
+        for clause in clauses {
+            if !queries.iter().any(|q| q == clause) {
+                queries.push(clause.to_string());
+            }
+        }
+    }
+    queries
+}
+
+/// Run every query against the provider and keep the best score per URL.
+pub async fn search_fragment(
+    provider: &dyn SearchProvider,
+    fragment: &str,
+    limit: usize,
+) -> anyhow::Result<Vec<SearchHit>> {
+    let mut best: HashMap<Url, f32> = HashMap::new();
+    for query in queries_from_fragment(fragment) {
+        for hit in provider.search(&query, limit).await? {
+            let score = best.entry(hit.url).or_insert(0.0);
+            if hit.score > *score {
+                *score = hit.score;
+            }
+        }
+    }
+    let mut hits: Vec<SearchHit> = best
+        .into_iter()
+        .map(|(url, score)| SearchHit { url, score })
+        .collect();
+    hits.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.url.as_str().cmp(b.url.as_str()))
+    });
+    hits.truncate(limit);
+    Ok(hits)
+}
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use crate::models::{Evidence, EvidenceStance, Id, Stance, VerificationStatus};
+use crate::pipeline::credibility::evidence_trust;
+
+/// Derives the overall status from per-evidence stances, weighting each side
+/// by source credibility (max trust per domain, summed across domains, so ten
+/// pages from one blog count once).
+#[derive(Clone, Debug)]
+pub struct StanceAggregation {
+    /// Trust mass the winning side needs for WHITE / SMOKE.
+    pub decisive_weight: f32,
+    /// `(supports - refutes) / (supports + refutes)` needed for WHITE / SMOKE.
+    pub decisive_margin: f32,
+}
+
+impl Default for StanceAggregation {
+    fn default() -> Self {
+        Self {
+            decisive_weight: 0.8,
+            decisive_margin: 0.5,
+        }
+    }
+}
+
+impl StanceAggregation {
+    pub fn aggregate(
+        &self,
+        stances: &[EvidenceStance],
+        evidence: &[&Evidence],
+    ) -> VerificationStatus {
+        let by_id: HashMap<&Id, &Evidence> =
+            evidence.iter().map(|e| (&e.evidence_id, *e)).collect();
+        let mut supports: HashMap<String, f32> = HashMap::new();
+        let mut refutes: HashMap<String, f32> = HashMap::new();
+        for stance in stances {
+            let Some(evidence) = by_id.get(&stance.evidence_id) else {
+                continue;
+            };
+            let side = match stance.stance {
+                Stance::Supports => &mut supports,
+                Stance::Refutes => &mut refutes,
+                Stance::Irrelevant => continue,
+            };
+            let domain = evidence
+                .credibility
+                .as_ref()
+                .map(|c| c.domain.clone())
+                .or_else(|| evidence.source.url.host_str().map(str::to_string))
+                .unwrap_or_default();
+            let trust = evidence_trust(evidence);
+            let entry = side.entry(domain).or_insert(0.0);
+            *entry = entry.max(trust);
+        }
+
+        let support: f32 = supports.values().sum();
+        let refute: f32 = refutes.values().sum();
+        if support + refute <= f32::EPSILON {
+            return VerificationStatus::GrayMid;
+        }
+        let margin = (support - refute) / (support + refute);
+        if margin >= self.decisive_margin && support >= self.decisive_weight {
+            VerificationStatus::White
+        } else if margin <= -self.decisive_margin && refute >= self.decisive_weight {
+            VerificationStatus::Smoke
+        } else if margin > 0.0 {
+            VerificationStatus::GrayWhite
+        } else if margin < 0.0 {
+            VerificationStatus::GrayBlack
+        } else {
+            VerificationStatus::GrayMid
+        }
+    }
+}
+
+pub(crate) fn parse_stance(raw: &str) -> Stance {
+    match raw.trim().to_lowercase().as_str() {
+        "supports" | "support" | "supported" => Stance::Supports,
+        "refutes" | "refute" | "refuted" | "contradicts" => Stance::Refutes,
+        _ => Stance::Irrelevant,
+    }
+}
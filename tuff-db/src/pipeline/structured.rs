@@ -0,0 +1,120 @@
+use crate::pipeline::llm_client::{LlmClient, LlmMessage, LlmRequest, LlmRole};
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+
+/// The model's last reply when it could not be read as the expected type.
+#[derive(Clone, Debug)]
+pub struct ParseFailure {
+    pub raw: String,
+    pub error: String,
+}
+
+/// Best-effort JSON slice of an LLM reply: the whole reply, a fenced
+/// ```` ```json ```` block, or the first balanced object/array in the text.
+pub fn extract_json(raw: &str) -> Option<&str> {
+    let trimmed = raw.trim();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        if let Some(end) = balanced_end(trimmed) {
+            return Some(&trimmed[..end]);
+        }
+    }
+    if let Some(block) = fenced_block(trimmed) {
+        if let Some(start) = block.find(['{', '[']) {
+            if let Some(end) = balanced_end(&block[start..]) {
+                return Some(&block[start..start + end]);
+            }
+        }
+    }
+    let mut offset = 0;
+    while let Some(pos) = trimmed[offset..].find(['{', '[']) {
+        let start = offset + pos;
+        if let Some(end) = balanced_end(&trimmed[start..]) {
+            let candidate = &trimmed[start..start + end];
+            if serde_json::from_str::<serde_json::Value>(candidate).is_ok() {
+                return Some(candidate);
+            }
+        }
+        offset = start + 1;
+    }
+    None
+}
+
+fn fenced_block(text: &str) -> Option<&str> {
+    let open = text.find("```")?;
+    let rest = &text[open + 3..];
+    // skip the info string (```json)
+    let body_start = rest.find('\n').map(|i| i + 1).unwrap_or(0);
+    let body = &rest[body_start..];
+    let close = body.find("```")?;
+    Some(&body[..close])
+}
+
+/// Byte length of the JSON value starting at `text[0]` (`{` or `[`).
+fn balanced_end(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, ch) in text.char_indices() {
+        if in_string {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(idx + ch.len_utf8());
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+pub fn parse_json<T: DeserializeOwned>(raw: &str) -> std::result::Result<T, String> {
+    let candidate = extract_json(raw).ok_or_else(|| "no JSON object found".to_string())?;
+    serde_json::from_str(candidate).map_err(|err| err.to_string())
+}
+
+/// Send `request` with the JSON schema of `T` attached and parse the reply.
+/// An unreadable reply gets one corrective re-prompt quoting the parse error;
+/// if that fails too the last reply is returned as a `ParseFailure` so callers
+/// keep their own fallback. Transport errors are returned as `Err`.
+pub async fn complete_json<T>(
+    client: &dyn LlmClient,
+    request: LlmRequest,
+    schema_name: &str,
+) -> Result<std::result::Result<T, ParseFailure>>
+where
+    T: DeserializeOwned + JsonSchema,
+{
+    let mut request = request.with_schema::<T>(schema_name);
+    let content = client.complete(&request).await?;
+    let error = match parse_json::<T>(&content) {
+        Ok(value) => return Ok(Ok(value)),
+        Err(err) => err,
+    };
+
+    request.messages.push(LlmMessage {
+        role: LlmRole::Assistant,
+        content,
+    });
+    request.messages.push(LlmMessage {
+        role: LlmRole::User,
+        content: format!(
+            "Your previous reply could not be parsed ({}). Reply again with only the JSON object matching the schema, without markdown or commentary.",
+            error
+        ),
+    });
+    let retry = client.complete(&request).await?;
+    Ok(parse_json::<T>(&retry).map_err(|error| ParseFailure { raw: retry, error }))
+}
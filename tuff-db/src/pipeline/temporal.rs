@@ -0,0 +1,262 @@
+use crate::models::{
+    Evidence, RequiredFact, Stance, TemporalQualifier, TemporalScope, VerificationStatus,
+};
+use crate::pipeline::rule_verifier::{extract_quantities, QuantityValue};
+use crate::pipeline::traits::VerificationResult;
+use chrono::{NaiveDate, Utc};
+
+// 過去の状態を述べる表現
+const PAST_MARKERS: &[&str] = &["だった", "であった", "でした", "かつて", "当時", "前任"];
+const PAST_WORDS: &[&str] = &["was", "were", "former", "formerly", "previously"];
+// 現在の状態を述べる表現
+const CURRENT_MARKERS: &[&str] = &["現在", "現職", "現任", "今の", "いまの", "目下"];
+const CURRENT_WORDS: &[&str] = &["currently", "current", "now", "incumbent", "today"];
+
+// 期間の区切り (「A〜B」「AからB」)
+const RANGE_SEPARATORS: &[&str] = &["〜", "～", "~", "–", "—", " - ", "から", " to ", " until "];
+// 区切りの後に続くと終端なし (現職) を表す語
+const OPEN_END_MARKERS: &[&str] = &["現在", "現職", "在任中", "present", "now", "current"];
+// 日付を探す範囲 (区切りの前後の文字数)
+const RANGE_WINDOW: usize = 24;
+// 本文中の範囲を有効期間とみなすのは任期を述べている場合だけ (統計の期間などを除く)
+const TERM_MARKERS: &[&str] = &["在任", "任期", "在職", "in office", "term of office"];
+
+/// Reads the time reference of a claim: past/current markers ("だった",
+/// "現在", "was", "currently") and the first date mentioned, widened to the
+/// period it names (`2024年` -> 2024-01-01..2024-12-31).
+pub fn extract_temporal(text: &str) -> TemporalQualifier {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let has = |markers: &[&str], english: &[&str]| {
+        markers.iter().any(|m| lower.contains(m)) || words.iter().any(|w| english.contains(w))
+    };
+    let scope = if has(PAST_MARKERS, PAST_WORDS) {
+        TemporalScope::Past
+    } else if has(CURRENT_MARKERS, CURRENT_WORDS) {
+        TemporalScope::Current
+    } else {
+        TemporalScope::Unspecified
+    };
+    let period = extract_quantities(text)
+        .into_iter()
+        .find_map(|q| match q.value {
+            QuantityValue::Date(date) => Some((date.first_day(), date.last_day())),
+            QuantityValue::Number { .. } => None,
+        });
+    let (from, to) = period.unwrap_or((None, None));
+    TemporalQualifier { scope, from, to }
+}
+
+/// Validity interval stated in running text, e.g. `在任期間は2024年10月から
+/// 2025年10月まで`. Ranges count only next to a term phrase (在任, 任期,
+/// "in office"); `2020年から2023年にかけて人口が減少` is a statistics period,
+/// not a validity interval.
+pub fn infer_validity(snippet: &str) -> Option<(Option<NaiveDate>, Option<NaiveDate>)> {
+    let lower = snippet.to_lowercase();
+    if !TERM_MARKERS.iter().any(|m| lower.contains(m)) {
+        return None;
+    }
+    first_range(snippet)
+}
+
+/// Validity interval of a table row, e.g. `令和6年10月1日〜令和7年10月21日` or
+/// `2025年10月21日〜現在`: rows of a list of office holders carry their term
+/// as a bare range. Only the first range is read, so a row that lists several
+/// terms yields the first one.
+pub fn table_row_validity(row: &str) -> Option<(Option<NaiveDate>, Option<NaiveDate>)> {
+    first_range(row)
+}
+
+fn first_range(snippet: &str) -> Option<(Option<NaiveDate>, Option<NaiveDate>)> {
+    for sep in RANGE_SEPARATORS {
+        for (pos, _) in snippet.match_indices(sep) {
+            let left: Vec<char> = snippet[..pos].chars().collect();
+            let left: String = left[left.len().saturating_sub(RANGE_WINDOW)..]
+                .iter()
+                .collect();
+            let Some(from) = last_date(&left) else {
+                continue;
+            };
+            let right: String = snippet[pos + sep.len()..]
+                .chars()
+                .take(RANGE_WINDOW)
+                .collect();
+            let right = right.trim_start();
+            let open = right.is_empty()
+                || OPEN_END_MARKERS
+                    .iter()
+                    .any(|m| right.to_lowercase().starts_with(m));
+            let to = if open {
+                None
+            } else {
+                first_date(right).and_then(|(_, last)| last)
+            };
+            return Some((from.0, to));
+        }
+    }
+    None
+}
+
+fn dates(text: &str) -> impl Iterator<Item = (Option<NaiveDate>, Option<NaiveDate>)> {
+    extract_quantities(text)
+        .into_iter()
+        .filter_map(|q| match q.value {
+            QuantityValue::Date(date) => Some((date.first_day(), date.last_day())),
+            QuantityValue::Number { .. } => None,
+        })
+}
+
+fn first_date(text: &str) -> Option<(Option<NaiveDate>, Option<NaiveDate>)> {
+    dates(text).next()
+}
+
+fn last_date(text: &str) -> Option<(Option<NaiveDate>, Option<NaiveDate>)> {
+    dates(text).last()
+}
+
+/// Validity of one evidence snippet: the fact's `valid_from` / `valid_to` when
+/// the fetcher set them (table rows), otherwise a term stated in the snippet.
+pub fn evidence_validity(
+    fact: &RequiredFact,
+    evidence: &Evidence,
+) -> Option<(Option<NaiveDate>, Option<NaiveDate>)> {
+    if fact.valid_from.is_some() || fact.valid_to.is_some() {
+        return Some((fact.valid_from, fact.valid_to));
+    }
+    infer_validity(&evidence.snippet)
+}
+
+pub(crate) fn format_validity(from: Option<NaiveDate>, to: Option<NaiveDate>) -> String {
+    let side = |d: Option<NaiveDate>| d.map(|d| d.to_string()).unwrap_or_default();
+    format!("{}..{}", side(from), side(to))
+}
+
+/// Outcome of the temporal check: the claim's qualifier and the validity of
+/// the evidence that supports it.
+#[derive(Clone, Debug, Default)]
+pub struct TemporalAssessment {
+    pub qualifier: TemporalQualifier,
+    pub valid_from: Option<NaiveDate>,
+    pub valid_to: Option<NaiveDate>,
+}
+
+/// Separates "was true" from "is true". A claim about the present whose
+/// supporting evidence stopped being valid before today becomes OUTDATED
+/// instead of WHITE; a claim about the past ("だった") backed by such
+/// evidence stays WHITE even if the verifier called it OUTDATED.
+#[derive(Clone, Debug)]
+pub struct TemporalCheck {
+    today: NaiveDate,
+}
+
+impl Default for TemporalCheck {
+    fn default() -> Self {
+        Self {
+            today: Utc::now().date_naive(),
+        }
+    }
+}
+
+impl TemporalCheck {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Judge against a fixed date instead of today.
+    pub fn at(today: NaiveDate) -> Self {
+        Self { today }
+    }
+
+    pub fn apply(
+        &self,
+        fragment: &str,
+        facts: &[RequiredFact],
+        result: &mut VerificationResult,
+    ) -> TemporalAssessment {
+        let qualifier = extract_temporal(fragment);
+        let positive = result.status >= VerificationStatus::GrayWhite
+            || result.status == VerificationStatus::Outdated;
+        let supporting: Vec<(Option<NaiveDate>, Option<NaiveDate>)> = facts
+            .iter()
+            .flat_map(|f| f.evidence.iter().map(move |e| (f, e)))
+            .filter(|(_, e)| {
+                if result.stances.is_empty() {
+                    positive
+                } else {
+                    result
+                        .stances
+                        .iter()
+                        .any(|s| s.evidence_id == e.evidence_id && s.stance == Stance::Supports)
+                }
+            })
+            .filter_map(|(f, e)| evidence_validity(f, e))
+            .collect();
+
+        let mut assessment = TemporalAssessment {
+            qualifier: qualifier.clone(),
+            valid_from: None,
+            valid_to: None,
+        };
+        if supporting.is_empty() {
+            if qualifier.scope == TemporalScope::Past
+                && result.status == VerificationStatus::Outdated
+            {
+                mark(result, VerificationStatus::White, "claim is about the past");
+            }
+            return assessment;
+        }
+        if supporting.iter().all(|(from, _)| from.is_some()) {
+            assessment.valid_from = supporting.iter().filter_map(|(from, _)| *from).min();
+        }
+        if supporting.iter().all(|(_, to)| to.is_some()) {
+            assessment.valid_to = supporting.iter().filter_map(|(_, to)| *to).max();
+        }
+        let validity = format_validity(assessment.valid_from, assessment.valid_to);
+
+        let reference = match (qualifier.from, qualifier.to) {
+            (Some(from), Some(to)) => Some((from, to)),
+            _ if qualifier.scope == TemporalScope::Past => None,
+            _ => Some((self.today, self.today)),
+        };
+        let Some((ref_from, ref_to)) = reference else {
+            if result.status == VerificationStatus::Outdated {
+                let note = format!("valid {}, claim is about the past", validity);
+                mark(result, VerificationStatus::White, &note);
+            }
+            return assessment;
+        };
+        let covered = supporting.iter().any(|(from, to)| {
+            from.is_none_or(|from| from <= ref_to) && to.is_none_or(|to| to >= ref_from)
+        });
+        if covered {
+            if result.status == VerificationStatus::Outdated {
+                let note = format!("valid {}, covers the claimed time", validity);
+                mark(result, VerificationStatus::White, &note);
+            }
+        } else if positive || result.status == VerificationStatus::Outdated {
+            let ended = supporting
+                .iter()
+                .all(|(_, to)| to.is_some_and(|to| to < ref_from));
+            if ended && qualifier.from.is_none() {
+                let note = format!("valid {}, outdated as of {}", validity, self.today);
+                mark(result, VerificationStatus::Outdated, &note);
+            } else {
+                let note = format!(
+                    "valid {}, not at {}",
+                    validity,
+                    format_validity(Some(ref_from), Some(ref_to))
+                );
+                mark(result, VerificationStatus::GrayBlack, &note);
+            }
+        }
+        assessment
+    }
+}
+
+fn mark(result: &mut VerificationResult, status: VerificationStatus, note: &str) {
+    result.status = status;
+    result.reason = format!("{}; temporal: {}", result.reason, note);
+}
@@ -1,4 +1,7 @@
-use crate::models::{Abstract, Claim, Evidence, RequiredFact, Transition, VerificationStatus};
+use crate::models::{
+    Abstract, Claim, Evidence, EvidenceStance, RequiredFact, Transition, VerificationStatus,
+    VerifierVote,
+};
 use async_trait::async_trait;
 
 #[derive(Clone, Debug)]
@@ -6,12 +9,51 @@ pub struct VerificationResult {
     pub status: VerificationStatus,
     pub confidence: f32,
     pub reason: String,
+    /// Per-evidence stances, when the verifier judges evidence individually.
+    pub stances: Vec<EvidenceStance>,
+    /// Member votes, when the result comes from an ensemble.
+    pub votes: Vec<VerifierVote>,
+}
+
+/// Per-request context carried through the pipeline.
+#[derive(Clone, Debug, Default)]
+pub struct IngestContext {
+    /// BCP 47 locale of the page the fragment came from (e.g. `ja-JP`).
+    pub locale: Option<String>,
+}
+
+impl IngestContext {
+    pub fn with_locale(locale: &str) -> Self {
+        let locale = locale.trim();
+        Self {
+            locale: (!locale.is_empty()).then(|| locale.to_string()),
+        }
+    }
+
+    /// Locale tag for prompts; `und` when unknown.
+    pub fn locale_tag(&self) -> &str {
+        self.locale.as_deref().unwrap_or("und")
+    }
 }
 
 pub trait InputSplitter: Send + Sync {
     fn split(&self, input: &str) -> Vec<String>;
 }
 
+/// Splitter that may need I/O (e.g. an LLM call) to break input into claims.
+/// Every sync `InputSplitter` is usable here as well, ignoring the context.
+#[async_trait]
+pub trait AsyncInputSplitter: Send + Sync {
+    async fn split(&self, input: &str, ctx: &IngestContext) -> anyhow::Result<Vec<String>>;
+}
+
+#[async_trait]
+impl<T: InputSplitter> AsyncInputSplitter for T {
+    async fn split(&self, input: &str, _ctx: &IngestContext) -> anyhow::Result<Vec<String>> {
+        Ok(InputSplitter::split(self, input))
+    }
+}
+
 #[async_trait]
 pub trait FactFetcher: Send + Sync {
     async fn fetch(&self, fragment: &str) -> anyhow::Result<Vec<RequiredFact>>;
@@ -23,6 +65,7 @@ pub trait ClaimVerifier: Send + Sync {
         &self,
         fragment: &str,
         facts: &[RequiredFact],
+        ctx: &IngestContext,
     ) -> anyhow::Result<VerificationResult>;
 }
 
@@ -33,17 +76,20 @@ pub trait AbstractGenerator: Send + Sync {
         fragment: &str,
         facts: &[RequiredFact],
         status: VerificationStatus,
+        ctx: &IngestContext,
     ) -> anyhow::Result<Abstract>;
 }
 
 #[async_trait]
 pub trait GapResolver: Send + Sync {
     /// Resolve the gap between internal knowledge and external evidence.
-    /// Returns a signed Transition if an explaining event is found.
+    /// Returns a Transition if an explaining event is found. It is signed
+    /// when appended to an engine configured with a signing key.
     async fn resolve(
         &self,
         claim: &Claim,
         internal_state: &str,
         external_evidence: &[Evidence],
+        ctx: &IngestContext,
     ) -> anyhow::Result<Option<Transition>>;
 }
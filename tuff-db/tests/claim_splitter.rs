@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use transformer_neo::db::{OpKind, TuffEngine};
+use transformer_neo::pipeline::{
+    AsyncInputSplitter, DummyAbstractGenerator, DummyFetcher, DummySplitter, DummyVerifier,
+    IngestContext, IngestPipeline, LlmClaimSplitter, ScriptedLlmClient,
+};
+
+#[tokio::test]
+async fn sync_splitters_are_async_splitters() {
+    let parts = AsyncInputSplitter::split(
+        &DummySplitter,
+        "  東京は首都 \n\n高市早苗は首相\n",
+        &IngestContext::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(parts, vec!["東京は首都", "高市早苗は首相"]);
+}
+
+#[tokio::test]
+async fn llm_splitter_trims_and_dedups_claims() {
+    let client = Arc::new(ScriptedLlmClient::new([
+        r#"{"claims": [" Sanae Takaichi is the prime minister of Japan ", "", "Sanae Takaichi is the prime minister of Japan", "Tokyo is the capital of Japan"]}"#,
+    ]));
+    let splitter = LlmClaimSplitter::new(client.clone());
+    let claims = splitter
+        .split(
+            "She is PM now. I think Tokyo, the capital, is lovely.",
+            &IngestContext::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        claims,
+        vec![
+            "Sanae Takaichi is the prime minister of Japan",
+            "Tokyo is the capital of Japan"
+        ]
+    );
+    let request = &client.requests()[0];
+    assert!(request.messages[1].content.contains("She is PM now."));
+
+    // blank input never reaches the model
+    assert!(splitter
+        .split("  \n", &IngestContext::default())
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(client.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn pipeline_verifies_each_extracted_claim() {
+    let client = Arc::new(ScriptedLlmClient::new([
+        r#"{"claims": ["Sanae Takaichi is the prime minister of Japan", "Tokyo is the capital of Japan"]}"#,
+    ]));
+    let dir = std::env::temp_dir().join(format!("tuff-splitter-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let pipeline = IngestPipeline {
+        splitter: LlmClaimSplitter::new(client),
+        fetcher: DummyFetcher,
+        verifier: DummyVerifier,
+        generator: DummyAbstractGenerator,
+        db: TuffEngine::new(dir.join("wal.log").to_str().unwrap())
+            .await
+            .unwrap(),
+        gap_resolver: None,
+        credibility: None,
+        entities: None,
+    };
+    let outcomes = pipeline
+        .ingest("She is PM now, and Tokyo is the capital.")
+        .await
+        .unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    let summaries: Vec<String> = outcomes
+        .iter()
+        .map(|o| match &o.op.kind {
+            OpKind::InsertAbstract { abstract_ } => abstract_.summary.clone(),
+            _ => panic!("expected InsertAbstract"),
+        })
+        .collect();
+    assert_eq!(
+        summaries,
+        vec![
+            "SMOKE: Sanae Takaichi is the prime minister of Japan",
+            "SMOKE: Tokyo is the capital of Japan"
+        ]
+    );
+}
@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use transformer_neo::models::{Evidence, RequiredFact, SourceMeta};
+use transformer_neo::pipeline::composite::source_timeout;
+use transformer_neo::pipeline::{CompositeFetcher, FactFetcher, FetchPolicy};
+use url::Url;
+
+enum Stub {
+    Facts(Vec<RequiredFact>),
+    Slow(Duration, Vec<RequiredFact>),
+    Fail,
+}
+
+#[async_trait]
+impl FactFetcher for Stub {
+    async fn fetch(&self, _fragment: &str) -> anyhow::Result<Vec<RequiredFact>> {
+        match self {
+            Stub::Facts(facts) => Ok(facts.clone()),
+            Stub::Slow(delay, facts) => {
+                tokio::time::sleep(*delay).await;
+                Ok(facts.clone())
+            }
+            Stub::Fail => anyhow::bail!("stub failure"),
+        }
+    }
+}
+
+// every snippet of one URL comes from the same page (same content hash)
+fn fact(url: &str, snippets: &[&str]) -> RequiredFact {
+    let source = SourceMeta::new(Url::parse(url).unwrap(), format!("sha-of-{}", url));
+    RequiredFact::new(
+        "source_url",
+        url,
+        snippets
+            .iter()
+            .map(|s| Evidence::new(source.clone(), *s))
+            .collect(),
+    )
+}
+
+const SECOND: Duration = Duration::from_secs(1);
+
+fn origins(facts: &[RequiredFact]) -> Vec<(&str, f32)> {
+    facts
+        .iter()
+        .map(|f| {
+            let origin = f.origin.as_ref().unwrap();
+            (origin.fetcher.as_str(), origin.weight)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn orders_by_weight_and_drops_duplicate_evidence() {
+    let kantei = "https://www.kantei.go.jp/";
+    let composite = CompositeFetcher::new()
+        .with_source(
+            "web",
+            Box::new(Stub::Facts(vec![fact(
+                kantei,
+                &["首相は高市早苗", "内閣の一覧"],
+            )])),
+            0.8,
+            SECOND,
+        )
+        .with_source(
+            "meaning_db",
+            Box::new(Stub::Facts(vec![
+                fact(kantei, &["首相は高市早苗"]),
+                RequiredFact::new("mock", "no evidence", Vec::new()),
+            ])),
+            1.0,
+            SECOND,
+        )
+        .with_source(
+            "local",
+            Box::new(Stub::Facts(vec![fact(kantei, &[" 内閣の一覧 "])])),
+            0.9,
+            SECOND,
+        );
+
+    let facts = composite.fetch("高市早苗は首相").await.unwrap();
+    // both web snippets were already seen on higher-weighted facts, so the web
+    // fact is dropped; whitespace around a snippet does not make it new
+    assert_eq!(
+        origins(&facts),
+        vec![("meaning_db", 1.0), ("meaning_db", 1.0), ("local", 0.9)]
+    );
+    assert_eq!(facts[0].evidence[0].snippet, "首相は高市早苗");
+    // facts that never had evidence are kept
+    assert!(facts[1].evidence.is_empty());
+    assert_eq!(facts[2].evidence[0].snippet, " 内閣の一覧 ");
+}
+
+#[tokio::test]
+async fn skips_slow_and_failing_members() {
+    let composite = CompositeFetcher::new()
+        .with_source(
+            "slow",
+            Box::new(Stub::Slow(
+                SECOND * 5,
+                vec![fact("https://slow.example/", &["遅い"])],
+            )),
+            1.0,
+            Duration::from_millis(50),
+        )
+        .with_source("broken", Box::new(Stub::Fail), 1.0, SECOND)
+        .with_source(
+            "fast",
+            Box::new(Stub::Slow(
+                Duration::from_millis(10),
+                vec![fact("https://fast.example/", &["速い"])],
+            )),
+            0.5,
+            SECOND,
+        );
+
+    let started = std::time::Instant::now();
+    let facts = composite.fetch("anything").await.unwrap();
+    assert!(started.elapsed() < SECOND);
+    assert_eq!(origins(&facts), vec![("fast", 0.5)]);
+}
+
+#[test]
+fn default_timeout_outlasts_the_fetch_policy() {
+    let policy = FetchPolicy::default();
+    assert!(source_timeout(&policy) > policy.timeout * 2);
+
+    let strict = FetchPolicy {
+        timeout: SECOND,
+        min_interval: Duration::ZERO,
+        ..FetchPolicy::default()
+    };
+    assert_eq!(source_timeout(&strict), SECOND * 2);
+}
+
+#[test]
+fn from_env_reads_per_source_timeouts() {
+    std::env::set_var("TUFF_FETCH_SOURCE_TIMEOUT_MS", "4000");
+    std::env::set_var("TUFF_FETCH_SOURCE_TIMEOUTS_MS", "meaning_db=250; web=x");
+    std::env::remove_var("TUFF_LOCAL_CORPUS");
+    std::env::remove_var("TUFF_WEB_FETCH");
+    let composite = CompositeFetcher::from_env(std::path::Path::new("missing.db")).unwrap();
+    std::env::remove_var("TUFF_FETCH_SOURCE_TIMEOUT_MS");
+    std::env::remove_var("TUFF_FETCH_SOURCE_TIMEOUTS_MS");
+
+    assert_eq!(
+        composite.timeout_of("meaning_db"),
+        Some(Duration::from_millis(250))
+    );
+    // an unparsable entry falls back to the shared timeout
+    assert_eq!(composite.timeout_of("web"), Some(SECOND * 4));
+    assert_eq!(composite.timeout_of("local"), None);
+    assert_eq!(composite.max_timeout(), SECOND * 4);
+}
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use transformer_neo::models::{RequiredFact, VerificationStatus};
+use transformer_neo::pipeline::{
+    ClaimVerifier, ConfidenceCalibration, IngestContext, LlmVerifier, ScriptedLlmClient,
+};
+
+fn local_fact() -> Vec<RequiredFact> {
+    // no evidence snippets: weight clamps to the 0.4 floor
+    vec![RequiredFact::new("k", "v", Vec::new())]
+}
+
+async fn verify_with(reply: &str, calibration: ConfidenceCalibration) -> (VerificationStatus, f32) {
+    let client = Arc::new(ScriptedLlmClient::new([reply]));
+    let verifier = LlmVerifier::new(client).with_calibration(calibration);
+    let result = verifier
+        .verify(
+            "東京は日本の首都である",
+            &local_fact(),
+            &IngestContext::default(),
+        )
+        .await
+        .unwrap();
+    (result.status, result.confidence)
+}
+
+#[test]
+fn table_interpolates_and_clamps_to_endpoints() {
+    let table = ConfidenceCalibration::load_str("# raw\tcalibrated\n0.5\t0.3\n0.9\t0.7\n").unwrap();
+    assert!((table.apply(0.7) - 0.5).abs() < 1e-6);
+    assert!((table.apply(0.1) - 0.3).abs() < 1e-6);
+    assert!((table.apply(1.0) - 0.7).abs() < 1e-6);
+    assert!((ConfidenceCalibration::identity().apply(0.42) - 0.42).abs() < 1e-6);
+}
+
+#[test]
+fn non_monotonic_or_out_of_range_tables_are_rejected() {
+    assert!(ConfidenceCalibration::from_points(vec![(0.2, 0.6), (0.8, 0.4)]).is_err());
+    assert!(ConfidenceCalibration::from_points(vec![(0.2, 1.4)]).is_err());
+    assert!(ConfidenceCalibration::load_str("0.5 0.3\n").is_err());
+}
+
+#[test]
+fn fitted_table_is_monotonic_and_round_trips() {
+    // overconfident at 0.9 (60% right), a noisy dip at 0.5
+    let mut samples = Vec::new();
+    for i in 0..10 {
+        samples.push((0.9, i < 6));
+        samples.push((0.5, i < 5));
+        samples.push((0.3, i < 6));
+    }
+    let table = ConfidenceCalibration::fit(&samples, 10);
+    let points = table.points();
+    assert!(points.windows(2).all(|w| w[0].1 <= w[1].1));
+    // 0.3 and 0.5 pooled into one block at 55%
+    assert!((table.apply(0.3) - 0.55).abs() < 1e-4);
+    assert!((table.apply(0.9) - 0.6).abs() < 1e-4);
+
+    let reloaded = ConfidenceCalibration::load_str(&table.to_tsv()).unwrap();
+    assert!((reloaded.apply(0.9) - 0.6).abs() < 1e-4);
+}
+
+#[tokio::test]
+async fn verifier_applies_calibration_before_evidence_weight() {
+    let table = ConfidenceCalibration::from_points(vec![(0.0, 0.0), (1.0, 0.5)]).unwrap();
+    let (status, confidence) = verify_with(
+        r#"{"status": "WHITE", "confidence": 0.8, "reasoning": "ok"}"#,
+        table,
+    )
+    .await;
+    assert_eq!(status, VerificationStatus::White);
+    // 0.8 -> 0.4 calibrated, * 0.4 evidence weight floor
+    assert!((confidence - 0.16).abs() < 1e-4);
+}
+
+#[tokio::test]
+async fn verifier_rejects_out_of_range_confidence() {
+    let identity = ConfidenceCalibration::identity;
+    // values above 1.0 are not read as percentages
+    for raw in ["75", "1.5", "100"] {
+        let (_, above) = verify_with(
+            &format!(
+                r#"{{"status": "WHITE", "confidence": {}, "reasoning": "ok"}}"#,
+                raw
+            ),
+            identity(),
+        )
+        .await;
+        assert!((above - 0.16).abs() < 1e-4, "confidence {}", raw);
+    }
+    let (_, one) = verify_with(
+        r#"{"status": "WHITE", "confidence": 1, "reasoning": "ok"}"#,
+        identity(),
+    )
+    .await;
+    assert!((one - 0.4).abs() < 1e-4);
+
+    let (status, missing) =
+        verify_with(r#"{"status": "WHITE", "reasoning": "ok"}"#, identity()).await;
+    assert_eq!(status, VerificationStatus::White);
+    assert!((missing - 0.16).abs() < 1e-4);
+
+    let (_, negative) = verify_with(
+        r#"{"status": "WHITE", "confidence": -3, "reasoning": "ok"}"#,
+        identity(),
+    )
+    .await;
+    assert!((negative - 0.16).abs() < 1e-4);
+}
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+use transformer_neo::lightweight::{LightweightVerifier, MeaningDb};
+use transformer_neo::models::{RequiredFact, VerificationStatus};
+use transformer_neo::pipeline::traits::VerificationResult;
+use transformer_neo::pipeline::{
+    verifier_from_env, ClaimVerifier, ConfiguredVerifier, EnsemblePolicy, EnsembleVerifier,
+    IngestContext, LightweightClaimVerifier, LlmClient, PromptSet, ScriptedLlmClient,
+};
+
+struct Fixed(VerificationStatus, f32);
+
+#[async_trait]
+impl ClaimVerifier for Fixed {
+    async fn verify(
+        &self,
+        _fragment: &str,
+        _facts: &[RequiredFact],
+        _ctx: &IngestContext,
+    ) -> anyhow::Result<VerificationResult> {
+        Ok(VerificationResult {
+            status: self.0,
+            confidence: self.1,
+            reason: format!("fixed {:?}", self.0),
+            stances: Vec::new(),
+            votes: Vec::new(),
+        })
+    }
+}
+
+struct Failing;
+
+#[async_trait]
+impl ClaimVerifier for Failing {
+    async fn verify(
+        &self,
+        _fragment: &str,
+        _facts: &[RequiredFact],
+        _ctx: &IngestContext,
+    ) -> anyhow::Result<VerificationResult> {
+        anyhow::bail!("upstream unavailable")
+    }
+}
+
+use VerificationStatus::*;
+
+fn ensemble(
+    policy: EnsemblePolicy,
+    members: &[(VerificationStatus, f32, f32)],
+) -> EnsembleVerifier {
+    members.iter().enumerate().fold(
+        EnsembleVerifier::new(policy),
+        |e, (i, (status, conf, weight))| {
+            e.with_member(&format!("m{}", i), Box::new(Fixed(*status, *conf)), *weight)
+        },
+    )
+}
+
+async fn run(verifier: &EnsembleVerifier) -> VerificationResult {
+    verifier
+        .verify("高市早苗は首相である", &[], &IngestContext::default())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn majority_picks_heaviest_status_and_records_votes() {
+    let verifier = ensemble(
+        EnsemblePolicy::Majority,
+        &[(White, 0.9, 1.0), (White, 0.7, 1.0), (GrayWhite, 0.6, 1.0)],
+    );
+    let result = run(&verifier).await;
+    assert_eq!(result.status, White);
+    assert!((result.confidence - 0.8).abs() < 1e-4);
+    assert_eq!(result.votes.len(), 3);
+    assert_eq!(result.votes[2].verifier, "m2");
+    assert_eq!(result.votes[2].status, GrayWhite);
+
+    let tie = ensemble(
+        EnsemblePolicy::Majority,
+        &[(White, 0.9, 1.0), (GrayWhite, 0.6, 1.0)],
+    );
+    assert_eq!(run(&tie).await.status, GrayMid);
+}
+
+#[tokio::test]
+async fn most_pessimistic_takes_lowest_status() {
+    let verifier = ensemble(
+        EnsemblePolicy::MostPessimistic,
+        &[
+            (White, 0.9, 1.0),
+            (GrayMid, 0.5, 1.0),
+            (GrayWhite, 0.6, 1.0),
+        ],
+    );
+    let result = run(&verifier).await;
+    assert_eq!(result.status, GrayMid);
+    assert!((result.confidence - 0.5).abs() < 1e-4);
+}
+
+#[tokio::test]
+async fn weighted_confidence_averages_on_status_scale() {
+    // White 1.0 x (0.9 x 3) + GrayMid 0.0 x (0.6 x 1) -> 2.7 / 3.3 = 0.82
+    let verifier = ensemble(
+        EnsemblePolicy::WeightedConfidence,
+        &[(White, 0.9, 3.0), (GrayMid, 0.6, 1.0)],
+    );
+    let result = run(&verifier).await;
+    assert_eq!(result.status, White);
+    assert!((result.confidence - 0.825).abs() < 1e-4);
+
+    // a confident GRAY_MID outweighs a weak GRAY_WHITE
+    let verifier = ensemble(
+        EnsemblePolicy::WeightedConfidence,
+        &[(GrayWhite, 0.3, 1.0), (GrayMid, 0.9, 1.0)],
+    );
+    assert_eq!(run(&verifier).await.status, GrayMid);
+}
+
+#[tokio::test]
+async fn disagreement_forces_gray_mid_unless_disabled() {
+    let members = [(White, 0.9, 1.0), (White, 0.8, 1.0), (Smoke, 0.7, 1.0)];
+    let result = run(&ensemble(EnsemblePolicy::Majority, &members)).await;
+    assert_eq!(result.status, GrayMid);
+    assert!(result.reason.starts_with("ensemble disagreement"));
+    assert!((result.confidence - 0.7).abs() < 1e-4);
+
+    let lenient = ensemble(EnsemblePolicy::Majority, &members).with_gray_on_disagreement(false);
+    assert_eq!(run(&lenient).await.status, White);
+}
+
+#[tokio::test]
+async fn failing_and_unknown_members_abstain() {
+    let db = MeaningDb::new(HashMap::from([(
+        "首相".to_string(),
+        "高市早苗".to_string(),
+    )]));
+    let lightweight = Arc::new(RwLock::new(LightweightVerifier::new(db)));
+    let verifier = EnsembleVerifier::new(EnsemblePolicy::MostPessimistic)
+        .with_member("llm", Box::new(Fixed(GrayWhite, 0.6)), 1.0)
+        .with_member("broken", Box::new(Failing), 1.0)
+        .with_member(
+            "lightweight",
+            Box::new(LightweightClaimVerifier::new(lightweight)),
+            1.0,
+        );
+    let result = verifier
+        .verify("東京は日本の首都である", &[], &IngestContext::default())
+        .await
+        .unwrap();
+    assert_eq!(result.status, GrayWhite);
+    assert_eq!(result.votes.len(), 3);
+    assert!(result.votes[1].abstained);
+    assert!(result.votes[1].reason.contains("upstream unavailable"));
+    assert!(result.votes[2].abstained);
+
+    let empty = EnsembleVerifier::new(EnsemblePolicy::Majority);
+    let result = run(&empty).await;
+    assert_eq!(result.status, GrayMid);
+    assert!(result.votes.is_empty());
+}
+
+#[test]
+fn verifier_from_env_picks_the_configured_verifier() {
+    let llm: Arc<dyn LlmClient> = Arc::new(ScriptedLlmClient::new(Vec::<String>::new()));
+    let prompts = Arc::new(PromptSet::builtin());
+    let lightweight = Arc::new(RwLock::new(LightweightVerifier::new(MeaningDb::new(
+        HashMap::new(),
+    ))));
+    let build = |llm: Option<&Arc<dyn LlmClient>>| {
+        verifier_from_env(llm, prompts.clone(), Some(lightweight.clone())).unwrap()
+    };
+
+    std::env::remove_var("TUFF_ENSEMBLE_POLICY");
+    std::env::remove_var("TUFF_RULE_PRECHECK");
+    assert!(matches!(build(None), ConfiguredVerifier::Dummy(_)));
+    assert!(matches!(build(Some(&llm)), ConfiguredVerifier::Llm(_)));
+
+    std::env::set_var("TUFF_RULE_PRECHECK", "1");
+    assert!(matches!(build(Some(&llm)), ConfiguredVerifier::Precheck(_)));
+
+    std::env::set_var("TUFF_ENSEMBLE_POLICY", "pessimistic");
+    match build(Some(&llm)) {
+        ConfiguredVerifier::Ensemble(e) => assert_eq!(e.len(), 3),
+        _ => panic!("expected an ensemble"),
+    }
+    assert!(build(None).is_ensemble());
+    std::env::remove_var("TUFF_ENSEMBLE_POLICY");
+    std::env::remove_var("TUFF_RULE_PRECHECK");
+}
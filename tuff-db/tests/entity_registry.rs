@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use transformer_neo::lightweight::{LightweightCheckStatus, LightweightVerifier, MeaningDb};
+use transformer_neo::models::{
+    Entity, EntityKind, EntityRegistry, Evidence, Script, SharedEntityRegistry, StateValue,
+    VerificationStatus,
+};
+use transformer_neo::pipeline::{
+    AbstractGenerator, GapResolver, IngestContext, LlmAbstractor, LlmGapResolver, ScriptedLlmClient,
+};
+use url::Url;
+
+fn shared(registry: EntityRegistry) -> SharedEntityRegistry {
+    Arc::new(RwLock::new(registry))
+}
+
+#[test]
+fn resolves_aliases_readings_and_scripts() {
+    let registry = EntityRegistry::builtin();
+    for name in [
+        "高市早苗",
+        "Sanae Takaichi",
+        "Takaichi Sanae",
+        "高市 早苗",
+        "高市氏",
+        "たかいちさなえ",
+        "ＴＡＫＡＩＣＨＩ",
+    ] {
+        assert_eq!(
+            registry.resolve(name).map(|e| e.id.as_str()),
+            Some("person:takaichi-sanae"),
+            "{}",
+            name
+        );
+    }
+    assert_eq!(registry.canonical_name("Prime Minister"), "内閣総理大臣");
+    assert_eq!(registry.canonical_name("  unknown  "), "unknown");
+
+    let takaichi = registry.get("person:takaichi-sanae").unwrap();
+    assert_eq!(takaichi.kind, EntityKind::Person);
+    assert_eq!(takaichi.name_in(Script::Latin), Some("Sanae Takaichi"));
+    assert_eq!(
+        takaichi.scripts(),
+        vec![Script::Han, Script::Latin, Script::Kana]
+    );
+
+    // Latin names only match whole words
+    let mentioned: Vec<&str> = registry
+        .mentions("PM Suga met the press; sugar prices rose in 日本")
+        .iter()
+        .map(|e| e.id.as_str())
+        .collect();
+    assert_eq!(
+        mentioned,
+        vec![
+            "person:suga-yoshihide",
+            "office:prime-minister-jp",
+            "country:jp"
+        ]
+    );
+    assert!(registry.mentions("sugar and spice").is_empty());
+}
+
+#[test]
+fn cjk_names_do_not_match_inside_longer_words() {
+    let registry = EntityRegistry::builtin();
+    let ids = |text: &str| -> Vec<String> {
+        registry
+            .mentions(text)
+            .iter()
+            .map(|e| e.id.clone())
+            .collect()
+    };
+    // 副総理 is not the prime minister, 日本銀行 is not Japan
+    assert!(ids("麻生太郎副総理が日本銀行を訪問").is_empty());
+    assert!(ids("日本国憲法の改正").is_empty());
+    // next to another known name, an honorific or a particle it still counts
+    assert_eq!(
+        ids("石破茂首相と高市氏は日本の政治家"),
+        vec![
+            "person:ishiba-shigeru",
+            "person:takaichi-sanae",
+            "office:prime-minister-jp",
+            "country:jp"
+        ]
+    );
+}
+
+#[test]
+fn file_entries_extend_and_replace_builtin_ones() {
+    let dir = std::env::temp_dir().join(format!("tuff-entities-{}", uuid::Uuid::new_v4()));
+    let path = dir.join("entities.json");
+    // a missing file leaves the built-in registry
+    let mut registry = EntityRegistry::load(&path).unwrap();
+    assert!(registry.resolve("Koizumi").is_none());
+
+    registry.upsert(
+        Entity::new("person:koizumi-shinjiro", EntityKind::Person, "小泉進次郎")
+            .with_aliases(&["Shinjiro Koizumi", "小泉"]),
+    );
+    registry.upsert(
+        Entity::new("country:jp", EntityKind::Country, "日本").with_aliases(&["Japan", "JPN"]),
+    );
+    assert!(registry.remove("person:suga-yoshihide"));
+    registry.save(&path).unwrap();
+
+    // only the changes are written, so built-in updates still apply
+    let file: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    let ids: Vec<&str> = file["entities"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["country:jp", "person:koizumi-shinjiro"]);
+    assert_eq!(
+        file["removed"],
+        serde_json::json!(["person:suga-yoshihide"])
+    );
+
+    let loaded = EntityRegistry::load(&path).unwrap();
+    assert_eq!(loaded.canonical_name("Koizumi Shinjiro"), "小泉進次郎");
+    assert_eq!(loaded.canonical_name("JPN"), "日本");
+    // the built-in country entry was replaced, not duplicated
+    assert_eq!(loaded.canonical_name("日本国"), "日本国");
+    assert_eq!(loaded.canonical_name("Sanae Takaichi"), "高市早苗");
+    // a removed built-in entry stays removed
+    assert!(loaded.resolve("Suga").is_none());
+
+    assert!(registry.remove("person:koizumi-shinjiro"));
+    assert!(!registry.remove("person:koizumi-shinjiro"));
+    assert!(registry.resolve("小泉").is_none());
+
+    // the older form, a plain array, is still read as additions
+    std::fs::write(
+        &path,
+        r#"[{"id": "person:koizumi-shinjiro", "kind": "person", "name": "小泉進次郎", "aliases": ["小泉"]}]"#,
+    )
+    .unwrap();
+    let legacy = EntityRegistry::load(&path).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(legacy.canonical_name("小泉"), "小泉進次郎");
+    assert_eq!(legacy.canonical_name("Suga"), "菅義偉");
+}
+
+#[test]
+fn lightweight_matcher_accepts_registered_aliases() {
+    let mut meanings = HashMap::new();
+    meanings.insert("jp-pm".to_string(), "高市早苗".to_string());
+    let plain = LightweightVerifier::new(MeaningDb::new(meanings));
+    let with_entities = plain
+        .clone()
+        .with_entities(shared(EntityRegistry::builtin()));
+
+    let english = "jp-pm The prime minister is Sanae Takaichi";
+    assert_eq!(
+        plain.check_fragment(english),
+        LightweightCheckStatus::Mismatch
+    );
+    assert_eq!(
+        with_entities.check_fragment(english),
+        LightweightCheckStatus::Hit
+    );
+    assert_eq!(
+        with_entities.check_fragment("jp-pm The prime minister is Shigeru Ishiba"),
+        LightweightCheckStatus::Mismatch
+    );
+}
+
+#[tokio::test]
+async fn abstractor_tags_and_gap_states_use_canonical_names() {
+    let entities = shared(EntityRegistry::builtin());
+    let client = Arc::new(ScriptedLlmClient::new([
+        r#"{"summary": "Takaichi is PM", "tags": ["Sanae Takaichi", "Prime Minister", "Japan"]}"#,
+    ]));
+    let abstractor = LlmAbstractor::new(client).with_entities(entities.clone());
+    let abstract_ = abstractor
+        .generate(
+            "Sanae Takaichi is the prime minister of Japan",
+            &[],
+            VerificationStatus::White,
+            &IngestContext::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        abstract_.tags.tags,
+        vec!["内閣総理大臣", "日本", "高市早苗"]
+    );
+
+    // a later edit through the shared handle applies to the running resolver
+    entities.write().unwrap().upsert(
+        Entity::new("person:ishiba-shigeru", EntityKind::Person, "石破茂")
+            .with_aliases(&["Shigeru Ishiba", "Ishiba-san"]),
+    );
+    let client = Arc::new(ScriptedLlmClient::new([r#"{
+        "event_name": "高市内閣の発足",
+        "occurred_at": "2025-10-21T00:00:00Z",
+        "from": {"subject": "Japan", "predicate": "PM", "object": "Ishiba-san", "qualifiers": {}},
+        "to": {"subject": "Japan", "predicate": "PM", "object": "Sanae Takaichi", "qualifiers": {}}
+    }"#]));
+    let resolver = LlmGapResolver::new(client).with_entities(entities);
+    let evidence = Evidence::from_snippet(
+        Url::parse("https://www.kantei.go.jp/").unwrap(),
+        "第104代 高市 早苗",
+    );
+    let claim = transformer_neo::models::Claim {
+        statement: "Sanae Takaichi is prime minister".to_string(),
+        sources: Vec::new(),
+        temporal: Default::default(),
+    };
+    let transition = resolver
+        .resolve(&claim, "PM: Ishiba", &[evidence], &IngestContext::default())
+        .await
+        .unwrap()
+        .expect("transition");
+    assert_eq!(transition.from_state, "日本 内閣総理大臣: 石破茂");
+    assert_eq!(transition.to_state, "日本 内閣総理大臣: 高市早苗");
+    assert_eq!(
+        transition.to,
+        Some(StateValue::new("日本", "内閣総理大臣", "高市早苗"))
+    );
+}
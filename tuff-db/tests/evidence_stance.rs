@@ -0,0 +1,204 @@
+use std::sync::Arc;
+
+use transformer_neo::db::{OpKind, TuffEngine};
+use transformer_neo::models::{
+    Evidence, EvidenceStance, RequiredFact, SourceCategory, SourceCredibility, Stance,
+    VerificationStatus,
+};
+use transformer_neo::pipeline::{
+    ClaimVerifier, DummyAbstractGenerator, DummySplitter, FactFetcher, IngestContext,
+    IngestPipeline, LlmVerifier, ScriptedLlmClient, StanceAggregation,
+};
+use url::Url;
+
+fn evidence(url: &str, snippet: &str, trust: f32) -> Evidence {
+    let url = Url::parse(url).unwrap();
+    let domain = url.host_str().unwrap_or_default().to_string();
+    Evidence::from_snippet(url, snippet).with_credibility(SourceCredibility {
+        domain,
+        trust,
+        category: SourceCategory::Unknown,
+        bias_note: None,
+    })
+}
+
+fn stance(evidence: &Evidence, stance: Stance) -> EvidenceStance {
+    EvidenceStance {
+        evidence_id: evidence.evidence_id.clone(),
+        stance,
+        quote: None,
+    }
+}
+
+fn facts(evidence: Vec<Evidence>) -> Vec<RequiredFact> {
+    vec![RequiredFact::new("source_url", "test", evidence)]
+}
+
+#[test]
+fn aggregation_weighs_sides_by_credibility() {
+    let policy = StanceAggregation::default();
+    let gov = evidence("https://www.kantei.go.jp/a", "第104代 高市 早苗", 0.95);
+    let blog = evidence("https://example.hatenablog.com/a", "石破さんが首相", 0.25);
+    let blog2 = evidence("https://example.hatenablog.com/b", "石破さんが首相", 0.25);
+    let all = [&gov, &blog, &blog2];
+
+    let official_refutes = [
+        stance(&gov, Stance::Refutes),
+        stance(&blog, Stance::Supports),
+        stance(&blog2, Stance::Supports),
+    ];
+    // two pages from one blog count once
+    assert_eq!(
+        policy.aggregate(&official_refutes, &all),
+        VerificationStatus::Smoke
+    );
+
+    let blog_only = [stance(&blog, Stance::Supports)];
+    assert_eq!(
+        policy.aggregate(&blog_only, &all),
+        VerificationStatus::GrayWhite
+    );
+
+    let official = [
+        stance(&gov, Stance::Supports),
+        stance(&blog, Stance::Irrelevant),
+    ];
+    assert_eq!(policy.aggregate(&official, &all), VerificationStatus::White);
+
+    let irrelevant = [stance(&gov, Stance::Irrelevant)];
+    assert_eq!(
+        policy.aggregate(&irrelevant, &all),
+        VerificationStatus::GrayMid
+    );
+}
+
+#[tokio::test]
+async fn verifier_maps_stances_and_derives_status() {
+    let gov = evidence(
+        "https://www.kantei.go.jp/a",
+        "第104代 高市 早苗 令和7年10月21日",
+        0.95,
+    );
+    let blog = evidence("https://example.com/a", "石破さんが首相らしい", 0.25);
+    let client = Arc::new(ScriptedLlmClient::new([r#"{
+        "status": "WHITE",
+        "confidence": 0.9,
+        "reasoning": "blog says so",
+        "stances": [
+            {"evidence": "E1", "stance": "refutes", "quote": "第104代 高市 早苗"},
+            {"evidence": "E2", "stance": "supports", "quote": "not in the snippet"},
+            {"evidence": "E9", "stance": "supports"}
+        ]
+    }"#]));
+    let verifier = LlmVerifier::new(client.clone());
+    let result = verifier
+        .verify(
+            "石破茂は内閣総理大臣である",
+            &facts(vec![blog.clone(), gov.clone()]),
+            &IngestContext::default(),
+        )
+        .await
+        .unwrap();
+
+    // the model's WHITE is overridden by the weighted stances
+    assert_eq!(result.status, VerificationStatus::Smoke);
+    assert_eq!(result.stances.len(), 2);
+    // evidence is listed most credible first, so E1 is the government page
+    assert_eq!(result.stances[0].evidence_id, gov.evidence_id);
+    assert_eq!(result.stances[0].stance, Stance::Refutes);
+    assert_eq!(
+        result.stances[0].quote.as_deref(),
+        Some("第104代 高市 早苗")
+    );
+    assert_eq!(result.stances[1].evidence_id, blog.evidence_id);
+    assert!(result.stances[1].quote.is_none());
+
+    let user = &client.requests()[0].messages[1].content;
+    assert!(user.contains("[ID: E1] [URL: https://www.kantei.go.jp/a]"));
+}
+
+async fn verify_reply(reply: &str, evidence: Vec<Evidence>) -> VerificationStatus {
+    let client = Arc::new(ScriptedLlmClient::new([reply.to_string()]));
+    LlmVerifier::new(client)
+        .verify(
+            "石破茂は内閣総理大臣である",
+            &facts(evidence),
+            &IngestContext::default(),
+        )
+        .await
+        .unwrap()
+        .status
+}
+
+#[tokio::test]
+async fn verifier_keeps_outdated_and_stricter_model_status() {
+    let gov = evidence(
+        "https://www.kantei.go.jp/a",
+        "第102代 石破 茂 令和6年10月1日〜令和7年10月21日",
+        0.95,
+    );
+    // the stances alone would say WHITE, but the term has ended
+    let outdated = r#"{
+        "status": "OUTDATED",
+        "confidence": 0.9,
+        "reasoning": "term ended on 2025-10-21",
+        "stances": [{"evidence": "E1", "stance": "supports", "quote": "第102代 石破 茂"}]
+    }"#;
+    assert_eq!(
+        verify_reply(outdated, vec![gov.clone()]).await,
+        VerificationStatus::Outdated
+    );
+
+    // a refuting model verdict is not softened by a lone blog's support
+    let blog = evidence("https://example.com/a", "石破さんが首相らしい", 0.25);
+    let smoke = r#"{
+        "status": "SMOKE",
+        "confidence": 0.8,
+        "reasoning": "only a blog supports it",
+        "stances": [{"evidence": "E1", "stance": "supports"}]
+    }"#;
+    assert_eq!(
+        verify_reply(smoke, vec![blog]).await,
+        VerificationStatus::Smoke
+    );
+}
+
+struct StaticFetcher(Vec<RequiredFact>);
+
+#[async_trait::async_trait]
+impl FactFetcher for StaticFetcher {
+    async fn fetch(&self, _fragment: &str) -> anyhow::Result<Vec<RequiredFact>> {
+        Ok(self.0.clone())
+    }
+}
+
+#[tokio::test]
+async fn pipeline_stores_stances_with_abstract() {
+    let gov = evidence("https://www.kantei.go.jp/a", "第104代 高市 早苗", 0.95);
+    let client = Arc::new(ScriptedLlmClient::new([
+        r#"{"status": "WHITE", "confidence": 0.9, "reasoning": "ok", "stances": [{"evidence": "E1", "stance": "supports", "quote": "高市 早苗"}]}"#,
+    ]));
+    let dir = std::env::temp_dir().join(format!("tuff-stance-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wal = dir.join("wal.log");
+    let pipeline = IngestPipeline {
+        splitter: DummySplitter,
+        fetcher: StaticFetcher(facts(vec![gov.clone()])),
+        verifier: LlmVerifier::new(client),
+        generator: DummyAbstractGenerator,
+        db: TuffEngine::new(wal.to_str().unwrap()).await.unwrap(),
+        gap_resolver: None,
+        credibility: None,
+        entities: None,
+    };
+
+    let outcomes = pipeline.ingest("高市早苗は首相である").await.unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(outcomes[0].status, VerificationStatus::White);
+    let OpKind::InsertAbstract { abstract_ } = &outcomes[0].op.kind else {
+        panic!("expected InsertAbstract");
+    };
+    assert_eq!(abstract_.stances.len(), 1);
+    assert_eq!(abstract_.stances[0].evidence_id, gov.evidence_id);
+    assert_eq!(abstract_.stances[0].quote.as_deref(), Some("高市 早苗"));
+}
@@ -0,0 +1,273 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use transformer_neo::db::{OpKind, TuffEngine};
+use transformer_neo::history::compiler::compile;
+use transformer_neo::models::{
+    Abstract, AgentIdentity, Claim, EntityRegistry, Evidence, Id, IsoDateTime, RequiredFact,
+    TagBits, TagGroupId, TopicId, Transition, VerificationStatus, DUMMY_TAGS,
+};
+use transformer_neo::pipeline::traits::VerificationResult;
+use transformer_neo::pipeline::{
+    AbstractGenerator, ClaimVerifier, DummySplitter, FactFetcher, GapResolver, IngestContext,
+    IngestPipeline,
+};
+use url::Url;
+
+/// Serves the same page; `Some(hash)` reports it as changed since that hash.
+struct StaticFetcher(Option<String>);
+
+#[async_trait]
+impl FactFetcher for StaticFetcher {
+    async fn fetch(&self, _fragment: &str) -> anyhow::Result<Vec<RequiredFact>> {
+        let url = Url::parse("https://www.kantei.go.jp/").unwrap();
+        let mut evidence = Evidence::from_snippet(url.clone(), "第104代 高市 早苗");
+        evidence.source.previous_sha256_hex = self.0.clone();
+        Ok(vec![RequiredFact::new(
+            "source_url",
+            url.as_str(),
+            vec![evidence],
+        )])
+    }
+}
+
+/// Returns the queued statuses in order.
+struct QueuedVerifier(Mutex<Vec<VerificationStatus>>);
+
+impl QueuedVerifier {
+    fn new(statuses: &[VerificationStatus]) -> Self {
+        Self(Mutex::new(statuses.iter().rev().copied().collect()))
+    }
+}
+
+#[async_trait]
+impl ClaimVerifier for QueuedVerifier {
+    async fn verify(
+        &self,
+        _fragment: &str,
+        _facts: &[RequiredFact],
+        _ctx: &IngestContext,
+    ) -> anyhow::Result<VerificationResult> {
+        let status = self.0.lock().unwrap().pop().expect("no status queued");
+        Ok(VerificationResult {
+            status,
+            confidence: 0.9,
+            reason: format!("queued {:?}", status),
+            stances: Vec::new(),
+            votes: Vec::new(),
+        })
+    }
+}
+
+/// Tags every abstract with the same topic and keeps the fragment as summary.
+struct TopicGenerator(&'static [&'static str]);
+
+#[async_trait]
+impl AbstractGenerator for TopicGenerator {
+    async fn generate(
+        &self,
+        fragment: &str,
+        _facts: &[RequiredFact],
+        status: VerificationStatus,
+        _ctx: &IngestContext,
+    ) -> anyhow::Result<Abstract> {
+        let mut abstract_ = Abstract::new(
+            TopicId::new(),
+            TagGroupId::new(),
+            TagBits {
+                tags: self.0.iter().map(|t| t.to_string()).collect(),
+            },
+        );
+        abstract_.summary = fragment.to_string();
+        abstract_.verification = status;
+        Ok(abstract_)
+    }
+}
+
+const PM_TOPIC: &[&str] = &["内閣総理大臣"];
+
+/// Records the internal state it was asked about.
+#[derive(Default)]
+struct RecordingResolver(Mutex<Vec<String>>);
+
+#[async_trait]
+impl GapResolver for RecordingResolver {
+    async fn resolve(
+        &self,
+        claim: &Claim,
+        internal_state: &str,
+        external_evidence: &[Evidence],
+        _ctx: &IngestContext,
+    ) -> anyhow::Result<Option<Transition>> {
+        self.0.lock().unwrap().push(internal_state.to_string());
+        Ok(Some(Transition {
+            transition_id: Id::new(),
+            observed_at: IsoDateTime::now(),
+            agent: AgentIdentity::current(),
+            from_state: internal_state.to_string(),
+            to_state: claim.statement.clone(),
+            from: None,
+            to: None,
+            event: "首相指名選挙".to_string(),
+            occurred_at: None,
+            evidence_ids: external_evidence
+                .iter()
+                .map(|e| e.evidence_id.clone())
+                .collect(),
+            prompt_version: None,
+            abstract_id: None,
+            signature: None,
+        }))
+    }
+}
+
+struct Harness {
+    dir: std::path::PathBuf,
+    resolver: Arc<RecordingResolver>,
+    pipeline:
+        IngestPipeline<DummySplitter, StaticFetcher, QueuedVerifier, TopicGenerator, TuffEngine>,
+}
+
+async fn harness(statuses: &[VerificationStatus], with_resolver: bool) -> Harness {
+    harness_with(statuses, with_resolver, StaticFetcher(None), PM_TOPIC).await
+}
+
+async fn harness_with(
+    statuses: &[VerificationStatus],
+    with_resolver: bool,
+    fetcher: StaticFetcher,
+    tags: &'static [&'static str],
+) -> Harness {
+    let dir = std::env::temp_dir().join(format!("tuff-gap-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wal = dir.join("wal.log");
+    let resolver = Arc::new(RecordingResolver::default());
+    let pipeline = IngestPipeline {
+        splitter: DummySplitter,
+        fetcher,
+        verifier: QueuedVerifier::new(statuses),
+        generator: TopicGenerator(tags),
+        db: TuffEngine::new(wal.to_str().unwrap()).await.unwrap(),
+        gap_resolver: with_resolver.then(|| resolver.clone() as Arc<dyn GapResolver>),
+        credibility: None,
+        entities: None,
+    };
+    Harness {
+        dir,
+        resolver,
+        pipeline,
+    }
+}
+
+use VerificationStatus::*;
+
+#[tokio::test]
+async fn smoke_persists_transition_linked_to_abstract() {
+    let h = harness(&[Smoke], true).await;
+    let outcomes = h.pipeline.ingest("石破茂は首相である").await.unwrap();
+
+    let OpKind::InsertAbstract { abstract_ } = &outcomes[0].op.kind else {
+        panic!("expected InsertAbstract");
+    };
+    let op = outcomes[0]
+        .transition
+        .as_ref()
+        .expect("transition recorded");
+    let OpKind::InsertTransition { transition } = &op.kind else {
+        panic!("expected InsertTransition");
+    };
+    assert_eq!(transition.abstract_id, Some(Id(abstract_.id.0)));
+    assert_eq!(transition.evidence_ids.len(), 1);
+    // nothing stored yet, so the claim itself is the internal state
+    assert_eq!(*h.resolver.0.lock().unwrap(), vec!["石破茂は首相である"]);
+
+    // the WAL holds both ops and the timeline groups them under one topic
+    let wal = h.dir.join("wal.log");
+    let out = h.dir.join("history");
+    compile(&wal, &out, &EntityRegistry::builtin()).unwrap();
+    let timeline: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out.join("timeline.json")).unwrap()).unwrap();
+    std::fs::remove_dir_all(&h.dir).ok();
+    let timeline = timeline.as_array().unwrap();
+    assert_eq!(timeline.len(), 1);
+    let types: Vec<&str> = timeline[0]["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["type"].as_str().unwrap())
+        .collect();
+    assert_eq!(types, vec!["INGEST", "TRANSITION"]);
+}
+
+#[tokio::test]
+async fn disagreement_with_stored_state_uses_it_as_internal_state() {
+    let h = harness(&[White, White, GrayBlack], true).await;
+    let first = h.pipeline.ingest("高市早苗は首相である").await.unwrap();
+    assert!(first[0].transition.is_none());
+    // agreeing with the stored state needs no resolution
+    let second = h.pipeline.ingest("高市早苗は首相である").await.unwrap();
+    assert!(second[0].transition.is_none());
+
+    let third = h.pipeline.ingest("石破茂は首相である").await.unwrap();
+    std::fs::remove_dir_all(&h.dir).ok();
+    assert!(third[0].transition.is_some());
+    assert_eq!(*h.resolver.0.lock().unwrap(), vec!["高市早苗は首相である"]);
+}
+
+#[tokio::test]
+async fn placeholder_tags_have_no_stored_state() {
+    let h = harness_with(&[White, GrayBlack], true, StaticFetcher(None), &DUMMY_TAGS).await;
+    h.pipeline.ingest("高市早苗は首相である").await.unwrap();
+    let second = h.pipeline.ingest("東京は日本の首都である").await.unwrap();
+    std::fs::remove_dir_all(&h.dir).ok();
+    // GRAY_BLACK alone does not trigger resolution without a stored state
+    assert!(second[0].transition.is_none());
+    assert!(h.resolver.0.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn stored_state_must_share_the_claims_role() {
+    let h = harness(&[White, GrayBlack, GrayBlack], true).await;
+    h.pipeline.ingest("高市早苗は首相である").await.unwrap();
+    // same tags, different role: not the stored state of this claim
+    let second = h.pipeline.ingest("石破茂は衆議院議員である").await.unwrap();
+    assert!(second[0].transition.is_none());
+    // an alias of the same role is
+    let third = h
+        .pipeline
+        .ingest("石破茂は内閣総理大臣である")
+        .await
+        .unwrap();
+    std::fs::remove_dir_all(&h.dir).ok();
+    assert!(third[0].transition.is_some());
+    assert_eq!(*h.resolver.0.lock().unwrap(), vec!["高市早苗は首相である"]);
+}
+
+#[tokio::test]
+async fn no_resolver_means_no_transition() {
+    let h = harness(&[Smoke], false).await;
+    let outcomes = h.pipeline.ingest("石破茂は首相である").await.unwrap();
+    std::fs::remove_dir_all(&h.dir).ok();
+    assert!(outcomes[0].transition.is_none());
+    assert!(h.resolver.0.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn changed_source_of_a_known_topic_triggers_resolution() {
+    let h = harness_with(
+        &[White, White],
+        true,
+        StaticFetcher(Some("0".repeat(64))),
+        PM_TOPIC,
+    )
+    .await;
+    // first sighting: no stored state to compare the change against
+    let first = h.pipeline.ingest("高市早苗は首相である").await.unwrap();
+    assert!(first[0].transition.is_none());
+
+    // same verdict, but the page changed since the last fetch
+    let second = h.pipeline.ingest("高市早苗は首相である").await.unwrap();
+    std::fs::remove_dir_all(&h.dir).ok();
+    assert!(second[0].transition.is_some());
+    assert_eq!(*h.resolver.0.lock().unwrap(), vec!["高市早苗は首相である"]);
+}
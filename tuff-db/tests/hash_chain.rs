@@ -0,0 +1,257 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::Utc;
+use transformer_neo::db::audit::{checkpoint_path, load_checkpoints, record_hash, GENESIS_HASH};
+use transformer_neo::db::signing::{AgentSigner, KeyRegistry, SignatureCheck};
+use transformer_neo::db::{
+    verify_log, verify_log_signed, Checkpoint, LogIssue, OpKind, OpLog, TuffDb, TuffEngine,
+};
+use transformer_neo::models::{Abstract, AgentIdentity, TagBits, TagGroupId, TopicId};
+
+fn temp_wal(name: &str) -> (PathBuf, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("tuff-{}-{}", name, uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wal = dir.join("tuff.wal");
+    (dir, wal)
+}
+
+fn abstract_(tag: &str) -> Abstract {
+    Abstract::new(
+        TopicId::new(),
+        TagGroupId::new(),
+        TagBits {
+            tags: vec![tag.to_string()],
+        },
+    )
+}
+
+async fn write_log(wal: &Path, tags: &[&str]) {
+    let engine = TuffEngine::new(wal.to_str().unwrap())
+        .await
+        .unwrap()
+        .with_checkpoint_every(2);
+    for tag in tags {
+        engine.append_abstract(abstract_(tag)).await.unwrap();
+    }
+}
+
+fn edit_line(wal: &Path, edit: impl FnOnce(&mut Vec<String>)) {
+    let body = std::fs::read_to_string(wal).unwrap();
+    let mut lines: Vec<String> = body.lines().map(str::to_string).collect();
+    edit(&mut lines);
+    std::fs::write(wal, lines.join("\n") + "\n").unwrap();
+}
+
+#[tokio::test]
+async fn reopened_engine_continues_the_chain() {
+    let (dir, wal) = temp_wal("chain");
+    write_log(&wal, &["首相", "外相", "財務相"]).await;
+
+    let engine = TuffEngine::new(wal.to_str().unwrap()).await.unwrap();
+    assert_eq!(engine.chain_head().unwrap().seq, 3);
+    let op = engine.append_abstract(abstract_("防衛相")).await.unwrap();
+    assert_eq!(op.seq, Some(4));
+
+    let first: OpLog = serde_json::from_str(
+        std::fs::read_to_string(&wal)
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(first.prev_hash.as_deref(), Some(GENESIS_HASH));
+
+    let report = verify_log(&wal).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert!(report.is_intact(), "{:?}", report.first_issue);
+    assert_eq!(report.records, 4);
+    assert_eq!(report.checkpoints_checked, 1);
+    assert_eq!(report.head, engine.chain_head());
+}
+
+#[tokio::test]
+async fn reports_the_first_edited_or_deleted_record() {
+    let (dir, wal) = temp_wal("tamper");
+    write_log(&wal, &["首相", "外相", "財務相", "防衛相"]).await;
+    let original = std::fs::read_to_string(&wal).unwrap();
+
+    edit_line(&wal, |lines| {
+        lines[1] = lines[1].replace("外相", "官房長官")
+    });
+    assert_eq!(
+        verify_log(&wal).unwrap().first_issue,
+        Some(LogIssue::Tampered { line: 2, seq: 2 })
+    );
+
+    std::fs::write(&wal, &original).unwrap();
+    edit_line(&wal, |lines| {
+        lines.remove(1);
+    });
+    assert_eq!(
+        verify_log(&wal).unwrap().first_issue,
+        Some(LogIssue::Missing {
+            line: 2,
+            expected_seq: 2
+        })
+    );
+
+    // the last record has no successor; the checkpoint catches it
+    std::fs::write(&wal, &original).unwrap();
+    edit_line(&wal, |lines| {
+        lines[3] = lines[3].replace("防衛相", "官房長官")
+    });
+    assert_eq!(
+        verify_log(&wal).unwrap().first_issue,
+        Some(LogIssue::Tampered { line: 4, seq: 4 })
+    );
+
+    std::fs::write(&wal, &original).unwrap();
+    edit_line(&wal, |lines| {
+        lines.pop();
+    });
+    let report = verify_log(&wal).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(
+        report.first_issue,
+        Some(LogIssue::Missing {
+            line: 4,
+            expected_seq: 4
+        })
+    );
+}
+
+#[tokio::test]
+async fn accepts_a_legacy_prefix_only() {
+    let (dir, wal) = temp_wal("legacy");
+    let legacy = |tag: &str| OpLog {
+        op_id: uuid::Uuid::new_v4(),
+        kind: OpKind::InsertAbstract {
+            abstract_: abstract_(tag),
+        },
+        created_at: Utc::now(),
+        seq: None,
+        prev_hash: None,
+        signature: None,
+    };
+    let lines: Vec<String> = [legacy("首相"), legacy("外相")]
+        .iter()
+        .map(|op| serde_json::to_string(op).unwrap())
+        .collect();
+    std::fs::write(&wal, lines.join("\n") + "\n").unwrap();
+    write_log(&wal, &["財務相"]).await;
+
+    let report = verify_log(&wal).unwrap();
+    assert!(report.is_intact(), "{:?}", report.first_issue);
+    assert_eq!(report.legacy_records, 2);
+    // legacy records count toward the sequence
+    assert_eq!(report.head.as_ref().map(|h| h.seq), Some(3));
+
+    edit_line(&wal, |lines| {
+        lines.push(serde_json::to_string(&legacy("防衛相")).unwrap())
+    });
+    let report = verify_log(&wal).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(report.first_issue, Some(LogIssue::Unchained { line: 4 }));
+}
+
+fn write_lines<T: serde::Serialize>(path: &Path, records: &[T]) {
+    let lines: Vec<String> = records
+        .iter()
+        .map(|r| serde_json::to_string(r).unwrap())
+        .collect();
+    std::fs::write(path, lines.join("\n") + "\n").unwrap();
+}
+
+#[tokio::test]
+async fn signatures_catch_a_rehashed_forgery() {
+    let (dir, wal) = temp_wal("signed");
+    let (signer, _) = AgentSigner::generate(&AgentIdentity::current().origin).unwrap();
+    let keys = KeyRegistry::parse(&format!(
+        "{}={}\n",
+        signer.origin(),
+        signer.public_key_hex()
+    ))
+    .unwrap();
+    let engine = TuffEngine::new(wal.to_str().unwrap())
+        .await
+        .unwrap()
+        .with_checkpoint_every(2)
+        .with_signer(Arc::new(signer));
+    for tag in ["首相", "外相", "財務相", "防衛相"] {
+        engine.append_abstract(abstract_(tag)).await.unwrap();
+    }
+    let report = verify_log_signed(&wal, &keys, true).unwrap();
+    assert!(report.is_intact(), "{:?}", report.first_issue);
+    assert_eq!(report.signatures_checked, 4);
+    let checkpoints = load_checkpoints(&checkpoint_path(&wal)).unwrap();
+    assert_eq!(checkpoints.len(), 2);
+    assert!(checkpoints.iter().all(|c| c.signature.is_some()));
+
+    // a forger without the key edits record 3, drops its signature and
+    // recomputes every later hash and checkpoint
+    let original = std::fs::read_to_string(&wal).unwrap();
+    let mut ops: Vec<OpLog> = original
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    if let OpKind::InsertAbstract { abstract_ } = &mut ops[2].kind {
+        abstract_.tags.tags = vec!["偽装".to_string()];
+    }
+    ops[2].signature = None;
+    for i in 3..ops.len() {
+        ops[i].prev_hash = Some(record_hash(&ops[i - 1]).unwrap());
+    }
+    write_lines(&wal, &ops);
+    let forged: Vec<Checkpoint> = checkpoints
+        .iter()
+        .map(|c| Checkpoint {
+            head_hash: record_hash(&ops[c.seq as usize - 1]).unwrap(),
+            signature: None,
+            ..c.clone()
+        })
+        .collect();
+    write_lines(&checkpoint_path(&wal), &forged);
+
+    // the hash chain alone cannot tell
+    assert!(verify_log(&wal).unwrap().is_intact());
+    // record 4 signed the prev_hash the forger had to change
+    assert_eq!(
+        verify_log_signed(&wal, &keys, false).unwrap().first_issue,
+        Some(LogIssue::BadSignature {
+            line: 4,
+            seq: 4,
+            check: SignatureCheck::Invalid
+        })
+    );
+    // requiring signatures, the stripped record is the first failure
+    assert_eq!(
+        verify_log_signed(&wal, &keys, true).unwrap().first_issue,
+        Some(LogIssue::BadSignature {
+            line: 3,
+            seq: 3,
+            check: SignatureCheck::Unsigned
+        })
+    );
+
+    // untouched records with unsigned checkpoints
+    std::fs::write(&wal, original).unwrap();
+    let unsigned: Vec<Checkpoint> = checkpoints
+        .iter()
+        .map(|c| Checkpoint {
+            signature: None,
+            ..c.clone()
+        })
+        .collect();
+    write_lines(&checkpoint_path(&wal), &unsigned);
+    let report = verify_log_signed(&wal, &keys, true).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(
+        report.first_issue,
+        Some(LogIssue::BadCheckpoint {
+            seq: 2,
+            check: SignatureCheck::Unsigned
+        })
+    );
+}
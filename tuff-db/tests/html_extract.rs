@@ -0,0 +1,89 @@
+use transformer_neo::pipeline::extract::{extract_page, extract_tables, normalize_date};
+use url::Url;
+
+const PAGE: &str = r#"<html><head>
+<title>  歴代内閣 
+ 一覧 </title>
+<meta property="og:title" content="ignored">
+<meta property="article:published_time" content="2025-10-21T09:00:00+09:00">
+<meta name="last-modified" content="2025-10-22">
+<link rel="canonical" href="/jp/rekidai/">
+</head><body>
+<table>
+  <caption>歴代首相</caption>
+  <thead><tr><th>代</th><th>氏名</th><th>在任</th></tr></thead>
+  <tbody>
+    <tr><td>第103代</td><td>石破茂</td><td>2024年11月11日〜2025年10月21日</td></tr>
+    <tr><td>第104代</td><td>高市早苗</td><td>2025年10月21日〜</td></tr>
+    <tr><td></td><td></td><td></td></tr>
+  </tbody>
+</table>
+<table>
+  <tr><td>人口</td><td>1400万<table><tr><td>内訳</td></tr></table></td><td>注</td></tr>
+</table>
+</body></html>"#;
+
+#[test]
+fn reads_metadata_and_tables_in_one_pass() {
+    let base = Url::parse("https://www.kantei.go.jp/jp/index.html").unwrap();
+    let page = extract_page(PAGE, &base);
+
+    assert_eq!(page.metadata.title.as_deref(), Some("歴代内閣 一覧"));
+    assert_eq!(
+        page.metadata.published_at.as_deref(),
+        Some("2025-10-21T00:00:00+00:00")
+    );
+    assert_eq!(
+        page.metadata.modified_at.as_deref(),
+        Some("2025-10-22T00:00:00+00:00")
+    );
+    assert_eq!(
+        page.metadata.canonical_url.unwrap().as_str(),
+        "https://www.kantei.go.jp/jp/rekidai/"
+    );
+    assert_eq!(page.tables.len(), extract_tables(PAGE).len());
+}
+
+#[test]
+fn splits_tables_into_headers_and_rows() {
+    let tables = extract_tables(PAGE);
+    // the nested table is its own table, and the blank row is dropped
+    assert_eq!(tables.len(), 3);
+
+    let rekidai = &tables[0];
+    assert_eq!(rekidai.caption.as_deref(), Some("歴代首相"));
+    assert_eq!(rekidai.headers, vec!["代", "氏名", "在任"]);
+    assert_eq!(rekidai.rows.len(), 2);
+    assert_eq!(
+        rekidai.row_pairs(&rekidai.rows[1]),
+        vec![
+            ("代".to_string(), "第104代".to_string()),
+            ("氏名".to_string(), "高市早苗".to_string()),
+            ("在任".to_string(), "2025年10月21日〜".to_string()),
+        ]
+    );
+    assert_eq!(
+        rekidai.row_text(&rekidai.rows[0]),
+        "代=第103代 | 氏名=石破茂 | 在任=2024年11月11日〜2025年10月21日"
+    );
+
+    // without a header row the cells keep their column number
+    let population = &tables[1];
+    assert!(population.headers.is_empty());
+    assert_eq!(population.rows[0][0], "人口");
+    assert_eq!(
+        population.row_pairs(&population.rows[0])[2],
+        ("col3".to_string(), "注".to_string())
+    );
+    assert_eq!(tables[2].rows, vec![vec!["内訳".to_string()]]);
+}
+
+#[test]
+fn normalizes_known_date_formats() {
+    assert_eq!(
+        normalize_date("Tue, 21 Oct 2025 09:00:00 +0900"),
+        "2025-10-21T00:00:00+00:00"
+    );
+    assert_eq!(normalize_date("2025-10-21"), "2025-10-21T00:00:00+00:00");
+    assert_eq!(normalize_date(" 令和7年10月21日 "), "令和7年10月21日");
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use transformer_neo::pipeline::{
+    CachedResponse, FactFetcher, HttpCache, StaticSearchProvider, WebFetcher,
+};
+use url::Url;
+
+#[derive(Clone)]
+struct Page {
+    etag: Option<&'static str>,
+    body: String,
+}
+
+fn page(body: &str) -> Page {
+    Page {
+        etag: None,
+        body: body.to_string(),
+    }
+}
+
+type Routes = Arc<Mutex<HashMap<&'static str, Page>>>;
+
+/// Minimal HTTP/1.1 server whose routes can change between requests. Every
+/// raw request is appended to `requests`; a request whose `If-None-Match`
+/// equals the page's `ETag` gets `304 Not Modified`.
+async fn stub_server(routes: Routes, requests: Arc<Mutex<Vec<String>>>) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                break;
+            };
+            let routes = Arc::clone(&routes);
+            let requests = Arc::clone(&requests);
+            tokio::spawn(async move {
+                let mut buf = vec![0u8; 8192];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                requests.lock().unwrap().push(request.clone());
+                let route = routes.lock().unwrap().get(path.as_str()).cloned();
+                let (status, headers, body) = match route {
+                    Some(p) => {
+                        let not_modified = p.etag.is_some_and(|etag| {
+                            request.lines().any(|l| {
+                                l.to_ascii_lowercase() == format!("if-none-match: {}", etag)
+                            })
+                        });
+                        let headers: Vec<String> =
+                            p.etag.iter().map(|e| format!("ETag: {}", e)).collect();
+                        if not_modified {
+                            ("304 Not Modified", headers, String::new())
+                        } else {
+                            ("200 OK", headers, p.body)
+                        }
+                    }
+                    None => ("404 Not Found", Vec::new(), String::new()),
+                };
+                let mut out = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: text/html\r\nContent-Length: {}\r\n",
+                    status,
+                    body.len()
+                );
+                for header in headers {
+                    out.push_str(&format!("{}\r\n", header));
+                }
+                out.push_str("Connection: close\r\n\r\n");
+                out.push_str(&body);
+                let _ = stream.write_all(out.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            });
+        }
+    });
+    Url::parse(&format!("http://{}/", addr)).unwrap()
+}
+
+fn cached_fetcher(url: Url, ttl: chrono::Duration) -> (WebFetcher, std::path::PathBuf) {
+    let dir = std::env::temp_dir().join(format!("tuff-http-cache-{}", uuid::Uuid::new_v4()));
+    let fetcher = WebFetcher::with_provider(Arc::new(StaticSearchProvider::new(url)), 1)
+        .with_cache(HttpCache::new(&dir, ttl));
+    (fetcher, dir)
+}
+
+fn page_requests(requests: &Mutex<Vec<String>>) -> Vec<String> {
+    requests
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|r| r.starts_with("GET /page "))
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn entries_are_fresh_within_the_ttl() {
+    let fetched_at = chrono::Utc::now();
+    let entry = CachedResponse {
+        url: Url::parse("https://example.com/").unwrap(),
+        etag: None,
+        last_modified: None,
+        fetched_at,
+        sha256_hex: String::new(),
+        body: String::new(),
+    };
+    let ttl = chrono::Duration::minutes(10);
+    assert!(entry.is_fresh(ttl, fetched_at + chrono::Duration::minutes(9)));
+    assert!(!entry.is_fresh(ttl, fetched_at + chrono::Duration::minutes(10)));
+}
+
+#[tokio::test]
+async fn serves_fresh_cache_entries_without_a_request() {
+    let routes: Routes = Arc::new(Mutex::new(HashMap::from([(
+        "/page",
+        page("<p>Sanae Takaichi is the prime minister.</p>"),
+    )])));
+    let requests = Arc::default();
+    let base = stub_server(routes, Arc::clone(&requests)).await;
+    let (fetcher, dir) = cached_fetcher(base.join("page").unwrap(), chrono::Duration::hours(1));
+
+    let first = fetcher.fetch("prime minister").await.unwrap();
+    let second = fetcher.fetch("prime minister").await.unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(page_requests(&requests).len(), 1);
+    let (first, second) = (&first[0].evidence[0].source, &second[0].evidence[0].source);
+    assert_eq!(first.previous_sha256_hex, None);
+    assert_eq!(second.previous_sha256_hex.as_ref(), Some(&first.sha256_hex));
+    assert!(!second.content_changed());
+}
+
+#[tokio::test]
+async fn revalidates_stale_entries_with_etag() {
+    let mut cached = page("<p>Sanae Takaichi is the prime minister.</p>");
+    cached.etag = Some("\"v1\"");
+    let routes: Routes = Arc::new(Mutex::new(HashMap::from([("/page", cached)])));
+    let requests = Arc::default();
+    let base = stub_server(routes, Arc::clone(&requests)).await;
+    let (fetcher, dir) = cached_fetcher(base.join("page").unwrap(), chrono::Duration::zero());
+
+    fetcher.fetch("prime minister").await.unwrap();
+    let facts = fetcher.fetch("prime minister").await.unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    let sent = page_requests(&requests);
+    assert_eq!(sent.len(), 2);
+    assert!(sent[1]
+        .to_ascii_lowercase()
+        .contains("if-none-match: \"v1\""));
+    // the 304 body comes from the cache
+    let evidence = &facts[0].evidence[0];
+    assert!(evidence.snippet.contains("Takaichi"));
+    assert!(!evidence.source.content_changed());
+}
+
+#[tokio::test]
+async fn reports_a_changed_content_hash() {
+    let routes: Routes = Arc::new(Mutex::new(HashMap::from([(
+        "/page",
+        page("<p>Shigeru Ishiba is the prime minister.</p>"),
+    )])));
+    let requests = Arc::default();
+    let base = stub_server(Arc::clone(&routes), Arc::clone(&requests)).await;
+    let (fetcher, dir) = cached_fetcher(base.join("page").unwrap(), chrono::Duration::zero());
+
+    let before = fetcher.fetch("prime minister").await.unwrap();
+    routes.lock().unwrap().insert(
+        "/page",
+        page("<p>Sanae Takaichi is the prime minister.</p>"),
+    );
+    let after = fetcher.fetch("prime minister").await.unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    let (before, after) = (&before[0].evidence[0].source, &after[0].evidence[0].source);
+    assert!(!before.content_changed());
+    assert!(after.content_changed());
+    assert_eq!(after.previous_sha256_hex.as_ref(), Some(&before.sha256_hex));
+    assert_ne!(after.sha256_hex, before.sha256_hex);
+}
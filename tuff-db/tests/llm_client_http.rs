@@ -0,0 +1,154 @@
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use transformer_neo::pipeline::{AnthropicClient, LlmClient, LlmRequest, OpenAiClient};
+
+/// Answers every POST with `response` and keeps the JSON request bodies.
+async fn stub_api(response: Value) -> (String, Arc<Mutex<Vec<Value>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let bodies = Arc::new(Mutex::new(Vec::new()));
+    let recorded = bodies.clone();
+    let payload = response.to_string();
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            let mut raw = Vec::new();
+            let mut buf = [0u8; 8192];
+            // read headers, then exactly Content-Length bytes of body
+            let body = loop {
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                if n == 0 {
+                    break None;
+                }
+                raw.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&raw).to_string();
+                let Some(split) = text.find("\r\n\r\n") else {
+                    continue;
+                };
+                let length = text[..split]
+                    .lines()
+                    .find_map(|l| {
+                        let (name, value) = l.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if raw.len() >= split + 4 + length {
+                    break Some(raw[split + 4..split + 4 + length].to_vec());
+                }
+            };
+            if let Some(body) = body {
+                if let Ok(value) = serde_json::from_slice(&body) {
+                    recorded.lock().unwrap().push(value);
+                }
+            }
+            let reply = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                payload.len(),
+                payload
+            );
+            socket.write_all(reply.as_bytes()).await.ok();
+        }
+    });
+    (base, bodies)
+}
+
+fn request(max_tokens: Option<u32>) -> LlmRequest {
+    let mut request = LlmRequest::new("system prompt", "user prompt");
+    request.max_tokens = max_tokens;
+    request
+}
+
+async fn openai_stub() -> (String, Arc<Mutex<Vec<Value>>>) {
+    stub_api(json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "gpt-test",
+        "choices": [{
+            "index": 0,
+            "message": {"role": "assistant", "content": "{\"ok\": true}"},
+            "finish_reason": "stop"
+        }]
+    }))
+    .await
+}
+
+#[tokio::test]
+async fn openai_client_sends_max_tokens() {
+    let (base, bodies) = openai_stub().await;
+    // a compatible server gets the widely supported max_tokens
+    let client = OpenAiClient::with_base("test-key", "gpt-test", Some(&base));
+
+    assert_eq!(
+        client.complete(&request(Some(256))).await.unwrap(),
+        "{\"ok\": true}"
+    );
+    client.complete(&request(None)).await.unwrap();
+    client
+        .with_legacy_max_tokens(false)
+        .complete(&request(Some(128)))
+        .await
+        .unwrap();
+
+    let bodies = bodies.lock().unwrap();
+    assert_eq!(bodies[0]["model"], "gpt-test");
+    assert_eq!(bodies[0]["max_tokens"], 256);
+    assert!(bodies[0].get("max_completion_tokens").is_none());
+    assert_eq!(bodies[0]["messages"][1]["content"], "user prompt");
+    assert!(bodies[1].get("max_tokens").is_none());
+    assert_eq!(bodies[2]["max_completion_tokens"], 128);
+    assert!(bodies[2].get("max_tokens").is_none());
+}
+
+#[tokio::test]
+async fn client_defaults_apply_when_the_request_sets_none() {
+    let (base, bodies) = openai_stub().await;
+    let client = OpenAiClient::with_base("test-key", "gpt-test", Some(&base))
+        .with_max_tokens(Some(512))
+        .with_temperature(Some(0.0));
+
+    client.complete(&request(None)).await.unwrap();
+    client.complete(&request(Some(64))).await.unwrap();
+
+    let bodies = bodies.lock().unwrap();
+    assert_eq!(bodies[0]["max_tokens"], 512);
+    assert_eq!(bodies[0]["temperature"], 0.0);
+    assert_eq!(bodies[1]["max_tokens"], 64);
+}
+
+#[tokio::test]
+async fn anthropic_client_sends_max_tokens() {
+    let (base, bodies) = stub_api(json!({
+        "id": "msg_1",
+        "type": "message",
+        "role": "assistant",
+        "model": "claude-test",
+        "content": [{"type": "text", "text": "{\"ok\": true}"}],
+        "stop_reason": "end_turn"
+    }))
+    .await;
+    let client = AnthropicClient::with_base("test-key", "claude-test", Some(&base));
+
+    assert_eq!(
+        client.complete(&request(Some(256))).await.unwrap(),
+        "{\"ok\": true}"
+    );
+    client.complete(&request(None)).await.unwrap();
+    client
+        .with_max_tokens(Some(2048))
+        .complete(&request(None))
+        .await
+        .unwrap();
+    let bodies = bodies.lock().unwrap();
+    assert_eq!(bodies[0]["max_tokens"], 256);
+    assert_eq!(bodies[0]["system"], "system prompt");
+    // the Messages API requires a limit
+    assert_eq!(bodies[1]["max_tokens"], 1024);
+    assert_eq!(bodies[2]["max_tokens"], 2048);
+}
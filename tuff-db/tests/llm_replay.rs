@@ -0,0 +1,334 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use transformer_neo::models::{
+    Claim, EntityRegistry, Evidence, RequiredFact, SourceCategory, SourceCredibility, SourceMeta,
+    StateValue, VerificationStatus,
+};
+use transformer_neo::pipeline::{
+    AbstractGenerator, AsyncInputSplitter, ClaimVerifier, GapResolver, IngestContext,
+    LlmAbstractor, LlmClaimSplitter, LlmClient, LlmGapResolver, LlmVerifier, RecordingLlmClient,
+    ReplayLlmClient, ScriptedLlmClient,
+};
+use url::Url;
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/llm")
+        .join(format!("{}.json", name))
+}
+
+/// Client serving `tests/fixtures/llm/<name>.json`.
+///
+/// After a prompt change, run with `TUFF_LLM_FIXTURES=rerecord` to replay the
+/// stored responses in order and rewrite the recorded requests.
+fn replay(name: &str) -> Arc<dyn LlmClient> {
+    let path = fixture(name);
+    let client = ReplayLlmClient::from_file(&path).unwrap();
+    if env::var("TUFF_LLM_FIXTURES").as_deref() == Ok("rerecord") {
+        return Arc::new(RecordingLlmClient::new(Arc::new(client.sequential()), path));
+    }
+    Arc::new(client)
+}
+
+fn und() -> IngestContext {
+    IngestContext::default()
+}
+
+fn evidence(url: &str, snippet: &str, credibility: Option<(f32, SourceCategory)>) -> Evidence {
+    let url = Url::parse(url).unwrap();
+    let source =
+        SourceMeta::new(url.clone(), "0".repeat(64)).with_retrieved_at("2025-10-22T00:00:00+00:00");
+    let mut evidence = Evidence::new(source, snippet);
+    evidence.credibility = credibility.map(|(trust, category)| SourceCredibility {
+        domain: url.host_str().unwrap_or_default().to_string(),
+        trust,
+        category,
+        bias_note: None,
+    });
+    evidence
+}
+
+fn kantei_facts() -> Vec<RequiredFact> {
+    vec![RequiredFact::new(
+        "source_url",
+        "https://www.kantei.go.jp/jp/rekidai/index.html",
+        vec![evidence(
+            "https://www.kantei.go.jp/jp/rekidai/index.html",
+            "第102代 石破 茂 令和6年10月1日",
+            Some((0.95, SourceCategory::Government)),
+        )],
+    )]
+}
+
+fn blog_facts() -> Vec<RequiredFact> {
+    vec![RequiredFact::new(
+        "source_url",
+        "https://example.com/post",
+        vec![evidence(
+            "https://example.com/post",
+            "石破さんが首相になったらしい",
+            None,
+        )],
+    )]
+}
+
+#[tokio::test]
+async fn verifier_reads_recorded_verdict() {
+    let verifier = LlmVerifier::new(replay("verifier_white"));
+    let result = verifier
+        .verify("石破茂は第102代内閣総理大臣である", &kantei_facts(), &und())
+        .await
+        .unwrap();
+    assert_eq!(result.status, VerificationStatus::White);
+    // 0.9 from the model, scaled by one government source (0.95)
+    assert!((result.confidence - 0.855).abs() < 1e-4);
+    assert!(result.reason.contains("石破茂"));
+}
+
+#[tokio::test]
+async fn verifier_falls_back_to_gray_mid_on_prose() {
+    let verifier = LlmVerifier::new(replay("verifier_prose"));
+    let result = verifier
+        .verify("石破茂は首相である", &blog_facts(), &und())
+        .await
+        .unwrap();
+    assert_eq!(result.status, VerificationStatus::GrayMid);
+    assert!(result.reason.starts_with("Parse error"));
+    assert!((result.confidence - 0.16).abs() < 1e-4);
+}
+
+#[tokio::test]
+async fn verifier_maps_unknown_status_to_gray_mid() {
+    let verifier = LlmVerifier::new(replay("verifier_unknown_status"));
+    let result = verifier
+        .verify("石破茂は首相である", &blog_facts(), &und())
+        .await
+        .unwrap();
+    assert_eq!(result.status, VerificationStatus::GrayMid);
+    assert_eq!(result.reason, "partially supported");
+}
+
+#[tokio::test]
+async fn verifier_reads_fenced_json_after_prose() {
+    let verifier = LlmVerifier::new(replay("verifier_fenced"));
+    let result = verifier
+        .verify("石破茂は第102代内閣総理大臣である", &kantei_facts(), &und())
+        .await
+        .unwrap();
+    assert_eq!(result.status, VerificationStatus::White);
+    assert!((result.confidence - 0.76).abs() < 1e-4);
+}
+
+#[tokio::test]
+async fn verifier_accepts_answer_after_corrective_prompt() {
+    let verifier = LlmVerifier::new(replay("verifier_corrected"));
+    let result = verifier
+        .verify("石破茂は第102代内閣総理大臣である", &kantei_facts(), &und())
+        .await
+        .unwrap();
+    assert_eq!(result.status, VerificationStatus::White);
+    assert_eq!(result.reason, "再提示後に JSON で回答");
+}
+
+#[tokio::test]
+async fn verifier_reprompts_once_with_parse_error() {
+    let client = Arc::new(ScriptedLlmClient::new(["not json", "still not json"]));
+    let verifier = LlmVerifier::new(client.clone());
+    let result = verifier
+        .verify("石破茂は首相である", &kantei_facts(), &und())
+        .await
+        .unwrap();
+    assert_eq!(result.status, VerificationStatus::GrayMid);
+    assert_eq!(result.reason, "Parse error: still not json");
+
+    let requests = client.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].response_schema.is_some());
+    let retry = &requests[1].messages;
+    assert_eq!(retry.len(), 4);
+    assert_eq!(retry[2].content, "not json");
+    assert!(retry[3].content.contains("could not be parsed"));
+}
+
+#[tokio::test]
+async fn verifier_skips_llm_without_facts() {
+    let client = Arc::new(ScriptedLlmClient::default());
+    let verifier = LlmVerifier::new(client.clone());
+    let result = verifier.verify("anything", &[], &und()).await.unwrap();
+    assert_eq!(result.status, VerificationStatus::GrayMid);
+    assert!(client.requests().is_empty());
+}
+
+#[tokio::test]
+async fn abstractor_normalizes_recorded_tags() {
+    let abstractor = LlmAbstractor::new(replay("abstractor_tags"));
+    let abstract_ = abstractor
+        .generate(
+            "石破茂は第102代内閣総理大臣である",
+            &kantei_facts(),
+            VerificationStatus::White,
+            &und(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(abstract_.summary, "石破茂が第102代内閣総理大臣に就任した。");
+    assert_eq!(abstract_.tags.tags, vec!["内閣", "石破茂", "首相"]);
+    assert_eq!(abstract_.verification, VerificationStatus::White);
+    assert_eq!(abstract_.prompt_version.as_deref(), Some("default-v8"));
+}
+
+#[tokio::test]
+async fn abstractor_falls_back_to_unknown_tag_on_prose() {
+    let abstractor = LlmAbstractor::new(replay("abstractor_prose"));
+    let abstract_ = abstractor
+        .generate(
+            "石破茂は首相である",
+            &blog_facts(),
+            VerificationStatus::GrayMid,
+            &und(),
+        )
+        .await
+        .unwrap();
+    assert!(abstract_.summary.starts_with("LLM parse error"));
+    assert_eq!(abstract_.tags.tags, vec!["UNKNOWN"]);
+}
+
+fn pm_claim() -> Claim {
+    Claim {
+        statement: "高市早苗は日本の内閣総理大臣である".to_string(),
+        sources: Vec::new(),
+        temporal: Default::default(),
+    }
+}
+
+#[tokio::test]
+async fn gap_resolver_builds_transition_from_recording() {
+    let resolver = LlmGapResolver::new(replay("gap_resolver_transition"));
+    let external = vec![evidence(
+        "https://www.kantei.go.jp/jp/rekidai/index.html",
+        "第104代 高市 早苗 令和7年10月21日",
+        Some((0.95, SourceCategory::Government)),
+    )];
+    let transition = resolver
+        .resolve(&pm_claim(), "石破茂が内閣総理大臣", &external, &und())
+        .await
+        .unwrap()
+        .expect("transition");
+    assert_eq!(transition.event, "高市内閣の発足");
+    assert_eq!(transition.prompt_version.as_deref(), Some("default-v8"));
+    assert_eq!(transition.from_state, "石破茂が内閣総理大臣");
+    assert_eq!(transition.to_state, "高市早苗が内閣総理大臣");
+    // plain strings from older prompts are parsed into typed states
+    let from = transition.from.as_ref().expect("typed from");
+    assert_eq!(
+        (from.predicate.as_str(), from.object.as_str()),
+        ("内閣総理大臣", "石破茂")
+    );
+    assert_eq!(
+        transition.occurred_at.map(|t| t.0.to_rfc3339()),
+        Some("2025-10-21T00:00:00+00:00".to_string())
+    );
+    assert_eq!(
+        transition.evidence_ids,
+        vec![external[0].evidence_id.clone()]
+    );
+}
+
+#[tokio::test]
+async fn gap_resolver_reads_structured_states() {
+    let resolver = LlmGapResolver::new(replay("gap_resolver_structured"));
+    let external = vec![evidence(
+        "https://www.kantei.go.jp/jp/rekidai/index.html",
+        "第104代 高市 早苗 令和7年10月21日",
+        Some((0.95, SourceCategory::Government)),
+    )];
+    let transition = resolver
+        .resolve(&pm_claim(), "石破茂が内閣総理大臣", &external, &und())
+        .await
+        .unwrap()
+        .expect("transition");
+    let to = transition.to.as_ref().expect("typed to");
+    assert_eq!(to.subject, "日本");
+    assert_eq!(to.object, "高市 早苗");
+    assert_eq!(to.qualifiers.get("代").map(String::as_str), Some("第104代"));
+    assert_eq!(
+        transition.to_state,
+        "日本 内閣総理大臣: 高市 早苗 (代=第104代)"
+    );
+    // the typed value matches the legacy spelling of the same state
+    assert!(transition.from_value().same_as_in(
+        &StateValue::parse("Prime Minister: Shigeru Ishiba"),
+        &EntityRegistry::builtin()
+    ));
+}
+
+#[tokio::test]
+async fn gap_resolver_reads_date_only_occurred_at() {
+    let resolver = LlmGapResolver::new(replay("gap_resolver_date_only"));
+    let external = vec![evidence(
+        "https://www.kantei.go.jp/jp/rekidai/index.html",
+        "第104代 高市 早苗 令和7年10月21日",
+        Some((0.95, SourceCategory::Government)),
+    )];
+    let transition = resolver
+        .resolve(&pm_claim(), "石破茂が内閣総理大臣", &external, &und())
+        .await
+        .unwrap()
+        .expect("transition");
+    // a bare date is midnight UTC
+    assert_eq!(
+        transition.occurred_at.map(|t| t.0.to_rfc3339()),
+        Some("2025-10-21T00:00:00+00:00".to_string())
+    );
+}
+
+#[tokio::test]
+async fn gap_resolver_returns_none_on_prose() {
+    let resolver = LlmGapResolver::new(replay("gap_resolver_prose"));
+    let external = vec![evidence("https://example.com/news", "選挙があった", None)];
+    let transition = resolver
+        .resolve(&pm_claim(), "石破茂が内閣総理大臣", &external, &und())
+        .await
+        .unwrap();
+    assert!(transition.is_none());
+}
+
+#[tokio::test]
+async fn gap_resolver_skips_llm_without_evidence() {
+    let client = Arc::new(ScriptedLlmClient::default());
+    let resolver = LlmGapResolver::new(client.clone());
+    let transition = resolver
+        .resolve(&pm_claim(), "石破茂が内閣総理大臣", &[], &und())
+        .await
+        .unwrap();
+    assert!(transition.is_none());
+    assert!(client.requests().is_empty());
+}
+
+#[tokio::test]
+async fn splitter_dedups_recorded_claims() {
+    let splitter = LlmClaimSplitter::new(replay("splitter_claims"));
+    let claims = splitter
+        .split("彼女は今の首相だ。東京は日本の首都。", &und())
+        .await
+        .unwrap();
+    assert_eq!(
+        claims,
+        vec![
+            "高市早苗は日本の内閣総理大臣である",
+            "東京は日本の首都である"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn splitter_falls_back_to_lines_on_prose() {
+    let splitter = LlmClaimSplitter::new(replay("splitter_prose"));
+    let claims = splitter
+        .split("高市早苗は首相\n東京は首都\n", &und())
+        .await
+        .unwrap();
+    assert_eq!(claims, vec!["高市早苗は首相", "東京は首都"]);
+}
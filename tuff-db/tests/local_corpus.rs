@@ -0,0 +1,106 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use transformer_neo::pipeline::passage::split_passages;
+use transformer_neo::pipeline::{
+    Bm25Index, Bm25Ranker, FactFetcher, KeywordOverlapRanker, LocalCorpusFetcher, PassageRanker,
+};
+
+fn corpus() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tuff-corpus-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    std::fs::write(
+        dir.join("kantei.html"),
+        "<html><head><title>首相官邸</title>\
+         <meta property=\"article:published_time\" content=\"2025-10-21\"></head>\
+         <body><p>第104代内閣総理大臣は高市早苗。</p></body></html>",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("nested/tokyo.md"),
+        "# 東京\n\n東京は日本の首都。\n\n東京の人口は約1400万人。",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("cabinet.json"),
+        r#"{"cabinet": {"pm": "高市早苗", "ministers": [{"name": "林芳正"}]}}"#,
+    )
+    .unwrap();
+    std::fs::write(dir.join(".hidden.txt"), "高市早苗").unwrap();
+    std::fs::write(dir.join("image.png"), [0u8, 1, 2]).unwrap();
+    dir
+}
+
+#[test]
+fn index_scores_match_the_ranker() {
+    let passages = split_passages(
+        "東京の天気は晴れ。\n\n首相官邸の所在地は東京都千代田区。\n\n首相は高市早苗。",
+        400,
+    );
+    let index = Bm25Index::build(Bm25Ranker::default(), &passages);
+    assert_eq!(index.len(), 3);
+    for query in ["高市首相", "東京", "火星"] {
+        assert_eq!(
+            index.score(query),
+            Bm25Ranker::default().score(query, &passages)
+        );
+    }
+    let empty = Bm25Index::build(Bm25Ranker::default(), &[]);
+    assert!(empty.is_empty());
+    assert!(empty.score("東京").is_empty());
+}
+
+#[tokio::test]
+async fn indexes_supported_files_once() {
+    let dir = corpus();
+    let fetcher = LocalCorpusFetcher::from_dir(&dir).unwrap();
+    // hidden files and unsupported extensions are skipped
+    assert_eq!(fetcher.doc_count(), 3);
+    assert!(fetcher.passage_count() >= 3);
+
+    let facts = fetcher.fetch("高市早苗は首相").await.unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    let urls: Vec<&str> = facts.iter().map(|f| f.value.as_str()).collect();
+    assert_eq!(urls.len(), 2);
+    assert!(urls.iter().all(|u| u.starts_with("file://")));
+    assert!(facts.iter().all(|f| f.key == "source_url"));
+
+    let html = facts
+        .iter()
+        .find(|f| f.value.ends_with("kantei.html"))
+        .unwrap();
+    let evidence = &html.evidence[0];
+    assert_eq!(evidence.source.title.as_deref(), Some("首相官邸"));
+    assert_eq!(
+        evidence.source.published_at.as_deref(),
+        Some("2025-10-21T00:00:00+00:00")
+    );
+    assert!(evidence.snippet.contains("高市早苗"));
+    assert!(evidence.span.is_some());
+
+    // JSON is flattened into `path: value` lines
+    let json = facts
+        .iter()
+        .find(|f| f.value.ends_with("cabinet.json"))
+        .unwrap();
+    assert!(json.evidence[0].snippet.contains("cabinet.pm: 高市早苗"));
+}
+
+#[tokio::test]
+async fn ranks_passages_with_index_or_custom_ranker() {
+    let dir = corpus();
+    let fetcher = LocalCorpusFetcher::from_dir(&dir).unwrap().with_top_k(1);
+    let facts = fetcher.fetch("東京の人口").await.unwrap();
+    assert_eq!(facts.len(), 1);
+    assert!(facts[0].value.ends_with("tokyo.md"));
+    assert_eq!(facts[0].evidence[0].snippet, "東京の人口は約1400万人。");
+    assert!(fetcher.fetch("火星").await.unwrap().is_empty());
+
+    let keyword = LocalCorpusFetcher::from_dir(&dir)
+        .unwrap()
+        .with_ranker(Arc::new(KeywordOverlapRanker), 5);
+    let facts = keyword.fetch("林芳正").await.unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(facts.len(), 1);
+    assert!(facts[0].value.ends_with("cabinet.json"));
+}
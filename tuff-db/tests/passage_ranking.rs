@@ -0,0 +1,81 @@
+use transformer_neo::pipeline::passage::{
+    rank_passages, select_passages, split_passages, tokenize,
+};
+use transformer_neo::pipeline::{Bm25Ranker, KeywordOverlapRanker, Passage, PassageRanker};
+
+fn slice(text: &str, passage: &Passage) -> String {
+    text.chars()
+        .skip(passage.char_start)
+        .take(passage.char_end - passage.char_start)
+        .collect()
+}
+
+#[test]
+fn tokenizes_cjk_runs_as_bigrams() {
+    assert_eq!(
+        tokenize("高市早苗はPM、東京Tower 2025年"),
+        vec!["高市", "市早", "早苗", "苗は", "pm", "東京", "tower", "2025", "年"]
+    );
+    assert_eq!(tokenize("の"), vec!["の"]);
+    assert!(tokenize("、。 !?").is_empty());
+}
+
+#[test]
+fn passage_offsets_are_char_offsets_into_the_text() {
+    let text =
+        "  内閣総理大臣の一覧。\n\n第104代 高市早苗。就任は2025年10月21日。\n\nAbout this page.";
+    let passages = split_passages(text, 400);
+    let texts: Vec<&str> = passages.iter().map(|p| p.text.as_str()).collect();
+    assert_eq!(
+        texts,
+        vec![
+            "内閣総理大臣の一覧。",
+            "第104代 高市早苗。就任は2025年10月21日。",
+            "About this page."
+        ]
+    );
+    for passage in &passages {
+        assert_eq!(slice(text, passage), passage.text);
+    }
+    // leading whitespace is not part of the first passage
+    assert_eq!(passages[0].char_start, 2);
+
+    // long lines are cut at the last sentence end before the limit
+    let long = "一文目です。二文目です。三文目です。";
+    let cut = split_passages(long, 14);
+    let texts: Vec<&str> = cut.iter().map(|p| p.text.as_str()).collect();
+    assert_eq!(texts, vec!["一文目です。二文目です。", "三文目です。"]);
+    for passage in &cut {
+        assert_eq!(slice(long, passage), passage.text);
+    }
+}
+
+#[test]
+fn bm25_prefers_focused_passages() {
+    let passages = split_passages(
+        "東京の天気は晴れ。\n\n首相官邸の所在地は東京都千代田区。\n\n首相は高市早苗。首相官邸で会見した首相。\n\nUnrelated text.",
+        400,
+    );
+    let ranker = Bm25Ranker::default();
+    assert_eq!(rank_passages(&ranker, "高市首相", &passages, 3), vec![2, 1]);
+    // same term frequency: the shorter passage wins
+    assert_eq!(rank_passages(&ranker, "東京", &passages, 3), vec![0, 1]);
+    assert_eq!(rank_passages(&ranker, "東京", &passages, 1), vec![0]);
+    // a passage sharing no term scores zero and is never ranked
+    let scores = ranker.score("首相", &passages);
+    assert_eq!(scores[0], 0.0);
+    assert_eq!(scores[3], 0.0);
+
+    let overlap = KeywordOverlapRanker.score("高市早苗", &passages);
+    assert_eq!(overlap[2], 1.0);
+    assert_eq!(overlap[1], 0.0);
+}
+
+#[test]
+fn selection_falls_back_to_the_leading_passage() {
+    let text = "最初の段落。\n\n二番目の段落。";
+    let picked = select_passages(&Bm25Ranker::default(), "火星", text, 2);
+    assert_eq!(picked.len(), 1);
+    assert_eq!(picked[0].text, "最初の段落。");
+    assert!(select_passages(&Bm25Ranker::default(), "段落", text, 0).is_empty());
+}
@@ -0,0 +1,179 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use transformer_neo::models::VerificationStatus;
+use transformer_neo::pipeline::{
+    AbstractGenerator, AsyncInputSplitter, ClaimVerifier, IngestContext, LlmAbstractor,
+    LlmClaimSplitter, LlmVerifier, PromptSet, ScriptedLlmClient,
+};
+
+fn prompt_dir(files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tuff-prompts-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    for (name, body) in files {
+        fs::write(dir.join(name), body).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn builtin_set_renders_variables() {
+    let prompts = PromptSet::builtin();
+    assert_eq!(prompts.version(), "default-v8");
+    let user = prompts
+        .render("verifier.user", &[("claim", "A"), ("evidence", "B")])
+        .unwrap();
+    assert_eq!(user, "CLAIM:\nA\n\nEVIDENCE:\nB");
+}
+
+#[test]
+fn missing_variable_is_an_error() {
+    let prompts = PromptSet::builtin();
+    let err = prompts
+        .render("verifier.user", &[("claim", "A")])
+        .unwrap_err();
+    assert!(err.to_string().contains("evidence"));
+    assert!(prompts.render("no.such.template", &[]).is_err());
+}
+
+#[test]
+fn directory_without_version_is_rejected() {
+    let dir = prompt_dir(&[("verifier.system.txt", "x")]);
+    assert!(PromptSet::from_dir(&dir).is_err());
+    fs::remove_dir_all(dir).ok();
+}
+
+#[tokio::test]
+async fn deployment_set_overrides_templates_and_version() {
+    let dir = prompt_dir(&[
+        ("VERSION", "ops-2025-10\n"),
+        (
+            "abstractor.system.txt",
+            "Summarize {{claim}} for the newsroom ({{status}}).\n",
+        ),
+    ]);
+    let prompts = Arc::new(PromptSet::from_dir(&dir).unwrap());
+    fs::remove_dir_all(&dir).ok();
+
+    let client = Arc::new(ScriptedLlmClient::new([
+        r#"{"summary": "ok", "tags": ["a"]}"#,
+    ]));
+    let abstractor = LlmAbstractor::new(client.clone()).with_prompts(prompts);
+    let abstract_ = abstractor
+        .generate(
+            "東京は日本の首都である",
+            &[],
+            VerificationStatus::White,
+            &IngestContext::with_locale("ja-JP"),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(abstract_.prompt_version.as_deref(), Some("ops-2025-10"));
+    let request = &client.requests()[0];
+    assert_eq!(
+        request.system(),
+        Some("Summarize 東京は日本の首都である for the newsroom (White).")
+    );
+    // templates not in the directory keep the built-in text
+    assert!(request.messages[1]
+        .content
+        .starts_with("CLAIM:\n東京は日本の首都である"));
+}
+
+#[test]
+fn locale_variant_falls_back_to_language_then_base() {
+    let prompts = PromptSet::builtin();
+    let vars = [("claim", "A"), ("evidence", "B"), ("locale", "ja-JP")];
+    let ja = prompts
+        .render_for("verifier.system", Some("ja-JP"), &vars)
+        .unwrap();
+    assert!(ja.starts_with("あなたは厳格な検証エンジンです"));
+    let en = prompts
+        .render_for("verifier.system", Some("en-US"), &vars)
+        .unwrap();
+    assert_eq!(en, prompts.render("verifier.system", &vars).unwrap());
+    let und = prompts.render_for("verifier.system", None, &vars).unwrap();
+    assert_eq!(und, en);
+}
+
+#[tokio::test]
+async fn verifier_uses_japanese_prompt_for_ja_locale() {
+    let client = Arc::new(ScriptedLlmClient::new([
+        r#"{"status": "WHITE", "confidence": 0.9, "reasoning": "一致"}"#,
+    ]));
+    let verifier = LlmVerifier::new(client.clone());
+    let facts = vec![transformer_neo::models::RequiredFact::new(
+        "k",
+        "v",
+        Vec::new(),
+    )];
+    verifier
+        .verify(
+            "東京は日本の首都である",
+            &facts,
+            &IngestContext::with_locale("ja"),
+        )
+        .await
+        .unwrap();
+    let request = &client.requests()[0];
+    assert!(request.system().unwrap().contains("reasoning は日本語で"));
+}
+
+#[tokio::test]
+async fn splitter_uses_japanese_prompt_for_ja_locale() {
+    let client = Arc::new(ScriptedLlmClient::new([
+        r#"{"claims": ["東京は日本の首都である"]}"#,
+        r#"{"claims": ["東京は日本の首都である"]}"#,
+    ]));
+    let splitter = LlmClaimSplitter::new(client.clone());
+    let input = "東京は日本の首都。";
+    splitter
+        .split(input, &IngestContext::with_locale("ja-JP"))
+        .await
+        .unwrap();
+    splitter
+        .split(input, &IngestContext::default())
+        .await
+        .unwrap();
+    let requests = client.requests();
+    assert!(requests[0].system().unwrap().contains("主張抽出器"));
+    assert!(requests[1].system().unwrap().contains("claim extractor"));
+}
+
+#[tokio::test]
+async fn abstractor_output_language_follows_config_then_locale() {
+    let client = Arc::new(ScriptedLlmClient::new([
+        r#"{"summary": "ok", "tags": ["a"]}"#,
+        r#"{"summary": "ok", "tags": ["a"]}"#,
+        r#"{"summary": "ok", "tags": ["a"]}"#,
+    ]));
+    let abstractor = LlmAbstractor::new(client.clone());
+    let fixed = LlmAbstractor::new(client.clone()).with_output_locale(Some("en".to_string()));
+    let claim = "東京は日本の首都である";
+    let status = VerificationStatus::White;
+
+    abstractor
+        .generate(claim, &[], status, &IngestContext::default())
+        .await
+        .unwrap();
+    abstractor
+        .generate(claim, &[], status, &IngestContext::with_locale("fr-FR"))
+        .await
+        .unwrap();
+    fixed
+        .generate(claim, &[], status, &IngestContext::with_locale("ja-JP"))
+        .await
+        .unwrap();
+
+    let systems: Vec<String> = client
+        .requests()
+        .iter()
+        .map(|r| r.system().unwrap().to_string())
+        .collect();
+    assert!(systems[0].ends_with("in the same language as CLAIM."));
+    assert!(systems[1].ends_with("in French."));
+    // configured output language wins, but the ja prompt variant is still used
+    assert!(systems[2].contains("English で書いて"));
+}
@@ -0,0 +1,289 @@
+use std::sync::Arc;
+
+use transformer_neo::models::{Evidence, RequiredFact, Stance, VerificationStatus};
+use transformer_neo::pipeline::{
+    extract_quantities, ClaimVerifier, IngestContext, LlmVerifier, PartialDate, PrecheckVerifier,
+    QuantityValue, RuleVerifier, ScriptedLlmClient,
+};
+use url::Url;
+
+fn facts(url: &str, snippets: &[&str]) -> Vec<RequiredFact> {
+    let evidence = snippets
+        .iter()
+        .map(|snippet| Evidence::from_snippet(Url::parse(url).unwrap(), snippet))
+        .collect();
+    vec![RequiredFact::new("source_url", url, evidence)]
+}
+
+fn date(year: i32, month: u32, day: u32) -> QuantityValue {
+    QuantityValue::Date(PartialDate {
+        year,
+        month: Some(month),
+        day: Some(day),
+    })
+}
+
+fn number(text: &str) -> (f64, Option<String>, bool) {
+    match &extract_quantities(text)[0].value {
+        QuantityValue::Number {
+            value,
+            unit,
+            approx,
+        } => (*value, unit.clone(), *approx),
+        other => panic!("not a number: {:?}", other),
+    }
+}
+
+async fn verify(
+    claim: &str,
+    facts: &[RequiredFact],
+) -> transformer_neo::pipeline::traits::VerificationResult {
+    RuleVerifier::new()
+        .verify(claim, facts, &IngestContext::default())
+        .await
+        .unwrap()
+}
+
+#[test]
+fn extracts_western_era_and_english_dates() {
+    let values: Vec<QuantityValue> = extract_quantities(
+        "2025-10-21、令和7年10月21日、二〇二五年十月二十一日、October 21, 2025、平成元年",
+    )
+    .into_iter()
+    .map(|q| q.value)
+    .collect();
+    assert_eq!(
+        values,
+        vec![
+            date(2025, 10, 21),
+            date(2025, 10, 21),
+            date(2025, 10, 21),
+            date(2025, 10, 21),
+            QuantityValue::Date(PartialDate {
+                year: 1989,
+                month: None,
+                day: None
+            }),
+        ]
+    );
+    // month and day without a year are not comparable
+    assert!(extract_quantities("10月21日に就任").is_empty());
+}
+
+#[test]
+fn extracts_numbers_with_units_and_kanji() {
+    assert_eq!(
+        number("1億2435万人"),
+        (124_350_000.0, Some("人".to_string()), false)
+    );
+    assert_eq!(
+        number("一億二千五百万人"),
+        (125_000_000.0, Some("人".to_string()), false)
+    );
+    assert_eq!(
+        number("約125 million people"),
+        (125_000_000.0, Some("人".to_string()), true)
+    );
+    assert_eq!(number("第１０２代"), (102.0, Some("代".to_string()), false));
+    assert_eq!(number("3.5%"), (3.5, Some("%".to_string()), false));
+    // kanji inside ordinary words is not a number
+    assert!(extract_quantities("統一された一般的な見解").is_empty());
+}
+
+#[tokio::test]
+async fn matching_era_date_is_white_with_quote() {
+    let facts = facts(
+        "https://www.kantei.go.jp/jp/rekidai/index.html",
+        &[
+            "第102代 石破 茂 令和6年10月1日",
+            "第104代 高市 早苗 令和7年10月21日",
+        ],
+    );
+    let result = verify(
+        "高市早苗は2025年10月21日に第104代内閣総理大臣に就任した",
+        &facts,
+    )
+    .await;
+    assert_eq!(result.status, VerificationStatus::White);
+    assert!(result
+        .reason
+        .contains("date 2025-10-21 matches 令和7年10月21日 (www.kantei.go.jp)"));
+    assert_eq!(result.stances.len(), 1);
+    assert_eq!(result.stances[0].stance, Stance::Supports);
+    assert_eq!(result.stances[0].quote.as_deref(), Some("令和7年10月21日"));
+}
+
+#[tokio::test]
+async fn wrong_date_or_ordinal_is_smoke() {
+    let facts = facts(
+        "https://www.kantei.go.jp/jp/rekidai/index.html",
+        &["第104代 高市 早苗 令和7年10月21日"],
+    );
+    let result = verify("高市早苗は2025年10月4日に就任した", &facts).await;
+    assert_eq!(result.status, VerificationStatus::Smoke);
+    assert!(result
+        .reason
+        .contains("date 2025-10-04 differs from 令和7年10月21日"));
+    assert!(result.reason.contains("17 days apart"));
+
+    // ordinals must match exactly
+    let result = verify("高市早苗は第102代首相", &facts).await;
+    assert_eq!(result.status, VerificationStatus::Smoke);
+}
+
+#[tokio::test]
+async fn numbers_compare_with_tolerance() {
+    let facts = facts(
+        "https://www.stat.go.jp/data/jinsui/",
+        &["日本の総人口は1億2435万人（2023年10月1日現在）"],
+    );
+    let result = verify("Japan's population is 125 million", &facts).await;
+    assert_eq!(result.status, VerificationStatus::White);
+
+    let result = verify("日本の人口は1億3000万人", &facts).await;
+    assert_eq!(result.status, VerificationStatus::Smoke);
+    assert!(result.reason.contains("off by 4.3%, tolerance 1.0%"));
+
+    let result = verify("日本の人口は約1億3000万人", &facts).await;
+    assert_eq!(result.status, VerificationStatus::White);
+}
+
+#[tokio::test]
+async fn abstains_without_comparable_quantities() {
+    let facts = facts("https://example.com/", &["東京は日本の首都である"]);
+    let result = verify("東京は日本の首都である", &facts).await;
+    assert_eq!(result.status, VerificationStatus::GrayMid);
+    assert_eq!(result.confidence, 0.0);
+
+    let result = verify("2025年に就任した", &facts).await;
+    assert_eq!(result.status, VerificationStatus::GrayMid);
+    assert_eq!(result.confidence, 0.0);
+}
+
+#[tokio::test]
+async fn unrelated_numbers_in_the_evidence_are_not_conflicts() {
+    // another date of the same story, next to a different predicate
+    let facts1 = facts(
+        "https://www.nhk.or.jp/news/",
+        &["高市早苗は2025年10月4日の総裁選で選出"],
+    );
+    let result = verify("高市早苗は2025年10月21日に首相に就任", &facts1).await;
+    assert_eq!(result.status, VerificationStatus::GrayMid);
+    assert_eq!(result.confidence, 0.0);
+    let result = verify("2025年10月21日に首相に就任", &facts1).await;
+    assert_eq!(result.status, VerificationStatus::GrayMid);
+
+    // ordinals of another subject
+    let facts2 = facts(
+        "https://ja.wikipedia.org/wiki/石破茂",
+        &["石破茂は第102代・第103代内閣総理大臣"],
+    );
+    let result = verify("高市早苗は第104代首相", &facts2).await;
+    assert_eq!(result.status, VerificationStatus::GrayMid);
+    let result = verify("第104代首相", &facts2).await;
+    assert_eq!(result.status, VerificationStatus::GrayMid);
+
+    // 「2回目」 counts occurrences, not how often the meeting was held
+    let facts3 = facts("https://example.com/minutes", &["会議は2回目で結論"]);
+    let result = verify("会議は3回開かれた", &facts3).await;
+    assert_eq!(result.status, VerificationStatus::GrayMid);
+
+    // the same subject and predicate with another number is still SMOKE
+    let facts4 = facts("https://example.com/minutes", &["会議は2回開かれた"]);
+    let result = verify("会議は3回開かれた", &facts4).await;
+    assert_eq!(result.status, VerificationStatus::Smoke);
+    let facts5 = facts(
+        "https://www.nhk.or.jp/news/",
+        &["高市早苗は2025年10月4日に首相に就任"],
+    );
+    let result = verify("高市早苗は2025年10月21日に首相に就任", &facts5).await;
+    assert_eq!(result.status, VerificationStatus::Smoke);
+}
+
+#[tokio::test]
+async fn off_topic_matches_do_not_hide_an_on_topic_conflict() {
+    // the equal date belongs to another event and comes first
+    let mixed = facts(
+        "https://www.nhk.or.jp/news/",
+        &[
+            "党大会は2025年10月21日に開催",
+            "高市早苗は2025年10月4日に首相に就任",
+        ],
+    );
+    let result = verify("高市早苗は2025年10月21日に首相に就任", &mixed).await;
+    assert_eq!(result.status, VerificationStatus::Smoke);
+    assert!(result
+        .reason
+        .contains("date 2025-10-21 differs from 2025年10月4日"));
+
+    // an off-topic equal value alone is not support either
+    let off_topic = facts(
+        "https://www.nhk.or.jp/news/",
+        &["党大会は2025年10月21日に開催"],
+    );
+    let result = verify("高市早苗は2025年10月21日に首相に就任", &off_topic).await;
+    assert_eq!(result.status, VerificationStatus::GrayMid);
+    assert_eq!(result.confidence, 0.0);
+}
+
+#[tokio::test]
+async fn precheck_asks_the_llm_when_numbers_are_unrelated() {
+    let facts = facts(
+        "https://www.nhk.or.jp/news/",
+        &["高市早苗は2025年10月4日の総裁選で選出され、10月21日に就任"],
+    );
+    let client = Arc::new(ScriptedLlmClient::new([
+        r#"{"status": "WHITE", "confidence": 0.9, "reasoning": "一致"}"#,
+    ]));
+    let verifier = PrecheckVerifier::new(
+        RuleVerifier::new(),
+        Box::new(LlmVerifier::new(client.clone())),
+    );
+    let result = verifier
+        .verify(
+            "高市早苗は2025年10月21日に首相に就任",
+            &facts,
+            &IngestContext::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(client.requests().len(), 1);
+    assert_eq!(result.status, VerificationStatus::White);
+    assert!(result.votes[0].abstained);
+}
+
+#[tokio::test]
+async fn precheck_skips_llm_on_deterministic_smoke() {
+    let facts = facts(
+        "https://www.kantei.go.jp/jp/rekidai/index.html",
+        &["第104代 高市 早苗 令和7年10月21日"],
+    );
+    let client = Arc::new(ScriptedLlmClient::new([
+        r#"{"status": "WHITE", "confidence": 0.9, "reasoning": "一致"}"#,
+    ]));
+    let verifier = PrecheckVerifier::new(
+        RuleVerifier::new(),
+        Box::new(LlmVerifier::new(client.clone())),
+    );
+
+    let result = verifier
+        .verify(
+            "高市早苗は2024年10月21日に就任した",
+            &facts,
+            &IngestContext::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.status, VerificationStatus::Smoke);
+    assert!(client.requests().is_empty());
+
+    let result = verifier
+        .verify("高市早苗は首相である", &facts, &IngestContext::default())
+        .await
+        .unwrap();
+    assert_eq!(result.status, VerificationStatus::White);
+    assert_eq!(client.requests().len(), 1);
+    let voters: Vec<&str> = result.votes.iter().map(|v| v.verifier.as_str()).collect();
+    assert_eq!(voters, vec!["rule", "inner"]);
+    assert!(result.votes[0].abstained);
+}
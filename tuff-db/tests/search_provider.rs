@@ -0,0 +1,95 @@
+use transformer_neo::pipeline::search::{queries_from_fragment, search_fragment};
+use transformer_neo::pipeline::{FileSearchProvider, SearchProvider, StaticSearchProvider};
+use url::Url;
+
+const INDEX: &str = "# url<TAB>keywords
+https://www.kantei.go.jp/jp/rekidai/index.html\t首相 内閣総理大臣,歴代
+https://ja.wikipedia.org/wiki/東京都\t東京 首都
+not a url\t東京
+
+https://example.com/empty
+https://www.soumu.go.jp/\t人口 統計 東京
+";
+
+#[test]
+fn parses_tsv_index_and_skips_bad_lines() {
+    let provider = FileSearchProvider::from_str_index(INDEX);
+    // the comment, the blank line and the unparsable URL are dropped
+    assert_eq!(provider.len(), 4);
+    assert!(FileSearchProvider::from_str_index("# only a comment\n").is_empty());
+
+    let dir = std::env::temp_dir().join(format!("tuff-search-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("index.tsv");
+    std::fs::write(&path, INDEX).unwrap();
+    let loaded = FileSearchProvider::from_path(&path).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(loaded.len(), 4);
+}
+
+#[tokio::test]
+async fn ranks_urls_by_matched_keyword_share() {
+    let provider = FileSearchProvider::from_str_index(INDEX);
+    let hits = provider.search("東京は日本の首都である", 5).await.unwrap();
+    let urls: Vec<&str> = hits.iter().map(|h| h.url.as_str()).collect();
+    assert_eq!(
+        urls,
+        vec![
+            "https://ja.wikipedia.org/wiki/%E6%9D%B1%E4%BA%AC%E9%83%BD",
+            "https://www.soumu.go.jp/"
+        ]
+    );
+    assert_eq!(hits[0].score, 1.0);
+    // entries without keywords never match
+    assert!(provider
+        .search("https://example.com/empty", 5)
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(provider.search("東京", 1).await.unwrap().len(), 1);
+}
+
+#[test]
+fn derives_whole_fragment_then_clause_queries() {
+    assert_eq!(
+        queries_from_fragment("高市早苗は首相であり、東京は首都である。"),
+        vec![
+            "高市早苗は首相であり、東京は首都である",
+            "高市早苗は首相であり",
+            "東京は首都である"
+        ]
+    );
+    assert_eq!(
+        queries_from_fragment("Tokyo is the capital."),
+        vec!["Tokyo is the capital"]
+    );
+    assert!(queries_from_fragment("  。 ").is_empty());
+}
+
+#[tokio::test]
+async fn fragment_search_keeps_best_score_per_url() {
+    let provider = FileSearchProvider::from_str_index(INDEX);
+    let hits = search_fragment(&provider, "歴代の首相、東京の人口統計", 5)
+        .await
+        .unwrap();
+    let ranked: Vec<(&str, f32)> = hits.iter().map(|h| (h.url.as_str(), h.score)).collect();
+    assert_eq!(
+        ranked,
+        vec![
+            ("https://www.soumu.go.jp/", 1.0),
+            ("https://www.kantei.go.jp/jp/rekidai/index.html", 2.0 / 3.0),
+            (
+                "https://ja.wikipedia.org/wiki/%E6%9D%B1%E4%BA%AC%E9%83%BD",
+                0.5
+            ),
+        ]
+    );
+
+    let url = Url::parse("https://www.kantei.go.jp/").unwrap();
+    let fixed = StaticSearchProvider::new(url.clone());
+    let hits = search_fragment(&fixed, "anything, at all", 3)
+        .await
+        .unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].url, url);
+}
@@ -0,0 +1,226 @@
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use transformer_neo::db::signing::{AgentSigner, KeyRegistry, SignatureCheck};
+use transformer_neo::db::{TuffDb, TuffEngine};
+use transformer_neo::history::compiler::compile_verified;
+use transformer_neo::models::{
+    Abstract, AgentIdentity, EntityRegistry, Id, IsoDateTime, ManualOverride, TagBits, TagGroupId,
+    TopicId, Transition,
+};
+
+fn origin() -> String {
+    AgentIdentity::current().origin
+}
+
+fn transition(from: &str, to: &str) -> Transition {
+    Transition {
+        transition_id: Id::new(),
+        observed_at: IsoDateTime(Utc.with_ymd_and_hms(2025, 10, 22, 0, 0, 0).unwrap()),
+        agent: AgentIdentity::current(),
+        from_state: from.to_string(),
+        to_state: to.to_string(),
+        from: None,
+        to: None,
+        event: "首相指名選挙".to_string(),
+        occurred_at: None,
+        evidence_ids: Vec::new(),
+        prompt_version: None,
+        abstract_id: None,
+        signature: None,
+    }
+}
+
+fn registry_for(signer: &AgentSigner) -> KeyRegistry {
+    KeyRegistry::parse(&format!(
+        "# trusted\n{}={}\n",
+        signer.origin(),
+        signer.public_key_hex()
+    ))
+    .unwrap()
+}
+
+#[test]
+fn signatures_cover_the_canonical_record() {
+    let (signer, _) = AgentSigner::generate(&origin()).unwrap();
+    let keys = registry_for(&signer);
+
+    let mut t = transition("PM: Ishiba", "PM: Takaichi");
+    assert_eq!(keys.verify(&t), SignatureCheck::Unsigned);
+    signer.sign(&mut t).unwrap();
+    assert_eq!(keys.verify(&t), SignatureCheck::Valid);
+    // the signature survives a WAL round trip
+    let reread: Transition = serde_json::from_str(&serde_json::to_string(&t).unwrap()).unwrap();
+    assert_eq!(keys.verify(&reread), SignatureCheck::Valid);
+
+    let mut edited = t.clone();
+    edited.to_state = "PM: Koizumi".to_string();
+    assert_eq!(keys.verify(&edited), SignatureCheck::Invalid);
+
+    // a record claiming another origin
+    let mut spoofed = t.clone();
+    spoofed.agent.origin = "GPT-4o".to_string();
+    assert_eq!(keys.verify(&spoofed), SignatureCheck::OriginMismatch);
+
+    // a key nobody registered
+    let (stranger, _) = AgentSigner::generate(&origin()).unwrap();
+    let mut unknown = transition("A", "B");
+    stranger.sign(&mut unknown).unwrap();
+    assert_eq!(keys.verify(&unknown), SignatureCheck::UnknownKey);
+
+    let mut override_ = ManualOverride {
+        override_id: Id::new(),
+        observed_at: IsoDateTime::now(),
+        agent: AgentIdentity::current(),
+        conversation_id: None,
+        abstract_id: None,
+        note: Some("checked by hand".to_string()),
+        signature: None,
+    };
+    signer.sign(&mut override_).unwrap();
+    assert_eq!(keys.verify(&override_), SignatureCheck::Valid);
+}
+
+#[test]
+fn key_files_are_created_once_and_registered() {
+    let dir = std::env::temp_dir().join(format!("tuff-keys-{}", uuid::Uuid::new_v4()));
+    let key_path = dir.join("agent.key");
+    let first = AgentSigner::create("Gemini", &key_path).unwrap();
+    // an existing key is never replaced
+    assert!(AgentSigner::create("Gemini", &key_path).is_err());
+    let again = AgentSigner::load("Gemini", &key_path).unwrap();
+    assert_eq!(first.public_key_hex(), again.public_key_hex());
+
+    let keys_path = dir.join("public_keys");
+    let mut keys = KeyRegistry::load(&keys_path).unwrap();
+    assert!(!keys.has_origin("Gemini"));
+    keys.register("Gemini", &first.public_key_hex()).unwrap();
+    keys.register("Gemini", &first.public_key_hex()).unwrap();
+    let body = std::fs::read_to_string(&keys_path).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(body.lines().count(), 1);
+    let parsed = KeyRegistry::parse(&body).unwrap();
+    assert!(parsed.trusts("Gemini", &first.public_key_hex()));
+    // a second key for a known origin is not trusted until registered
+    let (other, _) = AgentSigner::generate("Gemini").unwrap();
+    assert!(!parsed.trusts("Gemini", &other.public_key_hex()));
+    assert!(!parsed.trusts("Claude", &first.public_key_hex()));
+    assert!(KeyRegistry::parse("not a key line").is_err());
+}
+
+#[test]
+fn keys_are_registered_only_through_the_admin_cli() {
+    let dir = std::env::temp_dir().join(format!("tuff-keys-{}", uuid::Uuid::new_v4()));
+    let key_path = dir.join("agent.key");
+    let keys_path = dir.join("public_keys");
+    let tuff_keys = || {
+        let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_tuff_keys"));
+        command
+            .env("AI_ORIGIN", "Gemini")
+            .env("TUFF_PUBLIC_KEYS", &keys_path);
+        command
+    };
+
+    let keygen = tuff_keys().arg("keygen").arg(&key_path).output().unwrap();
+    assert!(keygen.status.success());
+    let line = String::from_utf8(keygen.stdout).unwrap();
+    let (origin, key) = line.trim().split_once('=').unwrap();
+    assert_eq!(origin, "Gemini");
+    // keygen does not trust the key by itself
+    assert!(!keys_path.exists());
+    let again = tuff_keys().arg("keygen").arg(&key_path).output().unwrap();
+    assert!(!again.status.success());
+    assert!(String::from_utf8_lossy(&again.stderr).contains("already exists"));
+
+    let register = tuff_keys()
+        .args(["register-key", origin, key])
+        .output()
+        .unwrap();
+    assert!(register.status.success());
+    let keys = KeyRegistry::load(&keys_path).unwrap();
+    let signer = AgentSigner::load("Gemini", &key_path).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert!(keys.trusts("Gemini", &signer.public_key_hex()));
+}
+
+#[tokio::test]
+async fn replay_and_compile_drop_tampered_ops() {
+    let dir = std::env::temp_dir().join(format!("tuff-signed-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wal = dir.join("wal.log");
+    let (signer, _) = AgentSigner::generate(&origin()).unwrap();
+    let keys = registry_for(&signer);
+
+    let engine = TuffEngine::new(wal.to_str().unwrap())
+        .await
+        .unwrap()
+        .with_signer(Arc::new(signer));
+    let tags = TagBits {
+        tags: vec!["内閣総理大臣".to_string()],
+    };
+    let abstract_ = Abstract::new(TopicId::new(), TagGroupId::new(), tags.clone());
+    let abstract_id = Id(abstract_.id.0);
+    engine.append_abstract(abstract_).await.unwrap();
+    let mut t = transition("PM: Ishiba", "PM: Takaichi");
+    t.abstract_id = Some(abstract_id);
+    let op = engine.append_transition(t).await.unwrap();
+    assert!(op.signature.is_some());
+    drop(engine);
+
+    // rewrite the recorded state after the fact, and add an unsigned op
+    let body = std::fs::read_to_string(&wal).unwrap();
+    let mut lines: Vec<String> = body.lines().map(str::to_string).collect();
+    lines[1] = lines[1].replace("PM: Takaichi", "PM: Koizumi");
+    let unsigned = TuffEngine::new(dir.join("other.log").to_str().unwrap())
+        .await
+        .unwrap()
+        .append_transition(transition("PM: Koizumi", "PM: Kono"))
+        .await
+        .unwrap();
+    lines.push(serde_json::to_string(&unsigned).unwrap());
+    std::fs::write(&wal, lines.join("\n") + "\n").unwrap();
+
+    let engine = TuffEngine::new(wal.to_str().unwrap())
+        .await
+        .unwrap()
+        .with_keys(keys.clone(), false);
+    let report = engine.replay().await.unwrap();
+    assert_eq!(report.applied, 2);
+    assert_eq!(report.rejected.len(), 1);
+    assert_eq!(report.rejected[0].line, 2);
+    assert_eq!(report.rejected[0].check, SignatureCheck::Invalid);
+    assert_eq!(engine.current_state(&tags.to_key()).await.unwrap(), None);
+
+    let strict = TuffEngine::new(wal.to_str().unwrap())
+        .await
+        .unwrap()
+        .with_keys(keys.clone(), true);
+    let report = strict.replay().await.unwrap();
+    let checks: Vec<SignatureCheck> = report.rejected.iter().map(|r| r.check).collect();
+    assert_eq!(
+        checks,
+        vec![SignatureCheck::Invalid, SignatureCheck::Unsigned]
+    );
+
+    let out = dir.join("history");
+    compile_verified(&wal, &out, &keys, true, &EntityRegistry::builtin()).unwrap();
+    let read = |name: &str| -> serde_json::Value {
+        serde_json::from_str(&std::fs::read_to_string(out.join(name)).unwrap()).unwrap()
+    };
+    let rejected = read("rejected_ops.json");
+    let timeline = read("timeline.json");
+    let chains = read("chains.json");
+    std::fs::remove_dir_all(&dir).ok();
+
+    let rejected: Vec<&str> = rejected
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["check"].as_str().unwrap())
+        .collect();
+    assert_eq!(rejected, vec!["invalid", "unsigned"]);
+    let timeline = timeline.as_array().unwrap();
+    assert_eq!(timeline.len(), 1);
+    assert_eq!(timeline[0]["events"][0]["signature"], "valid");
+    assert!(chains.as_array().unwrap().is_empty());
+}
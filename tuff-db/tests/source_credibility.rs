@@ -0,0 +1,181 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use transformer_neo::db::TuffEngine;
+use transformer_neo::models::{
+    Evidence, RequiredFact, SourceCategory, SourceCredibility, VerificationStatus,
+};
+use transformer_neo::pipeline::credibility::{evidence_weight, DEFAULT_TRUST};
+use transformer_neo::pipeline::traits::VerificationResult;
+use transformer_neo::pipeline::{
+    ClaimVerifier, CredibilityRegistry, DummyAbstractGenerator, DummySplitter, FactFetcher,
+    IngestContext, IngestPipeline,
+};
+use url::Url;
+
+fn url(raw: &str) -> Url {
+    Url::parse(raw).unwrap()
+}
+
+fn evidence_from(raw: &str) -> Evidence {
+    Evidence::from_snippet(url(raw), "高市早苗は第104代首相")
+}
+
+#[test]
+fn lookup_walks_up_to_the_most_specific_entry() {
+    let registry = CredibilityRegistry::builtin();
+
+    let kantei = registry.lookup(&url("https://www.kantei.go.jp/jp/rekidai/"));
+    assert_eq!(kantei.domain, "kantei.go.jp");
+    assert_eq!(kantei.trust, 0.95);
+    assert_eq!(kantei.category, SourceCategory::Government);
+
+    let soumu = registry.lookup(&url("https://www.soumu.go.jp/"));
+    assert_eq!((soumu.domain.as_str(), soumu.trust), ("go.jp", 0.9));
+
+    let wiki = registry.lookup(&url("https://ja.wikipedia.org/wiki/首相"));
+    assert_eq!(wiki.category, SourceCategory::Reference);
+    assert!(wiki.bias_note.is_some());
+
+    let unknown = registry.lookup(&url("https://WWW.Example.COM./a"));
+    assert_eq!(unknown.domain, "example.com");
+    assert_eq!(unknown.trust, DEFAULT_TRUST);
+    assert_eq!(unknown.category, SourceCategory::Unknown);
+
+    // local corpus files count as separate local sources
+    let file = registry.lookup(&url("file:///corpus/a.md"));
+    assert_eq!(file.category, SourceCategory::Local);
+    assert_eq!(file.domain, "file:///corpus/a.md");
+}
+
+#[test]
+fn loaded_entries_override_builtins() {
+    let mut registry = CredibilityRegistry::builtin();
+    registry
+        .load_str(
+            "# domain\ttrust\tcategory\tnote\n\
+             \n\
+             example.com\t0.7\tnews\n\
+             www.kantei.go.jp\t1.5\tgov\tofficial\n",
+        )
+        .unwrap();
+    let example = registry.lookup(&url("https://news.example.com/"));
+    assert_eq!(
+        (example.trust, example.category),
+        (0.7, SourceCategory::News)
+    );
+    // trust is clamped, `www.` is dropped from the domain
+    let kantei = registry.lookup(&url("https://kantei.go.jp/"));
+    assert_eq!(kantei.trust, 1.0);
+    assert_eq!(kantei.bias_note.as_deref(), Some("official"));
+
+    assert!(registry.load_str("example.org\t0.5").is_err());
+    assert!(registry.load_str("example.org\thigh\tnews").is_err());
+}
+
+#[test]
+fn one_government_page_outweighs_three_blogs() {
+    let registry = CredibilityRegistry::builtin();
+    let mut gov = vec![RequiredFact::new(
+        "source_url",
+        "https://www.kantei.go.jp/",
+        vec![
+            evidence_from("https://www.kantei.go.jp/jp/a"),
+            // a second passage from the same site does not count twice
+            evidence_from("https://www.kantei.go.jp/jp/b"),
+        ],
+    )];
+    let mut blogs: Vec<RequiredFact> = [
+        "https://a.hatenablog.com/entry/1",
+        "https://note.com/b/n/1",
+        "https://c.blogspot.com/2025/10/pm.html",
+    ]
+    .iter()
+    .map(|u| RequiredFact::new("source_url", *u, vec![evidence_from(u)]))
+    .collect();
+    registry.annotate(&mut gov);
+    registry.annotate(&mut blogs);
+
+    assert!((evidence_weight(&gov) - 0.95).abs() < 1e-6);
+    assert!((evidence_weight(&blogs) - 0.75).abs() < 1e-6);
+    assert!(evidence_weight(&gov) > evidence_weight(&blogs));
+
+    // without annotation every host counts with the default trust
+    let plain = vec![RequiredFact::new(
+        "source_url",
+        "https://www.kantei.go.jp/",
+        vec![evidence_from("https://www.kantei.go.jp/jp/a")],
+    )];
+    assert_eq!(evidence_weight(&plain), DEFAULT_TRUST);
+}
+
+struct StaticFetcher(Vec<RequiredFact>);
+
+#[async_trait]
+impl FactFetcher for StaticFetcher {
+    async fn fetch(&self, _fragment: &str) -> anyhow::Result<Vec<RequiredFact>> {
+        Ok(self.0.clone())
+    }
+}
+
+#[derive(Clone, Default)]
+struct RecordingVerifier(Arc<Mutex<Vec<Option<SourceCredibility>>>>);
+
+#[async_trait]
+impl ClaimVerifier for RecordingVerifier {
+    async fn verify(
+        &self,
+        _fragment: &str,
+        facts: &[RequiredFact],
+        _ctx: &IngestContext,
+    ) -> anyhow::Result<VerificationResult> {
+        let mut seen = self.0.lock().unwrap();
+        seen.extend(
+            facts
+                .iter()
+                .flat_map(|f| &f.evidence)
+                .map(|e| e.credibility.clone()),
+        );
+        Ok(VerificationResult {
+            status: VerificationStatus::GrayMid,
+            confidence: 0.5,
+            reason: "recorded".to_string(),
+            stances: Vec::new(),
+            votes: Vec::new(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn pipeline_annotates_evidence_from_any_fetcher() {
+    let verifier = RecordingVerifier::default();
+    let dir = std::env::temp_dir().join(format!("tuff-credibility-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // evidence as a plain web or local corpus fetcher returns it
+    let fetched = vec![RequiredFact::new(
+        "source_url",
+        "https://www.kantei.go.jp/",
+        vec![
+            evidence_from("https://www.kantei.go.jp/jp/a"),
+            evidence_from("file:///corpus/cabinet.md"),
+        ],
+    )];
+    let pipeline = IngestPipeline {
+        splitter: DummySplitter,
+        fetcher: StaticFetcher(fetched),
+        verifier: verifier.clone(),
+        generator: DummyAbstractGenerator,
+        db: TuffEngine::new(dir.join("wal.log").to_str().unwrap())
+            .await
+            .unwrap(),
+        gap_resolver: None,
+        credibility: Some(CredibilityRegistry::builtin()),
+        entities: None,
+    };
+    pipeline.ingest("高市早苗は首相である").await.unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+
+    let seen = verifier.0.lock().unwrap();
+    let trust: Vec<f32> = seen.iter().map(|c| c.as_ref().unwrap().trust).collect();
+    assert_eq!(trust, vec![0.95, 0.7]);
+}
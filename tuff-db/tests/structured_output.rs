@@ -0,0 +1,24 @@
+use transformer_neo::pipeline::extract_json;
+
+#[test]
+fn extracts_bare_object() {
+    assert_eq!(extract_json(r#"  {"a": 1} "#), Some(r#"{"a": 1}"#));
+}
+
+#[test]
+fn extracts_fenced_block() {
+    let raw = "Here you go:\n```json\n{\"status\": \"WHITE\"}\n```\nThanks!";
+    assert_eq!(extract_json(raw), Some(r#"{"status": "WHITE"}"#));
+}
+
+#[test]
+fn extracts_object_after_leading_prose() {
+    let raw = r#"Result {not json} -> {"claims": ["a}b", "c"]} done"#;
+    assert_eq!(extract_json(raw), Some(r#"{"claims": ["a}b", "c"]}"#));
+}
+
+#[test]
+fn returns_none_without_json() {
+    assert_eq!(extract_json("The claim is true."), None);
+    assert_eq!(extract_json("{unterminated"), None);
+}
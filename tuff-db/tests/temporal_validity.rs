@@ -0,0 +1,253 @@
+use chrono::NaiveDate;
+use transformer_neo::db::{OpKind, TuffEngine};
+use transformer_neo::models::{
+    Abstract, Claim, Evidence, RequiredFact, SourceMeta, TagBits, TagGroupId, TemporalQualifier,
+    TemporalScope, TopicId, VerificationStatus,
+};
+use transformer_neo::pipeline::traits::VerificationResult;
+use transformer_neo::pipeline::{
+    extract_temporal, infer_validity, table_row_validity, AbstractGenerator,
+    DummyAbstractGenerator, DummySplitter, DummyVerifier, FactFetcher, IngestContext,
+    IngestPipeline, TemporalCheck,
+};
+use url::Url;
+
+fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+// a row of the kantei list of prime ministers, with the term the table fetcher reads from it
+fn fact(snippet: &str) -> RequiredFact {
+    let url = "https://www.kantei.go.jp/jp/rekidai/index.html";
+    let source = SourceMeta::new(Url::parse(url).unwrap(), "0".repeat(64))
+        .with_retrieved_at("2026-10-18T00:00:00+00:00");
+    let (from, to) = table_row_validity(snippet).unwrap_or_default();
+    RequiredFact::new("source_url", url, vec![Evidence::new(source, snippet)])
+        .with_validity(from, to)
+}
+
+fn white() -> VerificationResult {
+    VerificationResult {
+        status: VerificationStatus::White,
+        confidence: 0.9,
+        reason: "supported".to_string(),
+        stances: Vec::new(),
+        votes: Vec::new(),
+    }
+}
+
+const ISHIBA: &str = "第102代 石破 茂 令和6年10月1日〜令和7年10月21日";
+
+#[test]
+fn extracts_scope_and_period_from_claims() {
+    assert_eq!(
+        extract_temporal("石破茂は首相だった").scope,
+        TemporalScope::Past
+    );
+    assert_eq!(
+        extract_temporal("Shigeru Ishiba was prime minister").scope,
+        TemporalScope::Past
+    );
+    assert_eq!(
+        extract_temporal("現在の首相は高市早苗である").scope,
+        TemporalScope::Current
+    );
+    // "now" inside another word is not a marker
+    let plain = extract_temporal("高市早苗は首相として知られている (known)");
+    assert_eq!(plain.scope, TemporalScope::Unspecified);
+    assert_eq!(plain.from, None);
+
+    let dated = extract_temporal("2024年時点で石破茂が首相だった");
+    assert_eq!(dated.scope, TemporalScope::Past);
+    assert_eq!(dated.from, Some(day(2024, 1, 1)));
+    assert_eq!(dated.to, Some(day(2024, 12, 31)));
+}
+
+#[test]
+fn infers_validity_ranges_from_table_rows_and_terms() {
+    assert_eq!(
+        table_row_validity(ISHIBA),
+        Some((Some(day(2024, 10, 1)), Some(day(2025, 10, 21))))
+    );
+    assert_eq!(
+        table_row_validity("第104代 高市 早苗 令和7年10月21日〜現在"),
+        Some((Some(day(2025, 10, 21)), None))
+    );
+    assert_eq!(
+        infer_validity("在任期間は2024年10月から2025年10月まで"),
+        Some((Some(day(2024, 10, 1)), Some(day(2025, 10, 31))))
+    );
+    assert_eq!(
+        infer_validity("Ishiba was in office from October 1, 2024 to October 21, 2025"),
+        Some((Some(day(2024, 10, 1)), Some(day(2025, 10, 21))))
+    );
+    assert_eq!(infer_validity("東京は日本の首都である"), None);
+}
+
+#[test]
+fn statistics_periods_in_text_are_not_validity() {
+    assert_eq!(infer_validity("2020年から2023年にかけて人口が減少"), None);
+    assert_eq!(infer_validity("売上は2024年4月〜2025年3月で過去最高"), None);
+    assert_eq!(infer_validity("Prices rose 3% from 2021 to 2023"), None);
+    // a bare range in running text is not read as a term either
+    assert_eq!(infer_validity(ISHIBA), None);
+}
+
+#[test]
+fn present_claim_on_ended_fact_is_outdated() {
+    let check = TemporalCheck::at(day(2026, 10, 18));
+    let facts = [fact(ISHIBA)];
+
+    let mut result = white();
+    let assessment = check.apply("石破茂は内閣総理大臣である", &facts, &mut result);
+    assert_eq!(result.status, VerificationStatus::Outdated);
+    assert!(result
+        .reason
+        .contains("valid 2024-10-01..2025-10-21, outdated as of 2026-10-18"));
+    assert_eq!(assessment.valid_from, Some(day(2024, 10, 1)));
+    assert_eq!(assessment.valid_to, Some(day(2025, 10, 21)));
+
+    // the same evidence makes a claim about the past true
+    let mut result = white();
+    check.apply("石破茂は内閣総理大臣だった", &facts, &mut result);
+    assert_eq!(result.status, VerificationStatus::White);
+
+    // a verifier's OUTDATED for a past claim is corrected
+    let mut result = white();
+    result.status = VerificationStatus::Outdated;
+    check.apply("2025年1月時点で石破茂は首相だった", &facts, &mut result);
+    assert_eq!(result.status, VerificationStatus::White);
+
+    // supporting evidence that does not cover the claimed time
+    let mut result = white();
+    check.apply("2023年に石破茂は首相だった", &facts, &mut result);
+    assert_eq!(result.status, VerificationStatus::GrayBlack);
+    assert!(result.reason.contains("not at 2023-01-01..2023-12-31"));
+}
+
+#[test]
+fn fact_bounds_take_precedence_and_open_end_stays_current() {
+    let check = TemporalCheck::at(day(2026, 10, 18));
+    let mut takaichi = fact("高市早苗 内閣総理大臣");
+    takaichi.valid_from = Some(day(2025, 10, 21));
+
+    let mut result = white();
+    let assessment = check.apply("高市早苗は首相である", &[takaichi], &mut result);
+    assert_eq!(result.status, VerificationStatus::White);
+    assert_eq!(result.reason, "supported");
+    assert_eq!(assessment.valid_from, Some(day(2025, 10, 21)));
+    assert_eq!(assessment.valid_to, None);
+
+    // refuted claims are left to the verifier
+    let mut result = white();
+    result.status = VerificationStatus::Smoke;
+    check.apply("石破茂は首相である", &[fact(ISHIBA)], &mut result);
+    assert_eq!(result.status, VerificationStatus::Smoke);
+}
+
+struct StaticFetcher(Vec<RequiredFact>);
+
+#[async_trait::async_trait]
+impl FactFetcher for StaticFetcher {
+    async fn fetch(&self, _fragment: &str) -> anyhow::Result<Vec<RequiredFact>> {
+        Ok(self.0.clone())
+    }
+}
+
+#[tokio::test]
+async fn pipeline_reports_outdated_and_stores_validity() {
+    let dir = std::env::temp_dir().join(format!("tuff-temporal-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wal = dir.join("wal.log");
+    let pipeline = IngestPipeline {
+        splitter: DummySplitter,
+        fetcher: StaticFetcher(vec![fact(ISHIBA)]),
+        verifier: DummyVerifier,
+        generator: DummyAbstractGenerator,
+        db: TuffEngine::new(wal.to_str().unwrap()).await.unwrap(),
+        gap_resolver: None,
+        credibility: None,
+        entities: None,
+    };
+
+    let outcomes = pipeline.ingest("石破茂は首相である").await.unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(outcomes[0].status, VerificationStatus::Outdated);
+    let OpKind::InsertAbstract { abstract_ } = &outcomes[0].op.kind else {
+        panic!("expected InsertAbstract");
+    };
+    assert_eq!(abstract_.verification, VerificationStatus::Outdated);
+    assert_eq!(abstract_.valid_from, Some(day(2024, 10, 1)));
+    assert_eq!(abstract_.valid_to, Some(day(2025, 10, 21)));
+    assert_eq!(abstract_.claims[0].statement, "石破茂は首相である");
+    assert_eq!(
+        abstract_.claims[0].temporal.scope,
+        TemporalScope::Unspecified
+    );
+}
+
+struct TwoClaims;
+
+#[async_trait::async_trait]
+impl AbstractGenerator for TwoClaims {
+    async fn generate(
+        &self,
+        fragment: &str,
+        _facts: &[RequiredFact],
+        status: VerificationStatus,
+        _ctx: &IngestContext,
+    ) -> anyhow::Result<Abstract> {
+        let mut abstract_ = Abstract::new(
+            TopicId::new(),
+            TagGroupId::new(),
+            TagBits {
+                tags: vec!["石破茂".to_string()],
+            },
+        );
+        abstract_.summary = fragment.to_string();
+        abstract_.verification = status;
+        abstract_.claims = ["Shigeru Ishiba is currently prime minister", "石破茂は首相"]
+            .iter()
+            .map(|statement| Claim {
+                statement: statement.to_string(),
+                sources: Vec::new(),
+                temporal: TemporalQualifier::default(),
+            })
+            .collect();
+        Ok(abstract_)
+    }
+}
+
+#[tokio::test]
+async fn every_claim_carries_a_temporal_qualifier() {
+    let dir = std::env::temp_dir().join(format!("tuff-temporal-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let pipeline = IngestPipeline {
+        splitter: DummySplitter,
+        fetcher: StaticFetcher(vec![fact(ISHIBA)]),
+        verifier: DummyVerifier,
+        generator: TwoClaims,
+        db: TuffEngine::new(dir.join("wal.log").to_str().unwrap())
+            .await
+            .unwrap(),
+        gap_resolver: None,
+        credibility: None,
+        entities: None,
+    };
+
+    let outcomes = pipeline
+        .ingest("2025年1月時点で石破茂は首相だった")
+        .await
+        .unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    let OpKind::InsertAbstract { abstract_ } = &outcomes[0].op.kind else {
+        panic!("expected InsertAbstract");
+    };
+    // the claim's own marker wins
+    assert_eq!(abstract_.claims[0].temporal.scope, TemporalScope::Current);
+    // a claim without one inherits the fragment's
+    let inherited = &abstract_.claims[1].temporal;
+    assert_eq!(inherited.scope, TemporalScope::Past);
+    assert_eq!(inherited.from, Some(day(2025, 1, 1)));
+    assert_eq!(inherited.to, Some(day(2025, 1, 31)));
+}
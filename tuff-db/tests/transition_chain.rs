@@ -0,0 +1,324 @@
+use chrono::{TimeZone, Utc};
+use std::sync::{Arc, RwLock};
+use transformer_neo::db::{TuffDb, TuffEngine};
+use transformer_neo::history::chain::{ChainIssue, TransitionChain};
+use transformer_neo::history::compiler::compile;
+use transformer_neo::models::{
+    canonical_entity, Abstract, AgentIdentity, Entity, EntityKind, EntityRegistry, Id, IsoDateTime,
+    StateValue, TagBits, TagGroupId, TopicId, Transition,
+};
+
+fn at(year: i32, month: u32, day: u32) -> IsoDateTime {
+    IsoDateTime(Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap())
+}
+
+fn transition(from: &str, to: &str, occurred_at: Option<IsoDateTime>) -> Transition {
+    Transition {
+        transition_id: Id::new(),
+        observed_at: at(2026, 1, 1),
+        agent: AgentIdentity::current(),
+        from_state: from.to_string(),
+        to_state: to.to_string(),
+        from: None,
+        to: None,
+        event: format!("{} -> {}", from, to),
+        occurred_at,
+        evidence_ids: Vec::new(),
+        prompt_version: None,
+        abstract_id: None,
+        signature: None,
+    }
+}
+
+#[test]
+fn orders_by_occurred_at_and_reads_history() {
+    let chain = TransitionChain::build(
+        "pm",
+        vec![
+            transition("PM: Ishiba", "PM: Takaichi", Some(at(2025, 10, 21))),
+            transition("PM: Kishida", "PM: Ishiba", Some(at(2024, 10, 1))),
+            // same change reported twice, spelled differently
+            transition("pm:  ishiba", "PM: Takaichi", Some(at(2025, 10, 21))),
+        ],
+        &EntityRegistry::builtin(),
+    );
+    assert!(chain.is_consistent());
+    assert_eq!(chain.transitions.len(), 2);
+    assert_eq!(chain.current_state(), Some("PM: Takaichi"));
+
+    let history = chain.history();
+    let states: Vec<&str> = history.iter().map(|s| s.state.as_str()).collect();
+    assert_eq!(states, vec!["PM: Kishida", "PM: Ishiba", "PM: Takaichi"]);
+    assert_eq!(history[0].since, None);
+    assert_eq!(history[0].until, Some(at(2024, 10, 1)));
+    assert_eq!(history[1].since, Some(at(2024, 10, 1)));
+    assert_eq!(history[1].until, Some(at(2025, 10, 21)));
+    assert_eq!(history[2].until, None);
+    assert_eq!(
+        history[2].entered_by,
+        Some(chain.transitions[1].transition_id.clone())
+    );
+}
+
+#[test]
+fn flags_gaps_conflicts_and_undated_links() {
+    let kishida = transition("PM: Suga", "PM: Kishida", Some(at(2021, 10, 4)));
+    let takaichi = transition("PM: Ishiba", "PM: Takaichi", Some(at(2025, 10, 21)));
+    let koizumi = transition("PM: Ishiba", "PM: Koizumi", Some(at(2025, 10, 21)));
+    let undated = transition("PM: Takaichi", "PM: Takaichi (2nd)", None);
+    let chain = TransitionChain::build(
+        "pm",
+        vec![
+            undated.clone(),
+            takaichi.clone(),
+            koizumi.clone(),
+            kishida.clone(),
+        ],
+        &EntityRegistry::builtin(),
+    );
+    assert!(!chain.is_consistent());
+    assert_eq!(
+        chain.issues,
+        vec![
+            ChainIssue::Gap {
+                transition_id: takaichi.transition_id.clone(),
+                expected_from: "PM: Kishida".to_string(),
+                found_from: "PM: Ishiba".to_string(),
+            },
+            ChainIssue::Conflict {
+                transition_id: koizumi.transition_id.clone(),
+                other_id: takaichi.transition_id.clone(),
+                from_state: "PM: Ishiba".to_string(),
+            },
+            ChainIssue::Undated {
+                transition_id: undated.transition_id.clone(),
+            },
+        ]
+    );
+    // the gap keeps the unexpected state in the history
+    let states: Vec<String> = chain.history().into_iter().map(|s| s.state).collect();
+    assert_eq!(
+        states[..4],
+        ["PM: Suga", "PM: Kishida", "PM: Ishiba", "PM: Takaichi"]
+    );
+}
+
+#[test]
+fn parses_free_text_states_in_either_word_order() {
+    let fields = |text: &str| {
+        let v = StateValue::parse(text);
+        (v.subject, v.predicate, v.object)
+    };
+    let owned = |s: &str, p: &str, o: &str| (s.to_string(), p.to_string(), o.to_string());
+
+    // holder first
+    assert_eq!(
+        fields("石破茂が内閣総理大臣"),
+        owned("", "内閣総理大臣", "石破茂")
+    );
+    assert_eq!(
+        fields("高市早苗は首相である。"),
+        owned("", "首相", "高市早苗")
+    );
+    assert_eq!(
+        fields("Shigeru Ishiba is the Prime Minister"),
+        owned("", "Prime Minister", "Shigeru Ishiba")
+    );
+    // role first, with the country as subject
+    assert_eq!(
+        fields("日本の首相は石破茂"),
+        owned("日本", "首相", "石破茂")
+    );
+    assert_eq!(
+        fields("内閣総理大臣は高市早苗です"),
+        owned("", "内閣総理大臣", "高市早苗")
+    );
+    assert!(StateValue::parse("日本の首相は石破茂").same_as_in(
+        &StateValue::parse("石破茂が内閣総理大臣"),
+        &EntityRegistry::builtin()
+    ));
+
+    // は / が inside a name does not split it
+    let mut registry = EntityRegistry::builtin();
+    registry.upsert(Entity::new("person:haga", EntityKind::Person, "芳賀はるか"));
+    let parse = |text: &str| {
+        let v = StateValue::parse_in(text, &registry);
+        (v.subject, v.predicate, v.object)
+    };
+    assert_eq!(parse("芳賀はるかは首相"), owned("", "首相", "芳賀はるか"));
+    assert_eq!(parse("首相は芳賀はるか"), owned("", "首相", "芳賀はるか"));
+    // a known holder marks the other side as the role
+    assert_eq!(parse("芳賀はるかが議長"), owned("", "議長", "芳賀はるか"));
+    assert_eq!(parse("議長は芳賀はるか"), owned("", "議長", "芳賀はるか"));
+
+    // neither side known: the whole text is the object
+    assert_eq!(
+        fields("東京は日本の首都"),
+        owned("", "", "東京は日本の首都")
+    );
+}
+
+#[test]
+fn typed_states_compare_across_scripts_and_aliases() {
+    assert_eq!(
+        canonical_entity("Shigeru Ishiba"),
+        canonical_entity("石破茂")
+    );
+    assert_eq!(
+        canonical_entity("Ishiba Shigeru"),
+        canonical_entity("石破 茂")
+    );
+    assert_eq!(
+        canonical_entity("高市氏"),
+        canonical_entity("Ｔａｋａｉｃｈｉ")
+    );
+    assert_ne!(canonical_entity("石破茂"), canonical_entity("高市早苗"));
+
+    let registry = EntityRegistry::builtin();
+    let same = |a: &StateValue, b: &StateValue| a.same_as_in(b, &registry);
+    let ja = StateValue::new("日本", "内閣総理大臣", "石破茂").with_qualifier("代", "第103代");
+    assert!(same(
+        &ja,
+        &StateValue::new("Japan", "Prime Minister", "Shigeru Ishiba")
+    ));
+    assert!(same(&ja, &StateValue::parse("PM: Ishiba")));
+    assert!(!same(
+        &ja,
+        &StateValue::new("日本", "内閣総理大臣", "高市早苗")
+    ));
+    assert!(!same(
+        &ja,
+        &StateValue::new("日本", "内閣総理大臣", "石破茂").with_qualifier("代", "第102代")
+    ));
+    assert_eq!(ja.render(), "日本 内閣総理大臣: 石破茂 (代=第103代)");
+
+    // a Japanese report continues an English one
+    let mut takaichi = transition(
+        "石破茂が内閣総理大臣",
+        "高市早苗が内閣総理大臣",
+        Some(at(2025, 10, 21)),
+    );
+    takaichi.to = Some(StateValue::new("日本", "内閣総理大臣", "高市早苗"));
+    let chain = TransitionChain::build(
+        "pm",
+        vec![
+            transition("PM: Kishida", "PM: Shigeru Ishiba", Some(at(2024, 10, 1))),
+            takaichi,
+            // the same change again, in English
+            transition(
+                "PM: Ishiba",
+                "Prime Minister: Sanae Takaichi",
+                Some(at(2025, 10, 21)),
+            ),
+        ],
+        &registry,
+    );
+    assert!(chain.is_consistent(), "{:?}", chain.issues);
+    assert_eq!(chain.transitions.len(), 2);
+    let history = chain.history();
+    assert_eq!(history.len(), 3);
+    assert_eq!(history[2].value.object, "高市早苗");
+}
+
+#[tokio::test]
+async fn engine_answers_state_queries_per_topic() {
+    let dir = std::env::temp_dir().join(format!("tuff-chain-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wal = dir.join("wal.log");
+    let engine = TuffEngine::new(wal.to_str().unwrap()).await.unwrap();
+
+    let tags = TagBits {
+        tags: vec!["内閣総理大臣".to_string(), "日本".to_string()],
+    };
+    let abstract_ = Abstract::new(TopicId::new(), TagGroupId::new(), tags.clone());
+    let abstract_id = Id(abstract_.id.0);
+    engine.append_abstract(abstract_).await.unwrap();
+    for (from, to, date) in [
+        ("PM: Kishida", "PM: Ishiba", at(2024, 10, 1)),
+        ("PM: Ishiba", "PM: Takaichi", at(2025, 10, 21)),
+    ] {
+        let mut t = transition(from, to, Some(date));
+        t.abstract_id = Some(abstract_id.clone());
+        engine.append_transition(t).await.unwrap();
+    }
+    // unlinked transitions do not leak into the topic
+    engine
+        .append_transition(transition("A", "B", Some(at(2020, 1, 1))))
+        .await
+        .unwrap();
+
+    let key = tags.to_key();
+    assert_eq!(
+        engine.current_state(&key).await.unwrap().as_deref(),
+        Some("PM: Takaichi")
+    );
+    assert_eq!(engine.state_history(&key).await.unwrap().len(), 3);
+    assert!(engine.transition_chain(&key).await.unwrap().is_consistent());
+    assert_eq!(engine.current_state("other").await.unwrap(), None);
+
+    let out = dir.join("history");
+    compile(&wal, &out, &EntityRegistry::builtin()).unwrap();
+    let chains: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out.join("chains.json")).unwrap()).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    let topic = chains
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["topic_id"] == format!("tag:{}", key))
+        .expect("topic chain");
+    assert_eq!(topic["current_state"], "PM: Takaichi");
+    assert_eq!(topic["consistent"], true);
+}
+
+#[tokio::test]
+async fn registered_aliases_join_chains_in_the_engine_and_compiler() {
+    let dir = std::env::temp_dir().join(format!("tuff-chain-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wal = dir.join("wal.log");
+    let mut registry = EntityRegistry::builtin();
+    registry.upsert(
+        Entity::new("person:koizumi-shinjiro", EntityKind::Person, "小泉進次郎")
+            .with_aliases(&["Shinjiro Koizumi", "Koizumi"]),
+    );
+    let shared = Arc::new(RwLock::new(registry.clone()));
+    let engine = TuffEngine::new(wal.to_str().unwrap())
+        .await
+        .unwrap()
+        .with_entities(shared);
+
+    let tags = TagBits {
+        tags: vec!["環境大臣".to_string()],
+    };
+    let abstract_ = Abstract::new(TopicId::new(), TagGroupId::new(), tags.clone());
+    let abstract_id = Id(abstract_.id.0);
+    engine.append_abstract(abstract_).await.unwrap();
+    for (from, to, date) in [
+        (
+            "Minister: Nishimura",
+            "Minister: 小泉進次郎",
+            at(2019, 9, 11),
+        ),
+        ("Minister: Koizumi", "Minister: Yamaguchi", at(2021, 10, 4)),
+    ] {
+        let mut t = transition(from, to, Some(date));
+        t.abstract_id = Some(abstract_id.clone());
+        engine.append_transition(t).await.unwrap();
+    }
+
+    // only the registered alias links 小泉進次郎 to Koizumi
+    let key = tags.to_key();
+    assert!(engine.transition_chain(&key).await.unwrap().is_consistent());
+    assert_eq!(engine.state_history(&key).await.unwrap().len(), 3);
+    let transitions = engine.transition_chain(&key).await.unwrap().transitions;
+    let builtin = TransitionChain::build(&key, transitions, &EntityRegistry::builtin());
+    assert!(!builtin.is_consistent());
+
+    let out = dir.join("history");
+    compile(&wal, &out, &registry).unwrap();
+    let chains: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out.join("chains.json")).unwrap()).unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert_eq!(chains[0]["consistent"], true);
+    assert_eq!(chains[0]["history"].as_array().unwrap().len(), 3);
+}
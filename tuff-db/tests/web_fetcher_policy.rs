@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use transformer_neo::pipeline::{
+    FactFetcher, FetchPolicy, FileSearchProvider, PolicyError, RobotsRules, StaticSearchProvider,
+    WebFetcher,
+};
+use url::Url;
+
+struct StubResponse {
+    status: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: String,
+}
+
+fn html(body: &str) -> StubResponse {
+    StubResponse {
+        status: "200 OK",
+        headers: vec![("Content-Type", "text/html; charset=utf-8".to_string())],
+        body: body.to_string(),
+    }
+}
+
+fn redirect(to: &str) -> StubResponse {
+    StubResponse {
+        status: "302 Found",
+        headers: vec![("Location", to.to_string())],
+        body: String::new(),
+    }
+}
+
+/// Minimal HTTP/1.1 server answering from a fixed route table (404 otherwise).
+async fn stub_server(routes: HashMap<&'static str, StubResponse>) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let routes = Arc::new(routes);
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                break;
+            };
+            let routes = Arc::clone(&routes);
+            tokio::spawn(async move {
+                let mut buf = vec![0u8; 8192];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                let (status, headers, body) = match routes.get(path.as_str()) {
+                    Some(r) => (r.status, r.headers.clone(), r.body.clone()),
+                    None => ("404 Not Found", Vec::new(), String::new()),
+                };
+                let mut out = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\n", status, body.len());
+                for (k, v) in headers {
+                    out.push_str(&format!("{}: {}\r\n", k, v));
+                }
+                out.push_str("Connection: close\r\n\r\n");
+                out.push_str(&body);
+                let _ = stream.write_all(out.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            });
+        }
+    });
+    Url::parse(&format!("http://{}/", addr)).unwrap()
+}
+
+fn fast_policy() -> FetchPolicy {
+    FetchPolicy {
+        min_interval: Duration::ZERO,
+        timeout: Duration::from_secs(5),
+        ..FetchPolicy::default()
+    }
+}
+
+fn fetcher_for(url: Url, policy: FetchPolicy) -> WebFetcher {
+    WebFetcher::with_provider(Arc::new(StaticSearchProvider::new(url)), 3).with_policy(policy)
+}
+
+fn policy_error(err: &anyhow::Error) -> &PolicyError {
+    err.downcast_ref::<PolicyError>()
+        .unwrap_or_else(|| panic!("expected PolicyError, got {err:#}"))
+}
+
+#[tokio::test]
+async fn fetches_allowed_page_as_evidence() {
+    let base = stub_server(HashMap::from([(
+        "/page",
+        html("<p>Sanae Takaichi is the prime minister of Japan.</p>"),
+    )]))
+    .await;
+    let fetcher = fetcher_for(base.join("page").unwrap(), fast_policy());
+
+    let facts = fetcher.fetch("prime minister Takaichi").await.unwrap();
+    assert_eq!(facts.len(), 1);
+    assert!(facts[0].evidence[0].snippet.contains("Takaichi"));
+}
+
+#[tokio::test]
+async fn respects_robots_disallow() {
+    let robots = StubResponse {
+        status: "200 OK",
+        headers: vec![("Content-Type", "text/plain".to_string())],
+        body: "User-agent: *\nDisallow: /private\n".to_string(),
+    };
+    let base = stub_server(HashMap::from([
+        ("/robots.txt", robots),
+        ("/private/page", html("<p>secret</p>")),
+    ]))
+    .await;
+    let url = base.join("private/page").unwrap();
+
+    let err = fetcher_for(url.clone(), fast_policy())
+        .fetch("secret")
+        .await
+        .unwrap_err();
+    assert!(matches!(policy_error(&err), PolicyError::RobotsDisallowed(u) if *u == url));
+
+    let ignoring = FetchPolicy {
+        respect_robots: false,
+        ..fast_policy()
+    };
+    assert!(fetcher_for(url, ignoring).fetch("secret").await.is_ok());
+}
+
+#[test]
+fn robots_patterns_support_wildcards_and_end_anchors() {
+    let rules = RobotsRules::parse(
+        "User-agent: *\n\
+         Disallow: /*.pdf$\n\
+         Disallow: /search*q=\n\
+         Disallow: /tmp$\n\
+         Disallow: /docs/\n\
+         Allow: /docs/*/public\n",
+        "tuff-db/0.1",
+    );
+    assert!(!rules.allows("/files/report.pdf"));
+    assert!(rules.allows("/files/report.pdf?download=1"));
+    assert!(rules.allows("/files/report.pdfx"));
+    assert!(!rules.allows("/search?lang=ja&q=首相"));
+    assert!(rules.allows("/search?lang=ja"));
+    assert!(!rules.allows("/tmp"));
+    assert!(rules.allows("/tmp/file"));
+    // the longer Allow pattern wins over the Disallow prefix
+    assert!(!rules.allows("/docs/guide/private"));
+    assert!(rules.allows("/docs/guide/public/page"));
+    assert!(rules.allows("/"));
+}
+
+#[tokio::test]
+async fn follows_redirects_up_to_the_limit() {
+    let base = stub_server(HashMap::from([
+        ("/a", redirect("/b")),
+        ("/b", redirect("/c")),
+        ("/c", html("<p>landed</p>")),
+    ]))
+    .await;
+    let url = base.join("a").unwrap();
+
+    let facts = fetcher_for(url.clone(), fast_policy())
+        .fetch("landed")
+        .await
+        .unwrap();
+    assert!(facts[0].evidence[0].snippet.contains("landed"));
+
+    let strict = FetchPolicy {
+        max_redirects: 1,
+        ..fast_policy()
+    };
+    let err = fetcher_for(url, strict).fetch("landed").await.unwrap_err();
+    assert!(matches!(
+        policy_error(&err),
+        PolicyError::TooManyRedirects { max: 1, .. }
+    ));
+}
+
+#[tokio::test]
+async fn rejects_non_html_content() {
+    let image = StubResponse {
+        status: "200 OK",
+        headers: vec![("Content-Type", "image/png".to_string())],
+        body: "PNG".to_string(),
+    };
+    let base = stub_server(HashMap::from([("/image", image)])).await;
+
+    let err = fetcher_for(base.join("image").unwrap(), fast_policy())
+        .fetch("image")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        policy_error(&err),
+        PolicyError::UnsupportedContentType { .. }
+    ));
+}
+
+#[tokio::test]
+async fn caps_response_size() {
+    let big = "<p>".to_string() + &"x".repeat(4096) + "</p>";
+    let base = stub_server(HashMap::from([("/big", html(&big))])).await;
+    let small = FetchPolicy {
+        max_body_bytes: 1024,
+        ..fast_policy()
+    };
+
+    let err = fetcher_for(base.join("big").unwrap(), small)
+        .fetch("x")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        policy_error(&err),
+        PolicyError::BodyTooLarge { max: 1024, .. }
+    ));
+}
+
+#[tokio::test]
+async fn spaces_requests_to_the_same_host() {
+    let base = stub_server(HashMap::from([
+        ("/one", html("<p>prime minister one</p>")),
+        ("/two", html("<p>prime minister two</p>")),
+    ]))
+    .await;
+    let index = format!(
+        "{}\tprime minister\n{}\tprime minister\n",
+        base.join("one").unwrap(),
+        base.join("two").unwrap()
+    );
+    let provider = Arc::new(FileSearchProvider::from_str_index(&index));
+    let policy = FetchPolicy {
+        respect_robots: false,
+        max_concurrent_per_host: 1,
+        min_interval: Duration::from_millis(300),
+        ..fast_policy()
+    };
+    let fetcher = WebFetcher::with_provider(provider, 3).with_policy(policy);
+
+    let started = Instant::now();
+    let facts = fetcher.fetch("who is the prime minister").await.unwrap();
+    assert_eq!(facts.len(), 2);
+    assert!(started.elapsed() >= Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn table_rows_become_one_fact_per_cell() {
+    let base = stub_server(HashMap::from([(
+        "/rekidai",
+        html(
+            "<p>歴代内閣総理大臣</p>\
+             <table><caption>歴代首相</caption>\
+             <tr><th>代</th><th>氏名</th></tr>\
+             <tr><td>第103代</td><td>石破茂</td></tr>\
+             <tr><td>第104代</td><td>高市早苗</td></tr></table>",
+        ),
+    )]))
+    .await;
+    let fetcher = fetcher_for(base.join("rekidai").unwrap(), fast_policy());
+
+    let facts = fetcher.fetch("高市早苗は第104代首相").await.unwrap();
+    let cells: Vec<(&str, &str, Option<&str>)> = facts
+        .iter()
+        .skip(1)
+        .map(|f| (f.key.as_str(), f.value.as_str(), f.qualifier.as_deref()))
+        .collect();
+    assert_eq!(
+        cells,
+        vec![
+            ("代", "第104代", Some("歴代首相[2]")),
+            ("氏名", "高市早苗", Some("歴代首相[2]")),
+        ]
+    );
+    // the row sharing no term with the claim is not returned; each cell keeps
+    // the whole row as its evidence
+    assert_eq!(facts[2].evidence[0].snippet, "代=第104代 | 氏名=高市早苗");
+}